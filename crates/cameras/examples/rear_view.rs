@@ -0,0 +1,94 @@
+use bevy::{prelude::*, render::camera::Viewport, window::WindowResized};
+use cameras::rear_view::{RearViewCamera, RearViewCameraPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, RearViewCameraPlugin))
+        .add_systems(Startup, setup)
+        .add_systems(Update, (drive_car, resize_mirror))
+        .run();
+}
+
+#[derive(Component)]
+struct Car;
+
+fn setup(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>, mut materials: ResMut<Assets<StandardMaterial>>) {
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(shape::Plane::from_size(50.0).into()),
+        material: materials.add(Color::DARK_GREEN.into()),
+        ..default()
+    });
+
+    for i in 0..20 {
+        let angle = i as f32 / 20.0 * std::f32::consts::TAU;
+        commands.spawn(PbrBundle {
+            mesh: meshes.add(shape::Cube::new(1.0).into()),
+            material: materials.add(Color::hsl(i as f32 * 18.0, 0.7, 0.5).into()),
+            transform: Transform::from_xyz(angle.cos() * 12.0, 0.5, angle.sin() * 12.0),
+            ..default()
+        });
+    }
+
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    let car = commands
+        .spawn((
+            Car,
+            PbrBundle {
+                mesh: meshes.add(shape::Box::new(1.0, 0.5, 2.0).into()),
+                material: materials.add(Color::RED.into()),
+                transform: Transform::from_xyz(0.0, 0.25, 8.0),
+                ..default()
+            },
+        ))
+        .id();
+
+    // main view from high above
+    commands.spawn(Camera3dBundle {
+        transform: Transform::from_xyz(0.0, 30.0, 20.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    // the viewport is sized in resize_mirror once the window size is known
+    commands.spawn((
+        RearViewCamera {
+            follow: car,
+            distance: 6.0,
+            height: 2.0,
+        },
+        RearViewCamera::pip_camera(Viewport::default()),
+    ));
+}
+
+fn drive_car(time: Res<Time>, mut query: Query<&mut Transform, With<Car>>) {
+    for mut transform in &mut query {
+        transform.rotate_around(Vec3::ZERO, Quat::from_rotation_y(time.delta_seconds() * 0.5));
+    }
+}
+
+/// keeps the mirror in the top right quarter of the window
+fn resize_mirror(
+    mut resized: EventReader<WindowResized>,
+    windows: Query<&Window>,
+    mut cameras: Query<&mut Camera, With<RearViewCamera>>,
+) {
+    if resized.read().last().is_none() {
+        return;
+    }
+
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+
+    let size = UVec2::new(window.physical_width(), window.physical_height()) / 4;
+    for mut camera in &mut cameras {
+        camera.viewport = Some(Viewport {
+            physical_position: UVec2::new(window.physical_width() - size.x, 0),
+            physical_size: size,
+            ..default()
+        });
+    }
+}
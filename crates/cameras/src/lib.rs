@@ -1,3 +1,6 @@
 pub mod flycam;
 pub mod pan_orbit;
+pub mod rear_view;
 pub mod spectator;
+#[cfg(feature = "path_recorder")]
+pub mod spectator_path;
@@ -0,0 +1,106 @@
+//! Picture-in-picture camera that trails a target, for a rear-view/3rd person preview next to the main camera.
+//!
+//! ```ignore
+//! commands.spawn((
+//!     RearViewCamera { follow: player_camera, distance: 15.0, height: 1.5 },
+//!     RearViewCamera::pip_camera(Viewport {
+//!         physical_position: UVec2::new(1536, 0),
+//!         physical_size: UVec2::new(512, 288),
+//!         ..default()
+//!     }),
+//! ));
+//! ```
+
+use bevy::{
+    core_pipeline::clear_color::ClearColorConfig, prelude::*, render::camera::Viewport, transform::TransformSystem,
+};
+
+pub struct RearViewCameraPlugin;
+
+impl Plugin for RearViewCameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            follow_rear_view_targets.after(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+/// Keeps the camera `distance` behind and `height` above `follow`, facing the same way as the target. The camera
+/// entity must not have a parent since its `Transform` is set in world space.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct RearViewCamera {
+    pub follow: Entity,
+    pub distance: f32,
+    pub height: f32,
+}
+
+impl RearViewCamera {
+    /// camera bundle drawn on top of the main camera inside `viewport`
+    pub fn pip_camera(viewport: Viewport) -> Camera3dBundle {
+        Camera3dBundle {
+            camera: Camera {
+                order: 1, // after the main camera
+                viewport: Some(viewport),
+                ..default()
+            },
+            camera_3d: Camera3d {
+                clear_color: ClearColorConfig::None,
+                ..default()
+            },
+            ..default()
+        }
+    }
+
+    /// world transform of the camera for a target at `target`. The offset is in the target's local space so the camera
+    /// swings around with it. The target's scale is ignored.
+    pub fn placement(&self, target: &Transform) -> Transform {
+        Transform {
+            translation: target.translation + target.rotation * Vec3::new(0.0, self.height, self.distance),
+            rotation: target.rotation,
+            ..default()
+        }
+    }
+}
+
+/// runs after transform propagation and writes the `GlobalTransform` as well so the camera doesn't lag a frame behind
+fn follow_rear_view_targets(
+    targets: Query<&GlobalTransform, Without<RearViewCamera>>,
+    mut cameras: Query<(&RearViewCamera, &mut Transform, &mut GlobalTransform), Without<Parent>>,
+) {
+    for (rear_view, mut transform, mut global_transform) in &mut cameras {
+        let Ok(target) = targets.get(rear_view.follow) else {
+            continue;
+        };
+
+        *transform = rear_view.placement(&target.compute_transform());
+        *global_transform = GlobalTransform::from(*transform);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_PI_2;
+
+    #[test]
+    fn placed_behind_and_above_target() {
+        let rear_view = RearViewCamera {
+            follow: Entity::PLACEHOLDER,
+            distance: 10.0,
+            height: 2.0,
+        };
+
+        // facing -X
+        let target = Transform::from_xyz(1.0, 2.0, 3.0).with_rotation(Quat::from_rotation_y(FRAC_PI_2));
+        let camera = rear_view.placement(&target);
+
+        let expected = Vec3::new(11.0, 4.0, 3.0);
+        assert!(
+            camera.translation.abs_diff_eq(expected, 1e-4),
+            "camera at {}",
+            camera.translation
+        );
+        assert!(camera.forward().abs_diff_eq(Vec3::NEG_X, 1e-4));
+    }
+}
@@ -13,10 +13,24 @@
 //! |Up|`Space`|
 //! |Down|`LControl`|
 //! |Alt. Speed|`LShift`|
+//! |Orbit (hold)|`LAlt`|
 //! |Release Cursor|`Escape`|
 //!
 //! Movement is constrained to the appropriate axes. (`WASD` to X & Z axes, `Space` & `LShift` to the Y axis)
 //!
+//! While the orbit key is held, mouse movement orbits the camera around the point `orbit_distance` in front of it
+//! instead of free-looking.
+//!
+//! Set `SpectatorSettings::gizmos` to draw a reference grid and the world axes at the origin.
+//!
+//! With the `debug_text_hud` feature, set `SpectatorSettings::show_hud` to print the spectator position and speed.
+//!
+//! With the `egui` feature the spectator ignores input while egui wants it, e.g. when dragging a slider in the
+//! inspector. Turn it off with `SpectatorSettings::ignore_egui_input`.
+//!
+//! With the `path_recorder` feature, `spectator_path::SpectatorPathRecorder` records keyframes of the spectator that
+//! can be replayed as a tween `Sequence`.
+//!
 //! ## `basic` Example
 //! ```
 //! use bevy::prelude::*;
@@ -38,6 +52,7 @@
 //! ```
 
 use bevy::{
+    ecs::system::SystemParam,
     input::mouse::MouseMotion,
     prelude::*,
     window::{CursorGrabMode, PrimaryWindow},
@@ -65,7 +80,18 @@ impl Plugin for SpectatorPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<SpectatorSettings>()
             .add_systems(PostStartup, setup)
-            .add_systems(Update, spectator_update);
+            .add_systems(Update, spectator_update)
+            .add_systems(Update, draw_spectator_gizmos.run_if(spectator_gizmos_enabled));
+
+        #[cfg(feature = "debug_text_hud")]
+        app.add_systems(Update, spectator_hud.after(spectator_update));
+
+        #[cfg(feature = "path_recorder")]
+        {
+            use crate::spectator_path::{record_spectator_path, SpectatorPathRecorder};
+            app.init_resource::<SpectatorPathRecorder>()
+                .add_systems(Update, record_spectator_path.after(spectator_update));
+        }
     }
 }
 
@@ -80,21 +106,41 @@ fn setup(mut commands: Commands, query: Query<Entity, With<Camera>>) {
     commands.entity(entity).insert(Spectator);
 }
 
+/// Time and input read by [`spectator_update`].
+#[derive(SystemParam)]
+struct SpectatorInput<'w, 's> {
+    time: Res<'w, Time>,
+    keys: Res<'w, Input<KeyCode>>,
+    buttons: Res<'w, Input<MouseButton>>,
+    motion: EventReader<'w, 's, MouseMotion>,
+}
+
 fn spectator_update(
-    time: Res<Time>,
-    keys: Res<Input<KeyCode>>,
-    buttons: Res<Input<MouseButton>>,
-    mut motion: EventReader<MouseMotion>,
+    input: SpectatorInput,
     mut settings: ResMut<SpectatorSettings>,
     mut q_windows: Query<&mut Window, With<PrimaryWindow>>,
     mut camera_transforms: Query<&mut Transform, With<Spectator>>,
     added: Query<Entity, Added<Spectator>>,
     mut focus: Local<bool>,
+    #[cfg(feature = "egui")] egui_state: Option<Res<egui_helper::EguiHelperState>>,
 ) {
     for entity in added.iter() {
         settings.active_spectator = Some(entity);
     }
 
+    let SpectatorInput {
+        time,
+        keys,
+        buttons,
+        mut motion,
+    } = input;
+
+    #[cfg(feature = "egui")]
+    if settings.ignore_egui_input && egui_state.is_some_and(|state| state.wants_input) {
+        motion.clear();
+        return;
+    }
+
     let mut window = q_windows.get_single_mut().unwrap();
 
     let Some(camera_id) = settings.active_spectator else {
@@ -139,14 +185,11 @@ fn spectator_update(
             let mouse_x = -mouse_delta.x * time.delta_seconds() * settings.sensitivity;
             let mouse_y = -mouse_delta.y * time.delta_seconds() * settings.sensitivity;
 
-            let mut dof: Vec3 = camera_transform.rotation.to_euler(EulerRot::YXZ).into();
-
-            dof.x += mouse_x;
-            // At 90 degrees, yaw gets misinterpeted as roll. Making 89 the limit fixes that.
-            dof.y = (dof.y + mouse_y).clamp(-89f32.to_radians(), 89f32.to_radians());
-            dof.z = 0f32;
-
-            camera_transform.rotation = Quat::from_euler(EulerRot::YXZ, dof.x, dof.y, dof.z);
+            if settings.orbit_key.is_some_and(|key| keys.pressed(key)) {
+                orbit(&mut camera_transform, settings.orbit_distance, mouse_x, mouse_y);
+            } else {
+                camera_transform.rotation = look(camera_transform.rotation, mouse_x, mouse_y);
+            }
         }
 
         // translation
@@ -177,6 +220,91 @@ fn spectator_update(
     motion.clear();
 }
 
+/// Run condition for [`draw_spectator_gizmos`].
+fn spectator_gizmos_enabled(settings: Res<SpectatorSettings>) -> bool {
+    settings.gizmos.is_some()
+}
+
+/// Draws a grid on the XZ plane and the world axes at the origin so there is a spatial reference in empty scenes.
+fn draw_spectator_gizmos(settings: Res<SpectatorSettings>, mut gizmos: Gizmos) {
+    let Some(config) = &settings.gizmos else {
+        return;
+    };
+
+    let cells = config.grid_cells as i32;
+    let extent = cells as f32 * config.grid_spacing;
+    for i in -cells..=cells {
+        let offset = i as f32 * config.grid_spacing;
+        gizmos.line(
+            Vec3::new(offset, 0.0, -extent),
+            Vec3::new(offset, 0.0, extent),
+            config.grid_color,
+        );
+        gizmos.line(
+            Vec3::new(-extent, 0.0, offset),
+            Vec3::new(extent, 0.0, offset),
+            config.grid_color,
+        );
+    }
+
+    gizmos.ray(Vec3::ZERO, Vec3::X * config.axis_length, Color::RED);
+    gizmos.ray(Vec3::ZERO, Vec3::Y * config.axis_length, Color::GREEN);
+    gizmos.ray(Vec3::ZERO, Vec3::Z * config.axis_length, Color::BLUE);
+}
+
+/// The text shown by the spectator HUD.
+pub fn spectator_hud_text(translation: Vec3, speed: f32) -> String {
+    format!(
+        "spectator pos: {:.2}, {:.2}, {:.2}\nspeed: {speed:.2}",
+        translation.x, translation.y, translation.z
+    )
+}
+
+/// Prints the position and speed (measured from the distance moved since the last frame) of the active spectator.
+#[cfg(feature = "debug_text_hud")]
+fn spectator_hud(
+    time: Res<Time>,
+    settings: Res<SpectatorSettings>,
+    transforms: Query<&Transform, With<Spectator>>,
+    mut last_translation: Local<Option<Vec3>>,
+) {
+    let Some(transform) = settings.active_spectator.and_then(|e| transforms.get(e).ok()) else {
+        *last_translation = None;
+        return;
+    };
+
+    let dt = time.delta_seconds();
+    let speed = match *last_translation {
+        Some(last) if dt > 0.0 => last.distance(transform.translation) / dt,
+        _ => 0.0,
+    };
+    *last_translation = Some(transform.translation);
+
+    if settings.show_hud {
+        use debug_text::screen_print;
+        screen_print!("{}", spectator_hud_text(transform.translation, speed));
+    }
+}
+
+/// Applies a yaw/pitch delta (in radians) to `rotation`, clamping the pitch and removing any roll.
+fn look(rotation: Quat, yaw: f32, pitch: f32) -> Quat {
+    let mut dof: Vec3 = rotation.to_euler(EulerRot::YXZ).into();
+
+    dof.x += yaw;
+    // At 90 degrees, yaw gets misinterpeted as roll. Making 89 the limit fixes that.
+    dof.y = (dof.y + pitch).clamp(-89f32.to_radians(), 89f32.to_radians());
+    dof.z = 0f32;
+
+    Quat::from_euler(EulerRot::YXZ, dof.x, dof.y, dof.z)
+}
+
+/// Rotates the camera like [`look`] but moves it so that the pivot `distance` along its forward axis stays in place.
+fn orbit(transform: &mut Transform, distance: f32, yaw: f32, pitch: f32) {
+    let pivot = transform.translation + transform.forward() * distance;
+    transform.rotation = look(transform.rotation, yaw, pitch);
+    transform.translation = pivot - transform.forward() * distance;
+}
+
 /// A `Resource` for controlling [`Spectator`]s.
 #[derive(Resource)]
 pub struct SpectatorSettings {
@@ -200,6 +328,45 @@ pub struct SpectatorSettings {
     ///
     /// Use this to control how fast the [`Spectator`] turns when you move the mouse.
     pub sensitivity: f32,
+    /// The key that, while held, orbits the active [`Spectator`] around its look target.
+    /// (Default: `Some(KeyCode::AltLeft)`)
+    ///
+    /// Set to `None` to always free-look.
+    pub orbit_key: Option<KeyCode>,
+    /// The distance along the forward axis of the pivot that is orbited around. (Default: `5.0`)
+    pub orbit_distance: f32,
+    /// Draws a reference grid and axes at the origin when set. (Default: `None`)
+    pub gizmos: Option<SpectatorGizmos>,
+    /// Prints the position and speed of the active [`Spectator`] when the `debug_text_hud` feature is enabled.
+    /// (Default: `false`)
+    pub show_hud: bool,
+    /// Ignores all spectator input while `EguiHelperState::wants_input` is set when the `egui` feature is enabled.
+    /// (Default: `true`)
+    pub ignore_egui_input: bool,
+}
+
+/// The reference grid and axes drawn when [`SpectatorSettings::gizmos`] is set.
+#[derive(Clone, Debug)]
+pub struct SpectatorGizmos {
+    /// The number of grid cells in each direction from the origin. (Default: `10`)
+    pub grid_cells: u32,
+    /// The size of a grid cell. (Default: `1.0`)
+    pub grid_spacing: f32,
+    /// The color of the grid lines. (Default: `Color::GRAY`)
+    pub grid_color: Color,
+    /// The length of the X (red), Y (green) and Z (blue) axes. (Default: `2.0`)
+    pub axis_length: f32,
+}
+
+impl Default for SpectatorGizmos {
+    fn default() -> Self {
+        Self {
+            grid_cells: 10,
+            grid_spacing: 1.0,
+            grid_color: Color::GRAY,
+            axis_length: 2.0,
+        }
+    }
 }
 
 impl Default for SpectatorSettings {
@@ -209,6 +376,88 @@ impl Default for SpectatorSettings {
             base_speed: 0.1,
             alt_speed: 0.5,
             sensitivity: 0.16,
+            orbit_key: Some(KeyCode::AltLeft),
+            orbit_distance: 5.0,
+            gizmos: None,
+            show_hud: false,
+            ignore_egui_input: true,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+
+    #[test]
+    fn orbit_keeps_pivot_fixed() {
+        let mut transform = Transform::from_xyz(0.0, 2.0, 10.0).looking_at(Vec3::ZERO, Vec3::Y);
+        let distance = transform.translation.length();
+        let start = transform.translation;
+
+        orbit(&mut transform, distance, 0.5, -0.2);
+
+        let pivot = transform.translation + transform.forward() * distance;
+        assert!(pivot.abs_diff_eq(Vec3::ZERO, 1e-4), "pivot moved to {pivot}");
+        assert!(transform.translation.distance(start) > 1.0);
+    }
+
+    #[test]
+    fn gizmos_system_runs_when_enabled() {
+        let mut app = App::new();
+        app.add_plugins(SpectatorPlugin);
+
+        let registered = app
+            .get_schedule(Update)
+            .unwrap()
+            .graph()
+            .systems()
+            .any(|(_, system, _)| system.name().contains("draw_spectator_gizmos"));
+        assert!(registered);
+
+        assert!(!app.world.run_system_once(spectator_gizmos_enabled));
+        app.world.resource_mut::<SpectatorSettings>().gizmos = Some(SpectatorGizmos::default());
+        assert!(app.world.run_system_once(spectator_gizmos_enabled));
+    }
+
+    #[test]
+    fn hud_text_includes_position() {
+        let text = spectator_hud_text(Vec3::new(1.0, -2.5, 30.25), 4.0);
+        assert!(text.contains("1.00, -2.50, 30.25"), "{text}");
+        assert!(text.contains("speed: 4.00"));
+    }
+
+    #[cfg(feature = "egui")]
+    #[test]
+    fn egui_input_freezes_spectator() {
+        use bevy::window::PrimaryWindow;
+        use egui_helper::EguiHelperState;
+
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .init_resource::<Input<KeyCode>>()
+            .init_resource::<Input<MouseButton>>()
+            .add_event::<MouseMotion>()
+            .init_resource::<SpectatorSettings>()
+            .insert_resource(EguiHelperState {
+                wants_input: true,
+                ..default()
+            })
+            .add_systems(Update, spectator_update);
+
+        app.world.spawn((Window::default(), PrimaryWindow));
+        let spectator = app.world.spawn((Transform::default(), Spectator)).id();
+
+        // grab the cursor and move forward, both stay pressed since nothing clears the input
+        app.world.resource_mut::<Input<MouseButton>>().press(MouseButton::Right);
+        app.world.resource_mut::<Input<KeyCode>>().press(KeyCode::W);
+
+        app.update();
+        assert_eq!(*app.world.get::<Transform>(spectator).unwrap(), Transform::default());
+
+        app.world.resource_mut::<EguiHelperState>().wants_input = false;
+        app.update();
+        assert_ne!(*app.world.get::<Transform>(spectator).unwrap(), Transform::default());
+    }
+}
@@ -0,0 +1,157 @@
+//! Records the path of the active [`Spectator`] to replay it as a tween [`Sequence`], e.g. to block out cutscenes by
+//! flying the camera around.
+//!
+//! Set [`SpectatorPathRecorder::recording`], fly around and press the keyframe key (or set an interval), then turn
+//! the keyframes into a `Sequence<Transform>` with [`SpectatorPathRecorder::to_sequence`] and play it back with an
+//! `Animator<Transform>` on the camera.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use tween::{lens::TransformPositionRotationLens, EaseMethod, Sequence, Tween};
+
+use crate::spectator::{Spectator, SpectatorSettings};
+
+/// A `Resource` recording keyframes of the active [`Spectator`], inserted by the `SpectatorPlugin`.
+#[derive(Resource)]
+pub struct SpectatorPathRecorder {
+    /// Keyframes are only recorded while set. (Default: `false`)
+    pub recording: bool,
+    /// The key recording a keyframe when pressed. (Default: `Some(KeyCode::K)`)
+    pub keyframe_key: Option<KeyCode>,
+    /// Also records a keyframe each time this much time passed since the last one. (Default: `None`)
+    pub interval: Option<Duration>,
+    keyframes: Vec<(Duration, Transform)>,
+}
+
+impl Default for SpectatorPathRecorder {
+    fn default() -> Self {
+        Self {
+            recording: false,
+            keyframe_key: Some(KeyCode::K),
+            interval: None,
+            keyframes: Vec::new(),
+        }
+    }
+}
+
+impl SpectatorPathRecorder {
+    /// Records a keyframe at `time`, the elapsed app time the keyframe was taken at.
+    pub fn record(&mut self, time: Duration, transform: Transform) {
+        self.keyframes.push((time, transform));
+    }
+
+    /// The recorded keyframes and the time they were taken at.
+    pub fn keyframes(&self) -> &[(Duration, Transform)] {
+        &self.keyframes
+    }
+
+    /// Removes all recorded keyframes.
+    pub fn clear(&mut self) {
+        self.keyframes.clear();
+    }
+
+    /// Builds a sequence moving from keyframe to keyframe with the timing they were recorded with. Keyframes taken
+    /// at the same time are skipped since a tween can't have a zero duration. Returns `None` when there is no time
+    /// between the keyframes to animate.
+    pub fn to_sequence(&self, ease_function: impl Into<EaseMethod>) -> Option<Sequence<Transform>> {
+        let ease_function = ease_function.into();
+        let tweens: Vec<_> = self
+            .keyframes
+            .windows(2)
+            .filter(|pair| pair[1].0 > pair[0].0)
+            .map(|pair| {
+                let ((start_time, start), (end_time, end)) = (pair[0], pair[1]);
+                Tween::new(
+                    ease_function.clone(),
+                    end_time - start_time,
+                    TransformPositionRotationLens {
+                        start: (start.translation, start.rotation),
+                        end: (end.translation, end.rotation),
+                    },
+                )
+            })
+            .collect();
+
+        (!tweens.is_empty()).then(|| Sequence::new(tweens))
+    }
+}
+
+/// Records a keyframe of the active [`Spectator`] when the keyframe key is pressed or the interval elapsed.
+pub(crate) fn record_spectator_path(
+    time: Res<Time>,
+    keys: Res<Input<KeyCode>>,
+    settings: Res<SpectatorSettings>,
+    mut recorder: ResMut<SpectatorPathRecorder>,
+    transforms: Query<&Transform, With<Spectator>>,
+) {
+    if !recorder.recording {
+        return;
+    }
+
+    let Some(transform) = settings.active_spectator.and_then(|e| transforms.get(e).ok()) else {
+        return;
+    };
+
+    let now = time.elapsed();
+    let pressed = recorder.keyframe_key.is_some_and(|key| keys.just_pressed(key));
+    let due = recorder.interval.is_some_and(|interval| {
+        recorder
+            .keyframes
+            .last()
+            .is_none_or(|(last, _)| now.saturating_sub(*last) >= interval)
+    });
+    if pressed || due {
+        recorder.record(now, *transform);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tween::Tweenable;
+
+    #[test]
+    fn sequence_matches_recorded_timing() {
+        let mut recorder = SpectatorPathRecorder::default();
+        assert!(recorder.to_sequence(EaseMethod::Linear).is_none());
+
+        recorder.record(Duration::from_secs(2), Transform::default());
+        recorder.record(Duration::from_secs(3), Transform::from_xyz(1.0, 0.0, 0.0));
+        recorder.record(Duration::from_secs(3), Transform::from_xyz(1.0, 0.0, 0.0));
+        recorder.record(Duration::from_millis(4500), Transform::from_xyz(1.0, 2.0, 0.0));
+
+        let sequence = recorder.to_sequence(EaseMethod::Linear).unwrap();
+        assert_eq!(sequence.duration(), Duration::from_millis(2500));
+    }
+
+    #[test]
+    fn interval_records_keyframes() {
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .init_resource::<Input<KeyCode>>()
+            .init_resource::<SpectatorSettings>()
+            .insert_resource(SpectatorPathRecorder {
+                recording: true,
+                interval: Some(Duration::from_millis(500)),
+                ..default()
+            })
+            .add_systems(Update, record_spectator_path);
+        let spectator = app.world.spawn((Transform::default(), Spectator)).id();
+        app.world.resource_mut::<SpectatorSettings>().active_spectator = Some(spectator);
+
+        for _ in 0..5 {
+            app.world.resource_mut::<Time>().advance_by(Duration::from_millis(250));
+            app.update();
+        }
+
+        let times: Vec<_> = app
+            .world
+            .resource::<SpectatorPathRecorder>()
+            .keyframes()
+            .iter()
+            .map(|(time, _)| *time)
+            .collect();
+        assert_eq!(times, [250, 750, 1250].map(Duration::from_millis));
+    }
+}
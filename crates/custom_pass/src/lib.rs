@@ -606,6 +606,17 @@ pub fn queue_prepass_view_bind_group<M: Material>(
     }
 }
 
+/// Adds the bits `alpha_mode` needs to `mesh_key`. Transparent alpha modes return `None` and are never queued, so
+/// they are neither drawn into the prepass nor classified as lights or occluders. This is what keeps the
+/// `AlphaMode::Blend` glow quads of the `meshes` crate's `DoomLight` out of the prepass without any extra setup.
+fn prepass_mesh_key(mesh_key: MeshPipelineKey, alpha_mode: AlphaMode) -> Option<MeshPipelineKey> {
+    match alpha_mode {
+        AlphaMode::Opaque => Some(mesh_key),
+        AlphaMode::Mask(_) => Some(mesh_key | MeshPipelineKey::BLEND_ALPHA), // TODO: was alph ALPHA_MASK
+        AlphaMode::Blend | AlphaMode::Premultiplied | AlphaMode::Add | AlphaMode::Multiply => None,
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn queue_prepass_material_meshes<M: Material>(
     opaque_draw_functions: Res<DrawFunctions<CustomOpaque3dPrepass>>,
@@ -655,6 +666,12 @@ pub fn queue_prepass_material_meshes<M: Material>(
                 continue;
             };
 
+            let alpha_mode = material.properties.alpha_mode;
+            let mesh_key = MeshPipelineKey::from_primitive_topology(mesh.primitive_topology) | view_key;
+            let Some(mesh_key) = prepass_mesh_key(mesh_key, alpha_mode) else {
+                continue;
+            };
+
             let Ok((is_light, is_occluder)) = occluder_components.get(*visible_entity) else {
                 println!("------ fuuuuuck nothing found");
                 continue;
@@ -666,14 +683,6 @@ pub fn queue_prepass_material_meshes<M: Material>(
                 is_occluder.is_some()
             );
 
-            let mut mesh_key = MeshPipelineKey::from_primitive_topology(mesh.primitive_topology) | view_key;
-            let alpha_mode = material.properties.alpha_mode;
-            match alpha_mode {
-                AlphaMode::Opaque => {}
-                AlphaMode::Mask(_) => mesh_key |= MeshPipelineKey::BLEND_ALPHA, // TODO: was alph ALPHA_MASK
-                AlphaMode::Blend | AlphaMode::Premultiplied | AlphaMode::Add | AlphaMode::Multiply => continue,
-            }
-
             let pipeline_id = pipelines.specialize(
                 &pipeline_cache,
                 &prepass_pipeline,
@@ -750,3 +759,25 @@ pub type DrawOcclusionPrepass<M> = (
     SetMeshBindGroup<2>,
     DrawMesh,
 );
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transparent_materials_are_not_queued() {
+        // DoomLightMaterial in the meshes crate is AlphaMode::Blend
+        let mesh_key = MeshPipelineKey::DEPTH_PREPASS;
+        for alpha_mode in [
+            AlphaMode::Blend,
+            AlphaMode::Premultiplied,
+            AlphaMode::Add,
+            AlphaMode::Multiply,
+        ] {
+            assert_eq!(prepass_mesh_key(mesh_key, alpha_mode), None);
+        }
+
+        assert_eq!(prepass_mesh_key(mesh_key, AlphaMode::Opaque), Some(mesh_key));
+        assert!(prepass_mesh_key(mesh_key, AlphaMode::Mask(0.5)).is_some());
+    }
+}
@@ -1,48 +1,174 @@
-use bevy::prelude::*;
-use bevy_inspector_egui::{
-    bevy_egui::{EguiContext, EguiSet},
-    quick::WorldInspectorPlugin,
-};
+use bevy::{prelude::*, utils::HashMap};
+use bevy_egui::{EguiContext, EguiSet};
 
+pub use bevy_egui;
+#[cfg(feature = "inspector")]
 pub use bevy_inspector_egui;
 
-#[derive(Resource, PartialEq, Eq)]
+#[derive(Resource, Default, PartialEq, Eq)]
 pub struct EguiHelperState {
     pub enabled: bool,
+    /// true if egui wants input in any window
     pub wants_input: bool,
+    /// whether egui wants input, keyed by window entity
+    pub window_wants_input: HashMap<Entity, bool>,
+}
+
+impl EguiHelperState {
+    /// stores whether egui wants input this frame and flips `enabled` when the toggle key was pressed. The toggle is
+    /// ignored while egui wants keyboard input so typing a backtick into a text field doesn't hide the inspector.
+    pub fn update(&mut self, wants_pointer_input: bool, wants_keyboard_input: bool, toggle_pressed: bool) {
+        self.wants_input = wants_pointer_input || wants_keyboard_input;
+        if toggle_pressed && !wants_keyboard_input {
+            self.enabled = !self.enabled;
+        }
+    }
+
+    /// per window version of `update`. Each item is `(window, wants_pointer_input, wants_keyboard_input)`.
+    pub fn update_windows(&mut self, windows: impl IntoIterator<Item = (Entity, bool, bool)>, toggle_pressed: bool) {
+        self.window_wants_input.clear();
+
+        let (mut wants_pointer_input, mut wants_keyboard_input) = (false, false);
+        for (window, pointer, keyboard) in windows {
+            self.window_wants_input.insert(window, pointer || keyboard);
+            wants_pointer_input |= pointer;
+            wants_keyboard_input |= keyboard;
+        }
+
+        self.update(wants_pointer_input, wants_keyboard_input, toggle_pressed);
+    }
+
+    /// whether egui wants input in `window`. Windows without an egui context never do.
+    pub fn wants_input_for(&self, window: Entity) -> bool {
+        self.window_wants_input.get(&window).copied().unwrap_or(false)
+    }
 }
 
 /// adds the WorldInspectorPlugin to the App and lets you hide/show it via pressing tilde. If egui wnats input
 /// EguiHelperState.wants_input will be true and the game can choose to ignore input events.
+///
+/// Without the `inspector` feature only the EguiPlugin is added, so the toggle and `wants_input` can gate your own
+/// egui windows.
 #[derive(Default)]
 pub struct EguiHelperPlugin;
 
 impl Plugin for EguiHelperPlugin {
     fn build(&self, app: &mut App) {
+        app.init_resource::<EguiHelperState>()
+            .add_systems(PreUpdate, update.after(EguiSet::ProcessInput));
+
+        #[cfg(feature = "inspector")]
+        app.add_plugins(bevy_inspector_egui::quick::WorldInspectorPlugin::new().run_if(run_if_egui_enabled));
+
+        #[cfg(not(feature = "inspector"))]
+        if !app.is_plugin_added::<bevy_egui::EguiPlugin>() {
+            app.add_plugins(bevy_egui::EguiPlugin);
+        }
+    }
+}
+
+/// helpfer for system `run_if` conditions to only run the system if egui is enabled. Without `EguiHelperState`, i.e.
+/// in apps that never added the `EguiHelperPlugin`, this is false.
+pub fn run_if_egui_enabled(res: Option<Res<EguiHelperState>>) -> bool {
+    res.is_some_and(|res| res.enabled)
+}
+
+/// opposite of `run_if_egui_enabled`
+pub fn run_if_egui_disabled(res: Option<Res<EguiHelperState>>) -> bool {
+    !run_if_egui_enabled(res)
+}
+
+fn update(
+    mut q: Query<(Entity, &mut EguiContext)>,
+    mut state: ResMut<EguiHelperState>,
+    keyboard_input: Res<Input<KeyCode>>,
+) {
+    let windows = q.iter_mut().map(|(window, mut egui)| {
+        let ctx = egui.get_mut();
+        (window, ctx.wants_pointer_input(), ctx.wants_keyboard_input())
+    });
+
+    state.update_windows(windows, keyboard_input.just_pressed(KeyCode::Grave));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toggles_and_reports_wants_input() {
+        let mut app = App::new();
         app.insert_resource(EguiHelperState {
             enabled: false,
-            wants_input: false,
+            wants_input: true,
+            ..default()
         })
-        .add_plugins(WorldInspectorPlugin::new().run_if(run_if_egui_enabled))
-        .add_systems(PreUpdate, update.after(EguiSet::ProcessInput));
+        .init_resource::<Input<KeyCode>>()
+        .add_systems(Update, update);
+
+        app.world.resource_mut::<Input<KeyCode>>().press(KeyCode::Grave);
+        app.update();
+        let state = app.world.resource::<EguiHelperState>();
+        assert!(state.enabled);
+        // no egui context is asking for input
+        assert!(!state.wants_input);
+
+        let mut state = app.world.resource_mut::<EguiHelperState>();
+        state.update(true, false, true);
+        assert!(state.wants_input && !state.enabled);
     }
-}
 
-/// helpfer for system `run_if` conditions to only run the system if egui is enabled
-pub fn run_if_egui_enabled(res: Option<Res<EguiHelperState>>) -> bool {
-    match res {
-        Some(res) => res.enabled,
-        None => true,
+    #[test]
+    fn toggle_ignored_while_typing() {
+        let mut state = EguiHelperState {
+            enabled: true,
+            ..default()
+        };
+
+        state.update(false, true, true);
+        assert!(state.enabled);
+        assert!(state.wants_input);
+
+        state.update(false, false, true);
+        assert!(!state.enabled);
     }
-}
 
-fn update(mut q: Query<&mut EguiContext>, mut state: ResMut<EguiHelperState>, keyboard_input: Res<Input<KeyCode>>) {
-    for egui in q.iter_mut() {
-        state.wants_input =
-            egui.clone().get_mut().wants_pointer_input() || egui.clone().get_mut().wants_keyboard_input();
+    #[test]
+    fn tracks_input_per_window() {
+        let (first, second, missing) = (Entity::from_raw(0), Entity::from_raw(1), Entity::from_raw(2));
+        let mut state = EguiHelperState::default();
+
+        state.update_windows([(first, true, false), (second, false, false)], false);
+        assert!(state.wants_input_for(first));
+        assert!(!state.wants_input_for(second));
+        assert!(!state.wants_input_for(missing));
+        assert!(state.wants_input);
+
+        state.update_windows([(first, false, false), (second, false, false)], false);
+        assert!(!state.wants_input);
     }
 
-    if keyboard_input.just_pressed(KeyCode::Grave) {
-        state.enabled = !state.enabled;
+    #[test]
+    fn run_conditions_without_state() {
+        let mut world = World::new();
+        let mut enabled = IntoSystem::into_system(run_if_egui_enabled);
+        let mut disabled = IntoSystem::into_system(run_if_egui_disabled);
+        enabled.initialize(&mut world);
+        disabled.initialize(&mut world);
+
+        // absent: don't run inspector-gated systems in apps that never added the plugin
+        assert!(!enabled.run((), &mut world));
+        assert!(disabled.run((), &mut world));
+
+        world.insert_resource(EguiHelperState {
+            enabled: false,
+            ..default()
+        });
+        assert!(!enabled.run((), &mut world));
+        assert!(disabled.run((), &mut world));
+
+        world.resource_mut::<EguiHelperState>().enabled = true;
+        assert!(enabled.run((), &mut world));
+        assert!(!disabled.run((), &mut world));
     }
 }
@@ -1,2 +0,0 @@
-mod moving_platform;
-pub use moving_platform::MovingPlatform;
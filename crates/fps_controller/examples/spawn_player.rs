@@ -0,0 +1,92 @@
+use bevy::{
+    prelude::*,
+    render::{camera::Viewport, view::RenderLayers},
+};
+
+use bevy_rapier3d::prelude::*;
+
+use fps_controller::{
+    camera_shake::{CameraShakePlugin, Shake3d},
+    input::{FpsInputPlugin, RenderPlayer},
+    player::{spawn_fps_player, FpsPlayerConfig},
+    ultrakill::{FpsController, UltrakillControllerPlugin},
+};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+        .add_plugins(FpsInputPlugin)
+        .add_plugins(UltrakillControllerPlugin)
+        .add_plugins(CameraShakePlugin)
+        .add_systems(Startup, setup_scene)
+        .add_systems(PostStartup, verify_hierarchy)
+        .run();
+}
+
+fn setup_scene(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::from_xyz(0.0, 10.0, 0.0).looking_at(Vec3::new(-0.15, -0.5, 0.25), Vec3::Y),
+        ..default()
+    });
+
+    commands.spawn((
+        Name::new("Plane"),
+        Collider::cuboid(140.0, 0.1, 140.0),
+        PbrBundle {
+            mesh: meshes.add(shape::Box::new(280.0, 0.2, 280.0).into()),
+            material: materials.add(Color::rgb(0.3, 0.5, 0.3).into()),
+            transform: Transform::from_xyz(0.0, -2.0, 0.0),
+            ..default()
+        },
+    ));
+
+    spawn_fps_player(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        FpsPlayerConfig {
+            transform: Transform::from_xyz(0.0, 2.0, 0.0),
+            pip_viewport: Some(Viewport {
+                physical_position: UVec2::new(2048, 0),
+                physical_size: UVec2::new(512, 288),
+                ..default()
+            }),
+            ..default()
+        },
+    );
+}
+
+/// walks the spawned hierarchy and checks that every node ended up where it should with the right render layers
+fn verify_hierarchy(
+    players: Query<(&Children, &RenderLayers), With<FpsController>>,
+    shakes: Query<&Children, With<Shake3d>>,
+    cameras: Query<(&RenderLayers, Option<&Children>), With<RenderPlayer>>,
+    pip_cameras: Query<&RenderLayers, (With<Camera>, Without<RenderPlayer>)>,
+) {
+    let (children, player_layers) = players.single();
+    let shake = children
+        .iter()
+        .find_map(|e| shakes.get(*e).ok())
+        .expect("Shake3d child");
+    let (camera_layers, camera_children) = shake
+        .iter()
+        .find_map(|e| cameras.get(*e).ok())
+        .expect("RenderPlayer child");
+
+    assert!(
+        !camera_layers.intersects(player_layers),
+        "render camera can see its own player"
+    );
+
+    let pip_layers = camera_children
+        .and_then(|children| children.iter().find_map(|e| pip_cameras.get(*e).ok()))
+        .expect("PiP camera child");
+    assert!(pip_layers.intersects(player_layers), "PiP camera can't see the player");
+
+    info!("player hierarchy is wired up correctly");
+}
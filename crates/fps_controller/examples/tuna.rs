@@ -1,9 +1,6 @@
-mod common;
-
 use std::f32::consts::TAU;
 
 use bevy::{
-    core_pipeline::clear_color::ClearColorConfig,
     prelude::*,
     render::{camera::Viewport, view::RenderLayers},
 };
@@ -14,9 +11,12 @@ use bevy_tnua::{
     TnuaPlatformerControls, TnuaPlatformerPlugin, TnuaRapier3dPlugin,
 };
 
-use common::MovingPlatform;
+use cameras::rear_view::{RearViewCamera, RearViewCameraPlugin};
 use egui_helper::EguiHelperPlugin;
-use fps_controller::input::{FpsControllerInput, FpsInputPlugin, FpsPlayer, RenderPlayer};
+use fps_controller::{
+    input::{FpsControllerInput, FpsInputPlugin, FpsPlayer, RenderPlayer},
+    moving_platform::{MovingPlatform, MovingPlatformPlugin},
+};
 use valve_maps::bevy::{ValveMapBundle, ValveMapPlayer, ValveMapPlugin};
 
 fn main() {
@@ -29,13 +29,12 @@ fn main() {
     app.add_plugin(EguiHelperPlugin);
     app.add_plugin(ValveMapPlugin);
     app.add_plugin(FpsInputPlugin);
+    app.add_plugin(MovingPlatformPlugin);
+    app.add_plugin(RearViewCameraPlugin);
     app.add_startup_system(setup_camera);
     app.add_startup_system(setup_level);
     app.add_startup_system(setup_player);
     app.add_system(apply_controls);
-    app.add_system(MovingPlatform::make_system(|velocity: &mut Velocity, linvel: Vec3| {
-        velocity.linvel = linvel;
-    }));
     app.run();
 }
 
@@ -164,48 +163,42 @@ fn setup_player(
     }));
     cmd.insert(TnuaPlatformerAnimatingOutput::default());
 
-    cmd.with_children(|builder| {
-        builder
-            .spawn((
-                RenderPlayer,
-                Camera3dBundle {
-                    transform: Transform::from_xyz(0.0, 1.0, 0.0),
-                    projection: Projection::Perspective(PerspectiveProjection {
-                        fov: TAU / 5.0,
-                        ..default()
-                    }),
+    let player = cmd.id();
+
+    let render_camera = commands
+        .spawn((
+            RenderPlayer,
+            Camera3dBundle {
+                transform: Transform::from_xyz(0.0, 1.0, 0.0),
+                projection: Projection::Perspective(PerspectiveProjection {
+                    fov: TAU / 5.0,
                     ..default()
-                },
-                RenderLayers::default().without(1), // all but our LogicalPlayer
-            ))
-            .with_children(|builder| {
-                // Right Camera for 3rd person view trailing a bit and slightly above the player
-                let win_w = 1280;
-                let frame_w = 256;
-                let frame_h = 256 / (1280 / 720);
-                builder.spawn((
-                    Camera3dBundle {
-                        transform: Transform::from_xyz(0., 1.5, 15.0),
-                        camera: Camera {
-                            order: 1, // after other camera
-                            viewport: Some(Viewport {
-                                physical_position: UVec2::new(win_w * 2 - frame_w * 2, 0),
-                                physical_size: UVec2::new(frame_w * 2, frame_h * 2),
-                                ..default()
-                            }),
-                            ..default()
-                        },
-                        camera_3d: Camera3d {
-                            clear_color: ClearColorConfig::None,
-                            ..default()
-                        },
-                        ..default()
-                    },
-                    UiCameraConfig { show_ui: false },
-                    RenderLayers::default().with(1),
-                ));
-            });
-    });
+                }),
+                ..default()
+            },
+            RenderLayers::default().without(1), // all but our LogicalPlayer
+        ))
+        .set_parent(player)
+        .id();
+
+    // Right Camera for 3rd person view trailing a bit and slightly above the player
+    let win_w = 1280;
+    let frame_w = 256;
+    let frame_h = 256 / (1280 / 720);
+    commands.spawn((
+        RearViewCamera {
+            follow: render_camera,
+            distance: 15.0,
+            height: 1.5,
+        },
+        RearViewCamera::pip_camera(Viewport {
+            physical_position: UVec2::new(win_w * 2 - frame_w * 2, 0),
+            physical_size: UVec2::new(frame_w * 2, frame_h * 2),
+            ..default()
+        }),
+        UiCameraConfig { show_ui: false },
+        RenderLayers::default().with(1),
+    ));
 }
 
 fn apply_controls(mut query: Query<(&Transform, &mut TnuaPlatformerControls, &FpsControllerInput)>) {
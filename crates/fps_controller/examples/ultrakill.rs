@@ -21,6 +21,7 @@ use fps_controller::{
     math::map,
     time_controller::TimeManagerPlugin,
     ultrakill::{FpsControllerBundle, UltrakillControllerPlugin},
+    FpsDebugLinesPlugin,
 };
 use valve_maps::bevy::{ValveMapBundle, ValveMapPlugin};
 
@@ -43,6 +44,7 @@ fn main() {
         .add_plugin(RapierPhysicsPlugin::<NoUserData>::default())
         .add_plugin(RapierDebugRenderPlugin::default())
         .add_plugin(FpsInputPlugin)
+        .add_plugin(FpsDebugLinesPlugin::default())
         .add_plugin(UltrakillControllerPlugin)
         .add_plugin(CameraShakePlugin)
         .add_plugin(TimeManagerPlugin)
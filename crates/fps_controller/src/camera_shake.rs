@@ -30,21 +30,17 @@ impl RandomSource for StandardRandom {
     }
 }
 
-/// The component to be added for a 3d shake.
-/// Will modify the transform of the entity that this component is added to.
-/// The entity must also have a `Transform` component for the shake to work.
-#[derive(Component)]
-pub struct Shake3d {
-    /// The maximum amount to translate laterally in all 3 dimensions.
+/// How a [`Shake3d`] turns trauma into motion. Different `Shake3d`s can use different profiles, e.g. a mostly
+/// vertical one for landing impacts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShakeProfile {
+    /// The maximum amount to translate laterally, separately for each axis.
     /// Defaults to `Vec3::new(0.0, 0.0, 0.0)`.
     /// Setting this to a non-zero value can cause the camera to poke through walls.
     pub max_offset: Vec3,
     /// The maximum amount to rotate in yaw, pitch, and roll.
     /// Defaults to  `Vec3::new(0.1, 0.1, 0.1)`.
     pub max_yaw_pitch_roll: Vec3,
-    /// The starting trauma when created.
-    /// Defaults to `0.0`.
-    pub trauma: f32,
     /// The exponent of the trauma used when calculating offset and rotational shakiness.
     /// Should likely be set to a value between `2.0` and `3.0`.
     /// Defaults to `2.0`.
@@ -53,6 +49,47 @@ pub struct Shake3d {
     /// If set to 1, there will be no trauma after 1 second. If set to 0, trauma will not decrease over time.
     /// Defaults to `0.8`.
     pub decay: f32,
+}
+
+impl Default for ShakeProfile {
+    fn default() -> Self {
+        Self {
+            max_offset: Vec3::new(0.0, 0.0, 0.0),
+            max_yaw_pitch_roll: Vec3::new(0.1, 0.1, 0.1),
+            trauma_power: 2.0,
+            decay: 0.8,
+        }
+    }
+}
+
+impl ShakeProfile {
+    /// The shake intensity for `trauma`, `trauma ^ trauma_power`.
+    pub fn intensity(&self, trauma: f32) -> f32 {
+        f32::powf(trauma, self.trauma_power)
+    }
+
+    /// The translation for `trauma` given a random value between -1.0 and 1.0 per axis.
+    pub fn offset(&self, trauma: f32, random: Vec3) -> Vec3 {
+        self.max_offset * self.intensity(trauma) * random
+    }
+
+    /// The yaw, pitch and roll for `trauma` given a random value between -1.0 and 1.0 per axis.
+    pub fn yaw_pitch_roll(&self, trauma: f32, random: Vec3) -> Vec3 {
+        self.max_yaw_pitch_roll * self.intensity(trauma) * random
+    }
+}
+
+/// The component to be added for a 3d shake.
+/// Will modify the transform of the entity that this component is added to.
+/// The entity must also have a `Transform` component for the shake to work.
+#[derive(Component)]
+pub struct Shake3d {
+    /// How trauma is turned into motion.
+    /// Defaults to `ShakeProfile::default()`.
+    pub profile: ShakeProfile,
+    /// The starting trauma when created.
+    /// Defaults to `0.0`.
+    pub trauma: f32,
     /// The random sources for all 6 dimensions.
     /// The first 3 are XYZ lateral motion, the last 3 are yaw/pitch/roll.
     /// Defaults to a `NotRandom`, which always returns `0.5`.
@@ -62,11 +99,8 @@ pub struct Shake3d {
 impl Default for Shake3d {
     fn default() -> Self {
         Self {
-            max_offset: Vec3::new(0.0, 0.0, 0.0),
-            max_yaw_pitch_roll: Vec3::new(0.1, 0.1, 0.1),
+            profile: ShakeProfile::default(),
             trauma: 0.0,
-            trauma_power: 2.0,
-            decay: 0.8,
             random_sources: [
                 Box::new(StandardRandom),
                 Box::new(StandardRandom),
@@ -79,6 +113,15 @@ impl Default for Shake3d {
     }
 }
 
+impl Shake3d {
+    pub fn with_profile(profile: ShakeProfile) -> Self {
+        Self {
+            profile,
+            ..Default::default()
+        }
+    }
+}
+
 /// The component to be added for a 2d shake.
 /// Will modify the transform of the entity that this component is added to.
 /// The entity must also have a `Transform` component for the shake to work.
@@ -123,27 +166,27 @@ impl Default for Shake2d {
 
 fn apply_shake_3d(mut query: Query<(&mut Transform, &mut Shake3d)>, time: Res<Time>) {
     for (mut transform, mut shake_settings) in query.iter_mut() {
-        shake_settings.trauma = f32::max(shake_settings.trauma - shake_settings.decay * time.delta_seconds(), 0.0);
+        let profile = shake_settings.profile;
+        shake_settings.trauma = f32::max(shake_settings.trauma - profile.decay * time.delta_seconds(), 0.0);
 
-        let trauma_amount = f32::powf(shake_settings.trauma, shake_settings.trauma_power);
-        if trauma_amount > 0.0 {
-            let shake_translation = shake_settings.max_offset
-                * trauma_amount
-                * time.relative_speed()
-                * Vec3::new(
+        if profile.intensity(shake_settings.trauma) > 0.0 {
+            let shake_translation = profile.offset(
+                shake_settings.trauma,
+                Vec3::new(
                     shake_settings.random_sources[0].rand(time.elapsed_seconds()),
                     shake_settings.random_sources[1].rand(time.elapsed_seconds()),
                     shake_settings.random_sources[2].rand(time.elapsed_seconds()),
-                );
+                ),
+            ) * time.relative_speed();
 
-            let rotation = shake_settings.max_yaw_pitch_roll
-                * trauma_amount
-                * time.relative_speed()
-                * Vec3::new(
+            let rotation = profile.yaw_pitch_roll(
+                shake_settings.trauma,
+                Vec3::new(
                     shake_settings.random_sources[3].rand(time.elapsed_seconds()),
                     shake_settings.random_sources[4].rand(time.elapsed_seconds()),
                     shake_settings.random_sources[5].rand(time.elapsed_seconds()),
-                );
+                ),
+            ) * time.relative_speed();
 
             let shake_rotation = Quat::from_euler(EulerRot::YXZ, rotation.x, rotation.y, rotation.z);
             transform.translation = shake_translation;
@@ -193,3 +236,26 @@ impl Plugin for CameraShakePlugin {
         app.add_system(apply_shake_3d);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn profiles_shake_different_axes() {
+        let horizontal = ShakeProfile {
+            max_offset: Vec3::new(0.2, 0.0, 0.2),
+            ..Default::default()
+        };
+        let vertical = ShakeProfile {
+            max_offset: Vec3::new(0.05, 0.5, 0.0),
+            trauma_power: 3.0,
+            ..Default::default()
+        };
+
+        let random = Vec3::ONE;
+        assert_eq!(horizontal.offset(0.5, random), Vec3::new(0.05, 0.0, 0.05));
+        assert_eq!(vertical.offset(0.5, random), Vec3::new(0.00625, 0.0625, 0.0));
+        assert_eq!(vertical.offset(0.0, random), Vec3::ZERO);
+    }
+}
@@ -1,7 +1,97 @@
 use std::f32::consts::FRAC_PI_2;
 
+#[cfg(feature = "gizmos")]
+use bevy::prelude::Gizmos;
+#[cfg(feature = "debug_lines")]
+use bevy::prelude::{App, Plugin, ResMut};
 use bevy::prelude::{Color, Vec3};
-use bevy_prototype_debug_lines::DebugLines;
+#[cfg(feature = "debug_lines")]
+use bevy_prototype_debug_lines::{DebugLines, DebugLinesPlugin};
+
+/// line drawing the controllers' debug visualizations go through, so they work with either `bevy_prototype_debug_lines`
+/// (the default `debug_lines` feature) or bevy's `Gizmos` (the `gizmos` feature)
+pub trait DebugDraw {
+    /// draws a line that stays up for `duration` seconds. Gizmos are immediate mode and ignore `duration`, their lines
+    /// only show for the frame they were drawn in
+    fn line_colored(&mut self, start: Vec3, end: Vec3, duration: f32, color: Color);
+}
+
+#[cfg(feature = "debug_lines")]
+impl DebugDraw for DebugLines {
+    fn line_colored(&mut self, start: Vec3, end: Vec3, duration: f32, color: Color) {
+        DebugLines::line_colored(self, start, end, duration, color);
+    }
+}
+
+#[cfg(feature = "gizmos")]
+impl DebugDraw for Gizmos<'_> {
+    fn line_colored(&mut self, start: Vec3, end: Vec3, _duration: f32, color: Color) {
+        self.line(start, end, color);
+    }
+}
+
+/// system param of the active [`DebugDraw`] backend, get at it with [`DebugDrawBackend::draw`]. With the `gizmos`
+/// feature this is bevy's `Gizmos`, otherwise the `DebugLines` resource, which is only there once
+/// [`FpsDebugLinesPlugin`] (or `DebugLinesPlugin`) was added. Without either feature nothing is drawn.
+#[cfg(feature = "gizmos")]
+pub type FpsDebugDraw<'w, 's> = Gizmos<'s>;
+#[cfg(all(feature = "debug_lines", not(feature = "gizmos")))]
+pub type FpsDebugDraw<'w, 's> = Option<ResMut<'w, DebugLines>>;
+#[cfg(not(any(feature = "debug_lines", feature = "gizmos")))]
+pub type FpsDebugDraw<'w, 's> = ();
+
+/// implemented by the possible [`FpsDebugDraw`] system params
+pub trait DebugDrawBackend {
+    /// the backend to draw with or `None` if there is nothing to draw with
+    fn draw(&mut self) -> Option<&mut dyn DebugDraw>;
+}
+
+#[cfg(feature = "gizmos")]
+impl DebugDrawBackend for Gizmos<'_> {
+    fn draw(&mut self) -> Option<&mut dyn DebugDraw> {
+        Some(self)
+    }
+}
+
+#[cfg(feature = "debug_lines")]
+impl DebugDrawBackend for Option<ResMut<'_, DebugLines>> {
+    fn draw(&mut self) -> Option<&mut dyn DebugDraw> {
+        self.as_deref_mut().map(|lines| lines as &mut dyn DebugDraw)
+    }
+}
+
+impl DebugDrawBackend for () {
+    fn draw(&mut self) -> Option<&mut dyn DebugDraw> {
+        None
+    }
+}
+
+/// opt-in debug line rendering for the controllers' debug drawing. Adds `DebugLinesPlugin` only if nothing else did
+/// so it composes with an app that already set it up, in which case `depth_test` is ignored.
+#[cfg(feature = "debug_lines")]
+pub struct FpsDebugLinesPlugin {
+    pub depth_test: bool,
+}
+
+#[cfg(feature = "debug_lines")]
+impl Default for FpsDebugLinesPlugin {
+    fn default() -> Self {
+        Self { depth_test: true }
+    }
+}
+
+#[cfg(feature = "debug_lines")]
+impl Plugin for FpsDebugLinesPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<DebugLinesPlugin>() {
+            app.add_plugin(DebugLinesPlugin::with_depth_test(self.depth_test));
+        }
+    }
+
+    fn is_unique(&self) -> bool {
+        false
+    }
+}
 
 pub fn get_circle_xz_pts(pos: Vec3, radius: f32, resolution: u32) -> Vec<Vec3> {
     let mut pts = Vec::new();
@@ -23,37 +113,60 @@ pub fn get_circle_xz_pts(pos: Vec3, radius: f32, resolution: u32) -> Vec<Vec3> {
     pts
 }
 
-impl DebugLinesExt for DebugLines {
-    fn get(&mut self) -> &mut DebugLines {
-        self
+/// line segment pairs outlining an upright cylinder: both end circles plus 4 lines connecting them
+pub fn get_cylinder_xz_pts(pos: Vec3, half_height: f32, radius: f32, resolution: u32) -> Vec<Vec3> {
+    let up = Vec3::Y * half_height;
+    let mut pts = get_circle_xz_pts(pos + up, radius, resolution);
+    pts.extend(get_circle_xz_pts(pos - up, radius, resolution));
+
+    for side in [Vec3::X, Vec3::NEG_X, Vec3::Z, Vec3::NEG_Z] {
+        pts.push(pos + up + side * radius);
+        pts.push(pos - up + side * radius);
     }
+
+    pts
 }
 
-pub trait DebugLinesExt {
-    fn get(&mut self) -> &mut DebugLines;
+/// line segment pairs for a shape cast `distance` straight down like the ground check does. The shape is outlined as
+/// a cylinder at the start and the end of the cast with a line between the two.
+pub fn get_ground_cast_pts(pos: Vec3, half_height: f32, radius: f32, distance: f32, resolution: u32) -> Vec<Vec3> {
+    let end = pos - Vec3::Y * distance;
+    let mut pts = get_cylinder_xz_pts(pos, half_height, radius, resolution);
+    pts.extend(get_cylinder_xz_pts(end, half_height, radius, resolution));
+    pts.push(pos);
+    pts.push(end);
+    pts
+}
 
+impl<T: DebugDraw + ?Sized> DebugLinesExt for T {}
+
+pub trait DebugLinesExt: DebugDraw {
     fn thick_line(&mut self, start: Vec3, end: Vec3, duration: f32) {
         self.thick_colored_line(start, end, duration, Color::WHITE);
     }
 
     fn thick_colored_line(&mut self, start: Vec3, end: Vec3, duration: f32, color: Color) {
-        let dl = self.get();
         let jitter = 0.0025;
         let a = Vec3::new(-1.0, 0.0, 1.0) * jitter;
         let b = Vec3::new(1.0, 0.0, 1.0) * jitter;
         let c = Vec3::new(-1.0, 0.0, -1.0) * jitter;
         let d = Vec3::new(-1.0, 0.0, -1.0) * jitter;
 
-        dl.line_colored(start + a, end + a, duration, color);
-        dl.line_colored(start + b, end + b, duration, color);
-        dl.line_colored(start + c, end + c, duration, color);
-        dl.line_colored(start + d, end + d, duration, color);
-        dl.line_colored(start, end, duration, color);
+        self.line_colored(start + a, end + a, duration, color);
+        self.line_colored(start + b, end + b, duration, color);
+        self.line_colored(start + c, end + c, duration, color);
+        self.line_colored(start + d, end + d, duration, color);
+        self.line_colored(start, end, duration, color);
     }
 
-    fn draw_circle_xz(&mut self, pos: Vec3, radius: f32, resolution: u32) {
-        let lines = self.get();
+    /// draws each pair of `pts` as a line, e.g. the output of the `get_*_pts` functions
+    fn draw_pts(&mut self, pts: &[Vec3], duration: f32, color: Color) {
+        for segment in pts.chunks_exact(2) {
+            self.line_colored(segment[0], segment[1], duration, color);
+        }
+    }
 
+    fn draw_circle_xz(&mut self, pos: Vec3, radius: f32, resolution: u32) {
         let angle_to_vec: fn(f32, f32) -> Vec3 =
             |angle_rads, len| Vec3::new(f32::cos(angle_rads) * len, 0.0, f32::sin(angle_rads) * len);
 
@@ -62,8 +175,76 @@ pub trait DebugLinesExt {
 
         for i in 1..resolution as u32 * 4 + 2 {
             let at = angle_to_vec(i as f32 * FRAC_PI_2 / resolution, radius);
-            lines.line(pos + last, pos + at, 10.0);
+            self.line_colored(pos + last, pos + at, 10.0, Color::WHITE);
             last = at;
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct RecordedLines(Vec<(Vec3, Vec3)>);
+
+    impl DebugDraw for RecordedLines {
+        fn line_colored(&mut self, start: Vec3, end: Vec3, _duration: f32, _color: Color) {
+            self.0.push((start, end));
+        }
+    }
+
+    #[test]
+    fn draw_pts_forwards_each_segment() {
+        let mut lines = RecordedLines::default();
+        let draw: &mut dyn DebugDraw = &mut lines;
+        draw.draw_pts(&[Vec3::ZERO, Vec3::X, Vec3::Y, Vec3::Z], 0.0, Color::RED);
+        assert_eq!(lines.0, [(Vec3::ZERO, Vec3::X), (Vec3::Y, Vec3::Z)]);
+    }
+
+    #[cfg(all(feature = "debug_lines", not(feature = "gizmos")))]
+    #[test]
+    fn active_backend_draws_into_debug_lines() {
+        let mut world = World::new();
+        let mut schedule = Schedule::default();
+        schedule.add_systems(|mut draw: FpsDebugDraw| {
+            if let Some(draw) = draw.draw() {
+                draw.line_colored(Vec3::ZERO, Vec3::X, 0.0, Color::RED);
+            }
+        });
+
+        // nothing to draw with until the DebugLines resource is there
+        schedule.run(&mut world);
+        world.init_resource::<DebugLines>();
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<DebugLines>().positions, [[0.0; 3], [1.0, 0.0, 0.0]]);
+    }
+
+    #[cfg(feature = "debug_lines")]
+    #[test]
+    fn debug_lines_plugin_registered_once() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .add_asset::<Shader>()
+            .add_asset::<Mesh>()
+            .add_plugins(DebugLinesPlugin::with_depth_test(false))
+            .add_plugins((FpsDebugLinesPlugin::default(), FpsDebugLinesPlugin { depth_test: true }));
+
+        // the user's plugin wins and neither of ours added a second one
+        assert_eq!(app.get_added_plugins::<DebugLinesPlugin>().len(), 1);
+        assert!(app.world.contains_resource::<DebugLines>());
+    }
+
+    #[test]
+    fn ground_cast_segment_count() {
+        // a circle of resolution 4 is 17 segments, a cylinder is 2 circles and 4 sides
+        assert_eq!(get_circle_xz_pts(Vec3::ZERO, 1.0, 4).len() / 2, 17);
+        assert_eq!(get_cylinder_xz_pts(Vec3::ZERO, 1.0, 0.5, 4).len() / 2, 38);
+
+        let pts = get_ground_cast_pts(Vec3::Y, 1.0, 0.5, 0.125, 4);
+        assert_eq!(pts.len() / 2, 2 * 38 + 1);
+        assert_eq!(pts[pts.len() - 1], Vec3::Y * 0.875);
+    }
+}
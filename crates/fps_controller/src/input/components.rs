@@ -16,23 +16,26 @@ pub enum InputAction {
     Slide,
     Dash,
     Shoot,
+    Use,
 }
 
 pub type InputActions = ActionState<InputAction>;
 
-#[derive(Default, Reflect)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Reflect)]
 pub struct InputState {
     pub pressed: bool,
     pub down: bool,
     pub released: bool,
 }
 
-#[derive(Component, Default, Reflect)]
+#[derive(Component, Default, Debug, Clone, PartialEq, Reflect)]
 pub struct FpsControllerInput {
     pub jump: InputState,
     pub slide: InputState,
     pub dash: InputState,
     pub shoot: InputState,
+    /// interact with the `Interactable` the player is looking at
+    pub interact: InputState,
     pub pitch: f32,
     pub yaw: f32,
     pub movement: Vec3,
@@ -42,6 +45,111 @@ pub struct FpsControllerInput {
     pub vel: Vec3,
 }
 
+impl InputState {
+    fn to_bits(self) -> u16 {
+        self.pressed as u16 | (self.down as u16) << 1 | (self.released as u16) << 2
+    }
+
+    fn from_bits(bits: u16) -> Self {
+        Self {
+            pressed: bits & 1 != 0,
+            down: bits & 2 != 0,
+            released: bits & 4 != 0,
+        }
+    }
+}
+
+impl FpsControllerInput {
+    /// size of one frame written by `write_bytes`: the packed buttons, pitch, yaw and four `Vec3`s
+    const BYTES: usize = 2 + 2 * 4 + 4 * 3 * 4;
+
+    fn write_bytes(&self, out: &mut Vec<u8>) {
+        let buttons = [self.jump, self.slide, self.dash, self.shoot, self.interact]
+            .iter()
+            .enumerate()
+            .fold(0u16, |bits, (i, state)| bits | state.to_bits() << (i * 3));
+        out.extend(buttons.to_le_bytes());
+
+        let vectors = [self.movement, self.movement_dir, self.dash_slide_dir, self.vel];
+        let vector_floats = vectors.iter().flat_map(|v| v.to_array());
+        for float in [self.pitch, self.yaw].into_iter().chain(vector_floats) {
+            out.extend(float.to_le_bytes());
+        }
+    }
+
+    /// reads a frame written by `write_bytes`, `bytes` has to be exactly `BYTES` long
+    fn read_bytes(bytes: &[u8]) -> Self {
+        let buttons = u16::from_le_bytes([bytes[0], bytes[1]]);
+        let button = |i: usize| InputState::from_bits(buttons >> (i * 3));
+
+        let floats: Vec<f32> = bytes[2..]
+            .chunks_exact(4)
+            .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+            .collect();
+        let vector = |i: usize| Vec3::from_slice(&floats[2 + i * 3..]);
+
+        Self {
+            jump: button(0),
+            slide: button(1),
+            dash: button(2),
+            shoot: button(3),
+            interact: button(4),
+            pitch: floats[0],
+            yaw: floats[1],
+            movement: vector(0),
+            movement_dir: vector(1),
+            dash_slide_dir: vector(2),
+            vel: vector(3),
+        }
+    }
+}
+
+/// records the `FpsControllerInput` of its entity every frame, e.g. to attach to a bug report or to drive a ghost
+/// with `InputPlayback` later. Replays are only deterministic when the game runs with a fixed timestep.
+#[derive(Component, Default, Debug, Clone)]
+pub struct InputRecorder {
+    pub frames: Vec<FpsControllerInput>,
+}
+
+impl InputRecorder {
+    /// the recorded frames packed into bytes, load them with `InputPlayback::from_bytes`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.frames.len() * FpsControllerInput::BYTES);
+        for frame in &self.frames {
+            frame.write_bytes(&mut bytes);
+        }
+        bytes
+    }
+}
+
+/// overwrites the `FpsControllerInput` of its entity with one recorded frame per frame. Once every frame was played
+/// the live input is left alone again.
+#[derive(Component, Default, Debug, Clone)]
+pub struct InputPlayback {
+    pub frames: Vec<FpsControllerInput>,
+    /// index of the next frame to play
+    pub frame: usize,
+}
+
+impl InputPlayback {
+    pub fn new(frames: Vec<FpsControllerInput>) -> Self {
+        Self { frames, frame: 0 }
+    }
+
+    /// loads frames written by `InputRecorder::to_bytes`. Returns `None` if `bytes` isn't a whole number of frames.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let chunks = bytes.chunks_exact(FpsControllerInput::BYTES);
+        if !chunks.remainder().is_empty() {
+            return None;
+        }
+        Some(Self::new(chunks.map(FpsControllerInput::read_bytes).collect()))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.frame >= self.frames.len()
+    }
+}
+
 #[derive(Component, Reflect)]
 pub struct FpsControllerInputConfig {
     pub mouse_sensitivity: f32,
@@ -1,5 +1,4 @@
 use bevy::prelude::*;
-use bevy_prototype_debug_lines::DebugLinesPlugin;
 use leafwing_input_manager::prelude::InputManagerPlugin;
 
 pub use self::components::*;
@@ -18,6 +17,11 @@ pub enum FpsControllerStages {
     RenderSync,
 }
 
+/// the `PostStartup` set that inserts the input map on the `FpsPlayer`. A player spawned in `Startup` is picked up
+/// automatically, one spawned in `PostStartup` needs to be ordered `.before(FpsInputSetupSet)`.
+#[derive(SystemSet, Clone, PartialEq, Eq, Debug, Hash)]
+pub struct FpsInputSetupSet;
+
 #[derive(Default)]
 pub struct FpsInputPlugin;
 
@@ -34,10 +38,41 @@ impl Plugin for FpsInputPlugin {
         );
 
         app.add_plugin(InputManagerPlugin::<InputAction>::default())
-            .add_plugin(DebugLinesPlugin::with_depth_test(true))
             .register_type::<FpsControllerInput>()
             .register_type::<FpsControllerInputConfig>()
-            .add_system(setup.on_startup().in_base_set(StartupSet::PostStartup))
-            .add_systems((controller_input, sync_rotation_input, temp_input_test).in_set(FpsControllerStages::Input));
+            // the apply_deferred makes players spawned by systems ordered before the set visible to setup
+            .add_systems(PostStartup, (apply_deferred, setup).chain().in_set(FpsInputSetupSet))
+            .add_systems((controller_input, sync_rotation_input, temp_input_test).in_set(FpsControllerStages::Input))
+            .add_systems(
+                (playback_input, record_input)
+                    .chain()
+                    .after(controller_input)
+                    .in_set(FpsControllerStages::Input),
+            )
+            .add_system(crate::player::validate_player_render_layers);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(SystemSet, Clone, PartialEq, Eq, Debug, Hash)]
+    struct SpawnPlayerSet;
+
+    fn spawn_player(mut commands: Commands) {
+        commands.spawn(FpsPlayer);
+    }
+
+    #[test]
+    fn setup_runs_after_ordered_spawn() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, FpsInputPlugin))
+            .add_systems(PostStartup, spawn_player.in_set(SpawnPlayerSet))
+            .configure_sets(PostStartup, SpawnPlayerSet.before(FpsInputSetupSet));
+        app.world.run_schedule(PostStartup);
+
+        let mut players = app.world.query_filtered::<&FpsControllerInput, With<FpsPlayer>>();
+        assert_eq!(players.iter(&app.world).count(), 1);
     }
 }
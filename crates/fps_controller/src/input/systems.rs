@@ -32,6 +32,9 @@ pub(crate) fn setup(mut commands: Commands, q: Query<Entity, With<FpsPlayer>>) {
         // shoot
         .insert(MouseButton::Left, InputAction::Shoot)
         .insert(GamepadButtonType::RightTrigger2, InputAction::Shoot)
+        // use
+        .insert(KeyCode::E, InputAction::Use)
+        .insert(GamepadButtonType::North, InputAction::Use)
         .build();
 
     commands.entity(entity).insert((
@@ -96,6 +99,10 @@ pub(crate) fn controller_input(
         input.shoot.down = actions.pressed(InputAction::Shoot);
         input.shoot.released = actions.just_released(InputAction::Shoot);
 
+        input.interact.pressed = actions.just_pressed(InputAction::Use);
+        input.interact.down = actions.pressed(InputAction::Use);
+        input.interact.released = actions.just_released(InputAction::Use);
+
         input.movement = if actions.pressed(InputAction::Move) {
             let axis_pair = actions.clamped_axis_pair(InputAction::Move).unwrap();
             let axis_pair = axis_pair.xy().normalize_or_zero();
@@ -113,6 +120,23 @@ pub(crate) fn controller_input(
     }
 }
 
+/// stores this frame's input of every entity with an `InputRecorder`
+pub(crate) fn record_input(mut query: Query<(&FpsControllerInput, &mut InputRecorder)>) {
+    for (input, mut recorder) in &mut query {
+        recorder.frames.push(input.clone());
+    }
+}
+
+/// replaces the live input with the next recorded frame
+pub(crate) fn playback_input(mut query: Query<(&mut FpsControllerInput, &mut InputPlayback)>) {
+    for (mut input, mut playback) in &mut query {
+        if let Some(frame) = playback.frames.get(playback.frame) {
+            *input = frame.clone();
+            playback.frame += 1;
+        }
+    }
+}
+
 pub(crate) fn temp_input_test(q: Query<&InputActions, With<FpsPlayer>>) {
     let Ok(input) = q.get_single() else { return; };
 
@@ -184,3 +208,56 @@ pub(crate) fn sync_rotation_input(
         ),
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn playback_reproduces_recording() {
+        let mut app = App::new();
+        app.add_systems(Update, (playback_input, record_input).chain());
+
+        let recorded = app
+            .world
+            .spawn((FpsControllerInput::default(), InputRecorder::default()))
+            .id();
+        let live_frames: Vec<FpsControllerInput> = (0..5)
+            .map(|i| FpsControllerInput {
+                jump: InputState {
+                    pressed: i == 1,
+                    down: (1..3).contains(&i),
+                    released: i == 3,
+                },
+                yaw: i as f32 * 0.01,
+                pitch: -0.25,
+                movement: Vec3::new(0.0, 0.0, i as f32 / 4.0),
+                ..default()
+            })
+            .collect();
+
+        for frame in &live_frames {
+            *app.world.get_mut::<FpsControllerInput>(recorded).unwrap() = frame.clone();
+            app.update();
+        }
+
+        let bytes = app.world.get::<InputRecorder>(recorded).unwrap().to_bytes();
+        let playback = InputPlayback::from_bytes(&bytes).unwrap();
+        assert!(InputPlayback::from_bytes(&bytes[1..]).is_none());
+
+        // the replaying entity records as well so every played frame can be compared
+        let replayed = app
+            .world
+            .spawn((FpsControllerInput::default(), playback, InputRecorder::default()))
+            .id();
+        for _ in 0..live_frames.len() + 2 {
+            app.update();
+        }
+
+        assert!(app.world.get::<InputPlayback>(replayed).unwrap().is_finished());
+        let replayed_frames = &app.world.get::<InputRecorder>(replayed).unwrap().frames;
+        assert_eq!(replayed_frames[..live_frames.len()], live_frames[..]);
+        // the last frame sticks around once playback is done
+        assert_eq!(replayed_frames.last(), live_frames.last());
+    }
+}
@@ -1,6 +1,9 @@
 pub mod character_controller;
 pub mod input;
+pub mod look_target;
 pub mod mod_fps;
+pub mod moving_platform;
+pub mod player;
 pub mod ultrakill;
 pub mod camera_shake;
 pub mod time_controller;
@@ -11,3 +14,6 @@ pub use utils::*;
 mod debug_lines;
 pub use debug_lines::*;
 
+#[cfg(test)]
+mod test_app;
+
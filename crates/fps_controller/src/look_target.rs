@@ -0,0 +1,182 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::input::{FpsControllerInput, FpsPlayer};
+
+/// marks a collider the player can use. Pressing use while looking at it within `LookTarget::interact_distance` sends
+/// an `InteractEvent`.
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct Interactable;
+
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct InteractEvent {
+    pub target: Entity,
+}
+
+/// add to the render camera to track what the player is looking at, e.g. for interaction prompts. Each frame a ray is
+/// cast forward from the camera and the closest hit within `max_distance` is stored in `entity` and `distance`.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct LookTarget {
+    pub max_distance: f32,
+    /// how close an `Interactable` has to be to be used. Can be shorter than `max_distance` so prompts show up before
+    /// the player is in range.
+    pub interact_distance: f32,
+    /// only colliders whose memberships intersect these groups are hit
+    pub filter: Group,
+    /// collider that is looked at, `None` if nothing is in range
+    pub entity: Option<Entity>,
+    pub distance: f32,
+}
+
+impl Default for LookTarget {
+    fn default() -> Self {
+        Self {
+            max_distance: 3.0,
+            interact_distance: 2.0,
+            filter: Group::ALL,
+            entity: None,
+            distance: 0.0,
+        }
+    }
+}
+
+/// casts a ray forward from every `LookTarget`. Sensors and players are ignored so the ray doesn't stop at the
+/// player's own collider.
+pub fn update_look_targets(
+    physics_context: Res<RapierContext>,
+    players: Query<Entity, With<FpsPlayer>>,
+    mut query: Query<(&GlobalTransform, &mut LookTarget)>,
+) {
+    for (transform, mut look_target) in &mut query {
+        let not_player = |entity| !players.contains(entity);
+        let filter = QueryFilter::new()
+            .exclude_sensors()
+            .groups(CollisionGroups::new(Group::ALL, look_target.filter))
+            .predicate(&not_player);
+
+        let hit = physics_context.cast_ray(
+            transform.translation(),
+            transform.forward(),
+            look_target.max_distance,
+            true,
+            filter,
+        );
+        look_target.entity = hit.map(|(entity, _)| entity);
+        look_target.distance = hit.map_or(0.0, |(_, toi)| toi);
+    }
+}
+
+/// sends an `InteractEvent` for every looked at `Interactable` in range when a player presses use
+pub fn send_interact_events(
+    players: Query<&FpsControllerInput, With<FpsPlayer>>,
+    look_targets: Query<&LookTarget>,
+    interactables: Query<(), With<Interactable>>,
+    mut events: EventWriter<InteractEvent>,
+) {
+    if !players.iter().any(|input| input.interact.pressed) {
+        return;
+    }
+
+    for look_target in &look_targets {
+        let Some(target) = look_target.entity else {
+            continue;
+        };
+
+        if interactables.contains(target) && look_target.distance <= look_target.interact_distance {
+            events.send(InteractEvent { target });
+        }
+    }
+}
+
+pub struct LookTargetPlugin;
+
+impl Plugin for LookTargetPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<InteractEvent>()
+            .add_systems((update_look_targets, send_interact_events).chain());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_app::{step, test_world};
+
+    #[test]
+    fn records_collider_in_front() {
+        // the camera starts out inside the player's capsule
+        let (mut app, player) = test_world(Vec3::new(0.0, 1.0, 0.0));
+        app.world.entity_mut(player).insert(FpsPlayer);
+        app.add_systems(Update, update_look_targets);
+
+        let crate_entity = app
+            .world
+            .spawn((
+                Collider::cuboid(0.5, 0.5, 0.5),
+                TransformBundle::from(Transform::from_xyz(0.0, 1.5, -3.0)),
+            ))
+            .id();
+        // ignored by the filter, in front of the crate
+        app.world.spawn((
+            Collider::cuboid(0.5, 0.5, 0.1),
+            CollisionGroups::new(Group::GROUP_2, Group::ALL),
+            TransformBundle::from(Transform::from_xyz(0.0, 1.5, -1.5)),
+        ));
+        let camera = app
+            .world
+            .spawn((
+                LookTarget {
+                    max_distance: 5.0,
+                    filter: Group::GROUP_1,
+                    ..default()
+                },
+                TransformBundle::from(Transform::from_xyz(0.0, 1.5, 0.0)),
+            ))
+            .id();
+
+        step(&mut app, 3);
+        let look_target = app.world.get::<LookTarget>(camera).unwrap();
+        assert_eq!(look_target.entity, Some(crate_entity));
+        assert!((look_target.distance - 2.5).abs() < 1e-4, "{look_target:?}");
+
+        app.world.get_mut::<LookTarget>(camera).unwrap().max_distance = 2.0;
+        step(&mut app, 1);
+        assert_eq!(app.world.get::<LookTarget>(camera).unwrap().entity, None);
+    }
+
+    #[test]
+    fn use_sends_interact_event() {
+        let mut app = App::new();
+        app.add_event::<InteractEvent>()
+            .add_systems(Update, send_interact_events);
+
+        let door = app.world.spawn(Interactable).id();
+        let wall = app.world.spawn_empty().id();
+        let player = app.world.spawn((FpsPlayer, FpsControllerInput::default())).id();
+        let camera = app
+            .world
+            .spawn(LookTarget {
+                entity: Some(door),
+                distance: 1.5,
+                ..default()
+            })
+            .id();
+
+        let send_use = |app: &mut App, target: Entity, distance: f32| {
+            let mut look_target = app.world.get_mut::<LookTarget>(camera).unwrap();
+            look_target.entity = Some(target);
+            look_target.distance = distance;
+            let mut input = app.world.get_mut::<FpsControllerInput>(player).unwrap();
+            input.interact.pressed = true;
+            app.update();
+
+            let events = app.world.resource::<Events<InteractEvent>>();
+            events.iter_current_update_events().copied().collect::<Vec<_>>()
+        };
+
+        assert_eq!(send_use(&mut app, door, 1.5), [InteractEvent { target: door }]);
+        // not interactable or out of range
+        assert!(send_use(&mut app, wall, 1.5).is_empty());
+        assert!(send_use(&mut app, door, 2.5).is_empty());
+    }
+}
@@ -2,13 +2,29 @@ use std::f32::consts::{FRAC_PI_2, PI, TAU};
 
 use bevy::{input::mouse::MouseMotion, math::Vec3Swizzles, prelude::*};
 use bevy_rapier3d::prelude::*;
+use valve_maps::bevy::ValveMapPlayerSpawned;
+
+use crate::{
+    jump::JumpTimers,
+    math::{clamp_delta, move_towards},
+    physics::{self_filter_all_bodies, sync_rapier_gravity, RapierGravity},
+};
 
 #[derive(Default)]
 pub struct FPSControllerPlugin;
 
 impl Plugin for FPSControllerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, (controller_input, controller_move, controller_render));
+        app.add_event::<ValveMapPlayerSpawned>().add_systems(
+            Update,
+            (
+                sync_spawn_yaw.before(controller_input),
+                controller_input,
+                sync_rapier_gravity::<FpsController>.before(controller_move),
+                controller_move,
+                controller_render,
+            ),
+        );
     }
 }
 
@@ -26,6 +42,8 @@ pub struct FpsControllerInput {
     pub crouch: bool,
     pub pitch: f32,
     pub yaw: f32,
+    /// -1 to 1 per axis. Keyboard input is digital, a gamepad stick carries its tilt so a partly tilted stick walks
+    /// slower.
     pub movement: Vec3,
 }
 
@@ -40,7 +58,11 @@ pub enum MoveMode {
 pub struct FpsController {
     pub move_mode: MoveMode,
     pub radius: f32,
+    /// downward acceleration used by the movement math. The player's rigid body runs with `GravityScale(0.0)` so this
+    /// is the only gravity it gets, independent of `RapierConfiguration::gravity` unless `use_rapier_gravity` is set.
     pub gravity: f32,
+    /// copy `gravity` from `RapierConfiguration::gravity` every frame so the player falls like dynamic objects do
+    pub use_rapier_gravity: bool,
     pub walk_speed: f32,
     pub run_speed: f32,
     pub forward_speed: f32,
@@ -55,6 +77,11 @@ pub struct FpsController {
     pub traction_normal_cutoff: f32,
     pub friction_speed_cutoff: f32,
     pub jump_speed: f32,
+    /// how long a jump pressed in the air is remembered so it still fires when landing
+    pub jump_buffer_duration: f32,
+    /// how long after walking off a ledge the player can still jump
+    pub coyote_timer_duration: f32,
+    pub jump_timers: JumpTimers,
     pub fly_speed: f32,
     pub crouched_speed: f32,
     pub crouch_speed: f32,
@@ -71,6 +98,16 @@ pub struct FpsController {
     pub sensitivity: f32,
     pub enable_input: bool,
     pub step_offset: f32,
+    /// how far the view may rise per frame after stepping up. The body still steps up instantly but the camera climbs
+    /// over a couple of frames so stairs don't pop it. 0 disables the smoothing.
+    pub step_smoothing: f32,
+    /// how far the view still lags below the body after stepping up, caught up by `controller_render`
+    pub step_view_lag: f32,
+    /// face the `angle` of the valve map spawn point when warped to it as a `ValveMapPlayer`
+    pub snap_to_spawn_yaw: bool,
+    /// upper bound for the delta time used by the movement math so a long frame doesn't launch the player. 0 disables
+    /// the clamp.
+    pub max_delta: f32,
     pub key_forward: KeyCode,
     pub key_back: KeyCode,
     pub key_left: KeyCode,
@@ -83,6 +120,16 @@ pub struct FpsController {
     pub key_crouch: KeyCode,
 }
 
+impl RapierGravity for FpsController {
+    fn use_rapier_gravity(&self) -> bool {
+        self.use_rapier_gravity
+    }
+
+    fn set_gravity(&mut self, gravity: f32) {
+        self.gravity = gravity;
+    }
+}
+
 impl Default for FpsController {
     fn default() -> Self {
         Self {
@@ -91,6 +138,7 @@ impl Default for FpsController {
             fly_speed: 10.0,
             fast_fly_speed: 30.0,
             gravity: 23.0,
+            use_rapier_gravity: false,
             walk_speed: 9.0,
             run_speed: 14.0,
             forward_speed: 30.0,
@@ -114,7 +162,14 @@ impl Default for FpsController {
             ground_tick: 0,
             stop_speed: 1.0,
             jump_speed: 10.5,
+            jump_buffer_duration: 0.1,
+            coyote_timer_duration: 0.2,
+            jump_timers: JumpTimers::default(),
             step_offset: 0.0,
+            step_smoothing: 0.1,
+            step_view_lag: 0.0,
+            snap_to_spawn_yaw: true,
+            max_delta: 1.0 / 30.0,
             enable_input: true,
             key_forward: KeyCode::W,
             key_back: KeyCode::S,
@@ -135,9 +190,20 @@ const ANGLE_EPSILON: f32 = 0.001953125;
 
 pub fn controller_input(
     key_input: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
     mut mouse_events: EventReader<MouseMotion>,
     mut query: Query<(&mut FpsController, &mut FpsControllerInput)>,
 ) {
+    // the first gamepad with its left stick out of the dead zone
+    let stick = gamepads
+        .iter()
+        .map(|gamepad| {
+            let axis = |axis_type| gamepad_axes.get(GamepadAxis::new(gamepad, axis_type)).unwrap_or(0.0);
+            Vec2::new(axis(GamepadAxisType::LeftStickX), axis(GamepadAxisType::LeftStickY))
+        })
+        .find(|stick| *stick != Vec2::ZERO);
+
     for (mut controller, mut input) in query.iter_mut() {
         let mut mouse_delta = Vec2::ZERO;
         for mouse_event in mouse_events.iter() {
@@ -159,12 +225,34 @@ pub fn controller_input(
             get_axis(&key_input, controller.key_up, controller.key_down),
             get_axis(&key_input, controller.key_forward, controller.key_back),
         );
+        if let Some(stick) = stick.filter(|_| input.movement.x == 0.0 && input.movement.z == 0.0) {
+            let stick = stick.clamp_length_max(1.0);
+            input.movement.x = stick.x;
+            input.movement.z = stick.y;
+        }
         input.sprint = key_input.pressed(controller.key_sprint);
         input.jump = key_input.just_pressed(controller.key_jump);
         input.crouch = key_input.pressed(controller.key_crouch);
     }
 }
 
+/// the view of `mod_fps` controllers follows `yaw` rather than the `Transform`, so a spawn point's angle has to be
+/// copied over when valve_maps warps the player to it
+pub fn sync_spawn_yaw(
+    mut events: EventReader<ValveMapPlayerSpawned>,
+    mut query: Query<(&mut FpsController, &mut FpsControllerInput)>,
+) {
+    for spawned in events.iter() {
+        let Ok((mut controller, mut input)) = query.get_mut(spawned.entity) else {
+            continue;
+        };
+        if controller.snap_to_spawn_yaw {
+            controller.yaw = spawned.yaw;
+            input.yaw = spawned.yaw;
+        }
+    }
+}
+
 pub fn controller_move(
     time: Res<Time>,
     physics_context: Res<RapierContext>,
@@ -177,9 +265,8 @@ pub fn controller_move(
         &mut Velocity,
     )>,
 ) {
-    let dt = time.delta_seconds();
-
     for (entity, input, mut controller, mut collider, mut transform, mut velocity) in query.iter_mut() {
+        let dt = clamp_delta(time.delta_seconds(), controller.max_delta);
         if let Some(capsule) = collider.as_capsule() {
             // Capsule cast downwards to find ground
             // Better than a ray cast as it handles when you are near the edge of a surface
@@ -187,7 +274,7 @@ pub fn controller_move(
             let cast_capsule =
                 Collider::capsule(capsule.segment.a.into(), capsule.segment.b.into(), capsule.radius * 0.9);
             // Avoid self collisions
-            let filter = QueryFilter::default().exclude_rigid_body(entity).exclude_sensors();
+            let filter = self_filter_all_bodies(entity);
             let ground_cast = physics_context.cast_shape(
                 transform.translation,
                 transform.rotation,
@@ -214,7 +301,16 @@ pub fn controller_move(
             } else {
                 controller.walk_speed
             };
-            wish_speed = f32::min(wish_speed, max_speed);
+            wish_speed = f32::min(wish_speed, analog_max_speed(max_speed, input.movement));
+
+            let on_ground =
+                ground_cast.is_some_and(|(_, toi)| Vec3::dot(toi.normal1, Vec3::Y) > controller.traction_normal_cutoff);
+            let (coyote_timer_duration, jump_buffer_duration) =
+                (controller.coyote_timer_duration, controller.jump_buffer_duration);
+            controller
+                .jump_timers
+                .tick(dt, on_ground, input.jump, coyote_timer_duration, jump_buffer_duration);
+            let jump_requested = controller.jump_timers.jump_requested(input.jump);
 
             if let Some((_, toi)) = ground_cast {
                 let has_traction = Vec3::dot(toi.normal1, Vec3::Y) > controller.traction_normal_cutoff;
@@ -246,8 +342,9 @@ pub fn controller_move(
                     let linvel = velocity.linvel;
                     velocity.linvel -= Vec3::dot(linvel, toi.normal1) * toi.normal1;
 
-                    if input.jump {
+                    if jump_requested {
                         velocity.linvel.y = controller.jump_speed;
+                        controller.jump_timers.consume();
                     }
                 }
 
@@ -273,6 +370,11 @@ pub fn controller_move(
                     velocity.linvel.x *= ratio;
                     velocity.linvel.z *= ratio;
                 }
+
+                if jump_requested && controller.jump_timers.can_coyote_jump(on_ground) {
+                    velocity.linvel.y = controller.jump_speed;
+                    controller.jump_timers.consume();
+                }
             }
 
             // Crouching
@@ -299,14 +401,22 @@ pub fn controller_move(
                     filter,
                 );
                 if let Some((_, hit)) = cast {
-                    transform.translation.y += controller.step_offset * 1.0625 - hit.toi;
+                    let rise = controller.step_offset * 1.0625 - hit.toi;
+                    transform.translation.y += rise;
                     transform.translation += cast_offset;
+                    controller.step_view_lag += rise;
                 }
             }
         }
     }
 }
 
+/// scales `max_speed` by how far the movement input is pushed. Digital input is always at full speed, diagonals
+/// included.
+pub fn analog_max_speed(max_speed: f32, movement: Vec3) -> f32 {
+    max_speed * movement.xz().length().min(1.0)
+}
+
 fn acceleration(wish_direction: Vec3, wish_speed: f32, acceleration: f32, velocity: Vec3, dt: f32) -> Vec3 {
     let velocity_projection = Vec3::dot(velocity, wish_direction);
     let add_speed = wish_speed - velocity_projection;
@@ -331,18 +441,126 @@ fn get_axis(key_input: &Res<Input<KeyCode>>, key_pos: KeyCode, key_neg: KeyCode)
 }
 
 pub fn controller_render(
-    logical_query: Query<(&Transform, &Collider, &FpsController), With<LogicalPlayer>>,
+    mut logical_query: Query<(&Transform, &Collider, &mut FpsController), With<LogicalPlayer>>,
     mut render_query: Query<&mut Transform, (With<RenderPlayer>, Without<LogicalPlayer>)>,
 ) {
     // TODO: inefficient O(N^2) loop, use hash map?
-    for (logical_transform, collider, controller) in logical_query.iter() {
+    for (logical_transform, collider, mut controller) in logical_query.iter_mut() {
+        if controller.step_view_lag > 0.0 {
+            controller.step_view_lag = if controller.step_smoothing > 0.0 {
+                move_towards(controller.step_view_lag, 0.0, controller.step_smoothing)
+            } else {
+                0.0
+            };
+        }
+
         if let Some(capsule) = collider.as_capsule() {
             for mut render_transform in render_query.iter_mut() {
                 // TODO: let this be more configurable
-                let camera_height = capsule.segment().b().y + capsule.radius() * 0.75;
+                let camera_height = capsule.segment().b().y + capsule.radius() * 0.75 - controller.step_view_lag;
                 render_transform.translation = logical_transform.translation + Vec3::Y * camera_height;
                 render_transform.rotation = Quat::from_euler(EulerRot::YXZ, controller.yaw, controller.pitch, 0.0);
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn half_tilted_stick_walks_at_half_speed() {
+        assert_eq!(analog_max_speed(9.0, Vec3::new(0.0, 0.0, 0.5)), 4.5);
+        assert_eq!(analog_max_speed(9.0, Vec3::new(0.0, 0.0, 1.0)), 9.0);
+        // digital diagonals don't go faster than straight movement
+        assert_eq!(analog_max_speed(9.0, Vec3::new(1.0, 0.0, 1.0)), 9.0);
+    }
+
+    #[test]
+    fn step_up_view_rises_at_most_max_step_per_frame() {
+        let mut world = World::new();
+        let player = world
+            .spawn((
+                LogicalPlayer,
+                Transform::from_xyz(0.0, 1.0, 0.0),
+                Collider::capsule(Vec3::Y * -0.5, Vec3::Y * 0.5, 0.5),
+                FpsController::default(),
+            ))
+            .id();
+        let camera = world.spawn((RenderPlayer, Transform::default())).id();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(controller_render);
+        let mut camera_y = |world: &mut World| {
+            schedule.run(world);
+            world.get::<Transform>(camera).unwrap().translation.y
+        };
+        let rest = camera_y(&mut world);
+
+        // what the step offset code does when climbing a 0.25 step
+        world.get_mut::<Transform>(player).unwrap().translation.y += 0.25;
+        world.get_mut::<FpsController>(player).unwrap().step_view_lag += 0.25;
+
+        let max_step_per_frame = FpsController::default().step_smoothing;
+        let mut previous = rest;
+        for _ in 0..3 {
+            let y = camera_y(&mut world);
+            let rise = y - previous;
+            assert!(rise > 0.0 && rise <= max_step_per_frame + 1e-5, "view rose {rise}");
+            previous = y;
+        }
+        assert!((previous - rest - 0.25).abs() < 1e-5);
+    }
+
+    #[test]
+    fn valve_map_spawn_sets_controller_yaw() {
+        use valve_maps::{
+            bevy::{loader::ValveMapEntity, ValveMap, ValveMapBundle, ValveMapPlayer, ValveMapPlugin},
+            convert::UpAxis,
+            formats::shared::Fields,
+        };
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), ValveMapPlugin))
+            .add_asset::<Mesh>()
+            .add_asset::<StandardMaterial>()
+            .add_systems(Update, sync_spawn_yaw);
+
+        let mut fields = Fields::default();
+        fields.insert("classname".into(), "spawn_point".into());
+        fields.insert("origin".into(), "0 0 0".into());
+        fields.insert("angle".into(), "180".into());
+        let map = ValveMap {
+            entities: vec![ValveMapEntity {
+                fields,
+                collision_geometry: Vec::new(),
+                concave_collision: None,
+                up_axis: UpAxis::Z,
+                visual_geometry: Vec::new(),
+            }],
+            worldspawn: default(),
+        };
+        let map = app.world.resource_mut::<Assets<ValveMap>>().add(map);
+        app.world.spawn(ValveMapBundle { map, ..default() });
+        let player = app
+            .world
+            .spawn((
+                ValveMapPlayer,
+                Transform::default(),
+                FpsController::default(),
+                FpsControllerInput::default(),
+            ))
+            .id();
+
+        app.update();
+        app.update();
+
+        let spawn_yaw = 90f32.to_radians();
+        let rotation = app.world.get::<Transform>(player).unwrap().rotation;
+        let (transform_yaw, _, _) = rotation.to_euler(EulerRot::YXZ);
+        assert!((transform_yaw - spawn_yaw).abs() < 1e-5);
+        assert_eq!(app.world.get::<FpsController>(player).unwrap().yaw, spawn_yaw);
+        assert_eq!(app.world.get::<FpsControllerInput>(player).unwrap().yaw, spawn_yaw);
+    }
+}
@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::Velocity;
+
+/// moves a kinematic velocity based body through `waypoints`, looping back to the first one after the last. The body
+/// needs a `Velocity` and `RigidBody::KinematicVelocityBased` so Rapier carries anything standing on it along.
+#[derive(Component, Debug, Clone)]
+pub struct MovingPlatform {
+    pub waypoints: Vec<Vec3>,
+    /// units per second
+    pub speed: f32,
+    /// index of the waypoint the platform is moving towards
+    pub current: usize,
+}
+
+impl MovingPlatform {
+    pub fn new(speed: f32, waypoints: &[Vec3]) -> Self {
+        Self {
+            waypoints: waypoints.to_owned(),
+            speed,
+            current: 0,
+        }
+    }
+
+    /// returns the velocity that moves a platform at `position` towards the current waypoint. When the waypoint would
+    /// be reached within `dt` the platform moves on to the next one.
+    pub fn velocity(&mut self, position: Vec3, dt: f32) -> Vec3 {
+        let Some(target) = self.waypoints.get(self.current) else {
+            return Vec3::ZERO;
+        };
+
+        let to_target = *target - position;
+        if to_target.length() <= self.speed * dt {
+            self.current = (self.current + 1) % self.waypoints.len();
+        }
+        to_target.normalize_or_zero() * self.speed
+    }
+}
+
+pub fn move_platforms(time: Res<Time>, mut query: Query<(&mut MovingPlatform, &GlobalTransform, &mut Velocity)>) {
+    for (mut platform, transform, mut velocity) in &mut query {
+        velocity.linvel = platform.velocity(transform.translation(), time.delta_seconds());
+    }
+}
+
+pub struct MovingPlatformPlugin;
+
+impl Plugin for MovingPlatformPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(move_platforms);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn advances_and_loops_waypoints() {
+        let mut platform = MovingPlatform::new(2.0, &[Vec3::X * 4.0, Vec3::ZERO]);
+
+        assert_eq!(platform.velocity(Vec3::ZERO, 0.5), Vec3::X * 2.0);
+        assert_eq!(platform.current, 0);
+
+        // close enough to reach the waypoint this frame
+        platform.velocity(Vec3::X * 3.5, 0.5);
+        assert_eq!(platform.current, 1);
+        assert_eq!(platform.velocity(Vec3::X * 4.0, 0.5), Vec3::X * -2.0);
+
+        platform.velocity(Vec3::X * 0.5, 0.5);
+        assert_eq!(platform.current, 0);
+
+        assert_eq!(MovingPlatform::new(2.0, &[]).velocity(Vec3::ZERO, 0.5), Vec3::ZERO);
+    }
+}
@@ -0,0 +1,200 @@
+use bevy::{
+    core_pipeline::clear_color::ClearColorConfig,
+    prelude::*,
+    render::{camera::Viewport, view::RenderLayers},
+};
+
+use crate::{
+    camera_shake::Shake3d,
+    input::{FpsPlayer, RenderPlayer},
+    ultrakill::FpsControllerBundle,
+};
+
+/// settings for [`spawn_fps_player`]
+pub struct FpsPlayerConfig {
+    /// where the logical player is spawned
+    pub transform: Transform,
+    /// color of the capsule that represents the logical player
+    pub color: Color,
+    /// vertical field of view of the render camera in degrees
+    pub fov: f32,
+    /// height of the render camera above the logical player's origin
+    pub camera_height: f32,
+    /// the `RenderLayers` layer the logical player lives on when `hide_from_first_person` is set. Can't be 0, the
+    /// default layer everything else is drawn on.
+    pub player_layer: u8,
+    /// moves the logical player to `player_layer` and removes that layer from the render camera so the capsule isn't
+    /// drawn in first person
+    pub hide_from_first_person: bool,
+    /// when set, a 3rd person picture-in-picture camera trailing the player is rendered into this viewport
+    pub pip_viewport: Option<Viewport>,
+}
+
+impl Default for FpsPlayerConfig {
+    fn default() -> Self {
+        Self {
+            transform: Transform::default(),
+            color: Color::rgb(0.8, 0.1, 0.9),
+            fov: 100.0,
+            camera_height: 1.0,
+            player_layer: 1,
+            hide_from_first_person: true,
+            pip_viewport: None,
+        }
+    }
+}
+
+/// the `RenderLayers` of the logical player and of its render camera. When hidden, the player is moved to
+/// `player_layer` and the camera sees the default layer without it.
+///
+/// # Panics
+///
+/// Panics if hiding the player on layer 0, the camera would then see no layer at all and render nothing.
+pub fn first_person_render_layers(player_layer: u8, hide_from_first_person: bool) -> (RenderLayers, RenderLayers) {
+    if hide_from_first_person {
+        assert!(
+            player_layer != 0,
+            "the logical player can't be hidden on layer 0, the render camera would see nothing. Use another \
+             `player_layer`."
+        );
+        (
+            RenderLayers::layer(player_layer),
+            RenderLayers::default().without(player_layer),
+        )
+    } else {
+        (RenderLayers::default(), RenderLayers::default())
+    }
+}
+
+/// spawns the logical player with the shake node and render camera as children:
+///
+/// ```text
+/// Player (FpsControllerBundle, ValveMapPlayer, capsule mesh)
+/// └── Shake3d
+///     └── RenderPlayer (Camera3d)
+///         └── PiP Camera (optional)
+/// ```
+///
+/// returns the logical player entity
+pub fn spawn_fps_player(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    config: FpsPlayerConfig,
+) -> Entity {
+    let (player_layers, camera_layers) = first_person_render_layers(config.player_layer, config.hide_from_first_person);
+
+    commands
+        .spawn((
+            (
+                Name::new("Player"),
+                FpsControllerBundle::default(),
+                valve_maps::bevy::ValveMapPlayer,
+                player_layers,
+            ),
+            PbrBundle {
+                mesh: meshes.add(shape::Capsule::default().into()),
+                material: materials.add(config.color.into()),
+                transform: config.transform,
+                ..default()
+            },
+        ))
+        .with_children(|builder| {
+            builder
+                .spawn((Name::new("Shake"), Shake3d::default(), SpatialBundle::default()))
+                .with_children(|builder| {
+                    let mut camera = builder.spawn((
+                        Name::new("Render Camera"),
+                        RenderPlayer,
+                        Camera3dBundle {
+                            transform: Transform::from_xyz(0.0, config.camera_height, 0.0),
+                            projection: Projection::Perspective(PerspectiveProjection {
+                                fov: config.fov.to_radians(),
+                                ..default()
+                            }),
+                            ..default()
+                        },
+                        camera_layers,
+                    ));
+
+                    let Some(viewport) = config.pip_viewport else {
+                        return;
+                    };
+
+                    // 3rd person view trailing a bit behind the player
+                    camera.with_children(|builder| {
+                        builder.spawn((
+                            Name::new("PiP Camera"),
+                            Camera3dBundle {
+                                transform: Transform::from_xyz(0.0, 0.0, 15.0),
+                                camera: Camera {
+                                    order: 1, // after the render camera
+                                    viewport: Some(viewport),
+                                    ..default()
+                                },
+                                camera_3d: Camera3d {
+                                    clear_color: ClearColorConfig::None,
+                                    ..default()
+                                },
+                                ..default()
+                            },
+                            UiCameraConfig { show_ui: false },
+                            RenderLayers::default().with(config.player_layer),
+                        ));
+                    });
+                });
+        })
+        .id()
+}
+
+/// warns when a render camera would show the logical player it belongs to even though the player was moved off the
+/// default layer to hide it from first person view
+pub fn validate_player_render_layers(
+    cameras: Query<
+        (Entity, Option<&RenderLayers>),
+        (With<RenderPlayer>, Or<(Added<RenderPlayer>, Changed<RenderLayers>)>),
+    >,
+    players: Query<Option<&RenderLayers>, With<FpsPlayer>>,
+    parents: Query<&Parent>,
+) {
+    for (camera, camera_layers) in &cameras {
+        let Some(player_layers) = parents.iter_ancestors(camera).find_map(|e| players.get(e).ok()) else {
+            continue;
+        };
+
+        let player_layers = player_layers.copied().unwrap_or_default();
+        let camera_layers = camera_layers.copied().unwrap_or_default();
+        if player_layers != RenderLayers::default() && camera_layers.intersects(&player_layers) {
+            warn!(
+                "render camera {camera:?} can see its own logical player ({player_layers:?}). Remove the player's layer \
+                 from the camera's RenderLayers to hide it from first person view."
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_person_layers_hide_player() {
+        let (player, camera) = first_person_render_layers(1, true);
+        assert!(!camera.intersects(&player));
+        // a 3rd person camera that adds the layer back sees the player
+        assert!(RenderLayers::default().with(1).intersects(&player));
+
+        let (player, camera) = first_person_render_layers(1, false);
+        assert!(camera.intersects(&player));
+
+        // layer 0 is fine as long as the player isn't hidden
+        let (player, camera) = first_person_render_layers(0, false);
+        assert!(camera.intersects(&player));
+    }
+
+    #[test]
+    #[should_panic(expected = "can't be hidden on layer 0")]
+    fn hiding_player_on_default_layer_panics() {
+        first_person_render_layers(0, true);
+    }
+}
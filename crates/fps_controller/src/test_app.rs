@@ -0,0 +1,141 @@
+//! headless app for end to end controller tests. `test_world` builds a Rapier world with a floor and a controller,
+//! `step` advances it a fixed number of frames.
+
+use std::time::Duration;
+
+use bevy::{prelude::*, scene::ScenePlugin, time::TimeUpdateStrategy};
+use bevy_rapier3d::prelude::*;
+
+use crate::{
+    mod_fps::{controller_move, FpsController, FpsControllerInput},
+    physics::sync_rapier_gravity,
+};
+
+/// every frame advances time and the physics by exactly this much so tests are deterministic
+pub const TIMESTEP: f32 = 1.0 / 60.0;
+
+/// minimal App with Rapier, a floor whose top is at y = 0 and a `mod_fps` controller spawned at `player_position`.
+/// Returns the app and the controller entity.
+pub fn test_world(player_position: Vec3) -> (App, Entity) {
+    let mut app = App::new();
+    // the asset and scene plugins are only there for Rapier's async collider systems
+    app.add_plugins((MinimalPlugins, TransformPlugin, HierarchyPlugin))
+        .add_plugins((AssetPlugin::default(), ScenePlugin))
+        .add_asset::<Mesh>()
+        .add_plugins(RapierPhysicsPlugin::<NoUserData>::default())
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(TIMESTEP)))
+        .insert_resource(RapierConfiguration {
+            timestep_mode: TimestepMode::Fixed {
+                dt: TIMESTEP,
+                substeps: 1,
+            },
+            ..default()
+        })
+        .add_systems(Update, (sync_rapier_gravity::<FpsController>, controller_move).chain());
+
+    app.world.spawn((
+        Collider::cuboid(20.0, 0.5, 20.0),
+        TransformBundle::from(Transform::from_xyz(0.0, -0.5, 0.0)),
+    ));
+
+    // same setup as the mod_fps example
+    let player = app
+        .world
+        .spawn((
+            Collider::capsule(Vec3::Y * -0.5, Vec3::Y * 0.5, 0.5),
+            Friction {
+                coefficient: 0.0,
+                combine_rule: CoefficientCombineRule::Min,
+            },
+            Restitution {
+                coefficient: 0.0,
+                combine_rule: CoefficientCombineRule::Min,
+            },
+            Velocity::zero(),
+            RigidBody::Dynamic,
+            Sleeping::disabled(),
+            LockedAxes::ROTATION_LOCKED,
+            AdditionalMassProperties::Mass(1.0),
+            GravityScale(0.0),
+            Ccd { enabled: true },
+            TransformBundle::from(Transform::from_translation(player_position)),
+            FpsControllerInput::default(),
+            FpsController::default(),
+        ))
+        .id();
+
+    (app, player)
+}
+
+/// runs `frames` updates of `app`
+pub fn step(app: &mut App, frames: usize) {
+    for _ in 0..frames {
+        app.update();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dropped_controller_rests_on_floor() {
+        let (mut app, player) = test_world(Vec3::new(0.0, 3.0, 0.0));
+        step(&mut app, 180);
+
+        let translation = app.world.get::<Transform>(player).unwrap().translation;
+        let velocity = app.world.get::<Velocity>(player).unwrap().linvel;
+
+        // the upright capsule reaches 1 unit below its origin
+        assert!((translation.y - 1.0).abs() < 0.1, "player at {translation}");
+        assert!(velocity.y.abs() < 0.1, "player still moving at {velocity}");
+    }
+
+    #[test]
+    fn controller_uses_rapier_gravity() {
+        let (mut app, player) = test_world(Vec3::new(0.0, 10.0, 0.0));
+        app.world.resource_mut::<RapierConfiguration>().gravity = Vec3::NEG_Y * 5.0;
+
+        step(&mut app, 1);
+        assert_eq!(app.world.get::<FpsController>(player).unwrap().gravity, 23.0);
+
+        app.world.get_mut::<FpsController>(player).unwrap().use_rapier_gravity = true;
+        step(&mut app, 1);
+        assert_eq!(app.world.get::<FpsController>(player).unwrap().gravity, 5.0);
+
+        // falling one more frame from rest picks up exactly one frame of the synced gravity
+        app.world.get_mut::<Velocity>(player).unwrap().linvel = Vec3::ZERO;
+        step(&mut app, 1);
+        let velocity = app.world.get::<Velocity>(player).unwrap().linvel;
+        assert!((velocity.y + 5.0 * TIMESTEP).abs() < 1e-4, "velocity {velocity}");
+    }
+
+    /// drops the controller and taps jump once the player is below `press_height`. Returns true if it jumped after
+    /// landing.
+    fn jumps_after_pressing_at(press_height: f32) -> bool {
+        let (mut app, player) = test_world(Vec3::new(0.0, 3.0, 0.0));
+
+        let mut pressed = false;
+        for _ in 0..120 {
+            let falling = app.world.get::<Velocity>(player).unwrap().linvel.y < 0.0;
+            let below = app.world.get::<Transform>(player).unwrap().translation.y < press_height;
+            let press = !pressed && falling && below;
+            pressed |= press;
+            app.world.get_mut::<FpsControllerInput>(player).unwrap().jump = press;
+
+            step(&mut app, 1);
+            if pressed && app.world.get::<Velocity>(player).unwrap().linvel.y > 1.0 {
+                return true;
+            }
+        }
+        false
+    }
+
+    #[test]
+    fn jump_pressed_just_before_landing_is_buffered() {
+        // a couple of frames above the floor
+        assert!(jumps_after_pressing_at(1.3));
+        // long before landing, the buffer runs out in the air
+        assert!(!jumps_after_pressing_at(2.9));
+    }
+}
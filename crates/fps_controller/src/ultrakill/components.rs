@@ -1,15 +1,44 @@
-use bevy::prelude::*;
+use bevy::{math::Vec3Swizzles, prelude::*};
 use bevy_rapier3d::prelude::*;
 
-use crate::{input::FpsPlayer, math::move_towards};
+use crate::{
+    input::FpsPlayer,
+    jump::JumpTimers,
+    math::move_towards,
+    physics::{self_filter, self_filter_all_bodies, self_filter_with_kinematic, RapierGravity},
+};
 
 #[derive(Component)]
 pub struct RenderPlayer;
 
+/// sent each time the grounded player has moved another `FpsController::stride_length`, e.g. for footstep audio
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct FootstepEvent {
+    pub position: Vec3,
+    /// collider the player is standing on, to pick a sound for the surface
+    pub surface: Option<Entity>,
+}
+
+/// moves every player to `translation` facing `rotation` with no velocity and a clean ground and jump state.
+/// Writing `Transform::translation` directly keeps the old velocity and whatever the player was standing on.
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct TeleportPlayer {
+    pub translation: Vec3,
+    pub rotation: Quat,
+}
+
+/// add to the player to draw the ground cast, the wall check cylinder and the closest wall point each frame
+#[derive(Component, Default)]
+pub struct FpsControllerDebug;
+
 #[derive(Component)]
 pub struct FpsController {
     pub radius: f32,
+    /// downward acceleration used by the movement math. The player's rigid body runs with `GravityScale(0.0)` so this
+    /// is the only gravity it gets, independent of `RapierConfiguration::gravity` unless `use_rapier_gravity` is set.
     pub gravity: f32,
+    /// copy `gravity` from `RapierConfiguration::gravity` every frame so the player falls like dynamic objects do
+    pub use_rapier_gravity: bool,
 
     pub walk_speed: f32,
     pub slide_speed: f32,
@@ -29,6 +58,10 @@ pub struct FpsController {
     pub slide_jump_speed: f32,
     pub dash_jump_speed: f32,
     pub wall_jump_speed: f32,
+    /// how many wall jumps can be chained before touching the ground again
+    pub max_wall_jumps: u8,
+    /// when false the player has to jump off of a different wall (one facing another direction) each time
+    pub allow_same_wall_jumps: bool,
     pub crouch_speed: f32,
     pub uncrouch_speed: f32,
 
@@ -37,9 +70,14 @@ pub struct FpsController {
 
     pub air_speed_cap: f32,
     pub air_acceleration: f32,
+    /// [0, 1] how much the player can steer while airborne. 0 locks the horizontal direction at takeoff, 1 applies
+    /// the full air_acceleration.
+    pub air_control: f32,
     pub max_air_speed: f32,
     pub acceleration: f32,
     pub ground_slam_speed: f32,
+    /// how long after a ground slam lands a slide or dash converts the slam force into a speed boost
+    pub slam_landing_window: f32,
     pub max_fall_velocity: f32,
     pub friction: f32,
     /// If the dot product (alignment) of the normal of the surface and the upward vector,
@@ -53,6 +91,28 @@ pub struct FpsController {
     pub sensitivity: f32,
     pub enable_input: bool,
     pub step_offset: f32,
+    /// when true the ground/wall checks also hit dynamic bodies so the player can stand on and wall jump off of them
+    pub collide_with_dynamic_bodies: bool,
+    /// when true the ground check also hits kinematic bodies and the player is carried along by the `Velocity` of the
+    /// body it stands on, including the tangential velocity of spinning platforms
+    pub ride_platforms: bool,
+    /// when riding a spinning platform the player also turns with its yaw
+    pub yaw_with_platforms: bool,
+    /// upper bound for the delta time used by the movement math so a long frame doesn't launch the player. 0 disables
+    /// the clamp.
+    pub max_delta: f32,
+    /// horizontal distance walked on the ground between two `FootstepEvent`s. 0 disables footsteps.
+    pub stride_length: f32,
+}
+
+impl RapierGravity for FpsController {
+    fn use_rapier_gravity(&self) -> bool {
+        self.use_rapier_gravity
+    }
+
+    fn set_gravity(&mut self, gravity: f32) {
+        self.gravity = gravity;
+    }
 }
 
 impl Default for FpsController {
@@ -60,6 +120,7 @@ impl Default for FpsController {
         Self {
             radius: 0.5,
             gravity: 23.0,
+            use_rapier_gravity: false,
 
             walk_speed: 20.0 * 30.0,
             slide_speed: 35.0 * 30.0,
@@ -74,6 +135,8 @@ impl Default for FpsController {
             slide_jump_speed: 8.0, // * 2.0 in UK
             dash_jump_speed: 8.0,  // * 1.5 in UK
             wall_jump_speed: 15.0,
+            max_wall_jumps: 3,
+            allow_same_wall_jumps: true,
             crouch_speed: 50.0,
             uncrouch_speed: 8.0,
 
@@ -82,7 +145,9 @@ impl Default for FpsController {
 
             air_speed_cap: 2.0,
             air_acceleration: 50.0,
+            air_control: 1.0,
             ground_slam_speed: 50.0,
+            slam_landing_window: 0.2,
             max_fall_velocity: -100.0,
             max_air_speed: 15.0,
             height: 1.0,
@@ -96,6 +161,44 @@ impl Default for FpsController {
             step_offset: 0.0,
             enable_input: true,
             sensitivity: 0.005,
+            collide_with_dynamic_bodies: false,
+            ride_platforms: true,
+            yaw_with_platforms: true,
+            max_delta: 1.0 / 30.0,
+            stride_length: 2.0,
+        }
+    }
+}
+
+impl FpsController {
+    /// accelerates the horizontal part of velocity towards wish_velocity while in the air. Acceleration is only
+    /// applied on axes where we are slower than wish_velocity so it can't be used to gain speed.
+    pub fn air_move(&self, velocity: Vec3, wish_velocity: Vec3, dt: f32) -> Vec3 {
+        let mut air_dir = Vec3::ZERO;
+        if (wish_velocity.x > 0.0 && velocity.x < wish_velocity.x)
+            || (wish_velocity.x < 0.0 && velocity.x > wish_velocity.x)
+        {
+            air_dir.x = wish_velocity.x;
+        }
+
+        if (wish_velocity.z > 0.0 && velocity.z < wish_velocity.z)
+            || (wish_velocity.z < 0.0 && velocity.z > wish_velocity.z)
+        {
+            air_dir.z = wish_velocity.z;
+        }
+
+        let air_control = self.air_control.clamp(0.0, 1.0);
+        velocity + air_dir.normalize_or_zero() * self.air_acceleration * air_control * dt
+    }
+
+    /// the QueryFilter used for all of the controllers physics queries. Excludes the controller itself and sensors.
+    pub fn query_filter(&self, entity: Entity) -> QueryFilter<'static> {
+        if self.collide_with_dynamic_bodies {
+            self_filter_all_bodies(entity)
+        } else if self.ride_platforms {
+            self_filter_with_kinematic(entity)
+        } else {
+            self_filter(entity)
         }
     }
 }
@@ -163,6 +266,8 @@ pub struct FpsControllerState {
     pub slam_storage: bool,
     pub super_jump_chance: f32,
     pub extra_jump_chance: f32,
+    pub slam_landing_timer: f32,
+    pub slam_landing_force: f32,
     // slide
     pub pre_slide_delay: f32,
     pub pre_slide_speed: f32,
@@ -173,9 +278,9 @@ pub struct FpsControllerState {
     pub jump_cooldown: CooldownTimer,
     pub not_jumping_cooldown: CooldownTimer,
     pub jump_timer: f32,
-    pub jump_buffer_timer: f32,
-    pub coyote_timer: f32,
+    pub jump_timers: JumpTimers,
     pub current_wall_jumps: u8,
+    pub last_wall_normal: Option<Vec3>,
     pub cling_fade: f32,
     // dash/dodge
     pub boost_duration: f32,
@@ -184,6 +289,19 @@ pub struct FpsControllerState {
     pub slide_ending_this_frame: bool,
     // grapple
     pub grapple_target: Vec3,
+    // contacts, written by controller_move every frame for gameplay and animation code
+    /// the ground check hit something this frame
+    pub on_ground: bool,
+    /// the wall check overlaps a wall this frame
+    pub on_wall: bool,
+    // platform riding
+    /// collider entity hit by the ground check this frame
+    pub ground_entity: Option<Entity>,
+    /// velocity of the ridden platform that was added on top of the player's own movement
+    pub platform_velocity: Vec3,
+    // footsteps
+    /// distance walked on the ground since the last footstep
+    pub stride_distance: f32,
 }
 
 impl FpsControllerState {
@@ -197,6 +315,44 @@ impl FpsControllerState {
         }
     }
 
+    /// clears all flags, timers and stored forces back to `new()` while keeping the configured `boost_duration`
+    pub fn reset(&mut self) {
+        *self = Self {
+            boost_duration: self.boost_duration,
+            ..Self::new()
+        };
+    }
+
+    /// puts the player back at `spawn` with a clean state and no velocity, e.g. after dying
+    pub fn respawn(&mut self, spawn: Transform, transform: &mut Transform, velocity: &mut Velocity) {
+        self.reset();
+        *transform = spawn;
+        *velocity = Velocity::zero();
+    }
+
+    /// applies a `TeleportPlayer`. Unlike `respawn` charges and cooldowns are kept, only the movement in progress is
+    /// dropped so the next frame starts fresh.
+    pub fn teleport(&mut self, teleport: TeleportPlayer, transform: &mut Transform, velocity: &mut Velocity) {
+        transform.translation = teleport.translation;
+        transform.rotation = teleport.rotation;
+        *velocity = Velocity::zero();
+
+        self.ground_entity = None;
+        self.on_ground = false;
+        self.on_wall = false;
+        self.platform_velocity = Vec3::ZERO;
+        self.stride_distance = 0.0;
+        self.falling = false;
+        self.fall_time = 0.0;
+        self.jumping = false;
+        self.jump_timer = 0.0;
+        self.jump_timers.consume();
+        self.sliding = false;
+        self.boost = false;
+        self.boost_left = 0.0;
+        self.reset_wall_jumps();
+    }
+
     pub fn tick_timers(&mut self, dt: f32) {
         self.jump_cooldown.tick(dt);
         self.not_jumping_cooldown.tick(dt);
@@ -216,6 +372,94 @@ impl FpsControllerState {
                 self.slam_force = 0.0;
             }
         }
+
+        if self.slam_landing_timer > 0.0 {
+            self.slam_landing_timer = move_towards(self.slam_landing_timer, 0.0, dt);
+            if self.slam_landing_timer <= 0.0 {
+                self.slam_landing_force = 0.0;
+            }
+        }
+    }
+
+    /// called when a ground slam hits the ground. Opens the window in which a slide or dash gets boosted.
+    pub fn land_slam(&mut self, window: f32) {
+        self.slam_landing_timer = window;
+        self.slam_landing_force = self.slam_force;
+    }
+
+    /// returns the stored slam force if we are still within the landing window. The force is consumed either way.
+    pub fn take_slam_boost(&mut self) -> Option<f32> {
+        if self.slam_landing_timer <= 0.0 {
+            return None;
+        }
+
+        self.slam_landing_timer = 0.0;
+        Some(std::mem::take(&mut self.slam_landing_force))
+    }
+
+    /// called right after the ground check. Movement is computed relative to the ridden platform so the carry added
+    /// last frame is removed while still standing on something. Stepping off keeps it as momentum.
+    pub fn update_ground(&mut self, ground_entity: Option<Entity>, velocity: &mut Vec3) {
+        self.ground_entity = ground_entity;
+        self.on_ground = ground_entity.is_some();
+        if ground_entity.is_some() {
+            *velocity -= self.platform_velocity;
+        }
+        self.platform_velocity = Vec3::ZERO;
+    }
+
+    /// adds the velocity of the platform we are standing on once the player's own movement has been computed
+    pub fn ride_platform(&mut self, platform_velocity: Vec3, velocity: &mut Vec3) {
+        self.platform_velocity = platform_velocity;
+        *velocity += platform_velocity;
+    }
+
+    /// adds `distance` walked this frame while on the ground and returns how many strides were completed
+    pub fn advance_stride(&mut self, distance: f32, stride_length: f32) -> u32 {
+        if self.ground_entity.is_none() || stride_length <= 0.0 {
+            return 0;
+        }
+
+        self.stride_distance += distance;
+        let steps = (self.stride_distance / stride_length).floor();
+        self.stride_distance -= steps * stride_length;
+        steps as u32
+    }
+
+    pub fn can_wall_jump(&self, controller: &FpsController, wall_normal: Vec3) -> bool {
+        if self.current_wall_jumps >= controller.max_wall_jumps {
+            return false;
+        }
+
+        if controller.allow_same_wall_jumps {
+            return true;
+        }
+
+        !matches!(self.last_wall_normal, Some(last) if last.dot(wall_normal) > 0.99)
+    }
+
+    pub fn register_wall_jump(&mut self, wall_normal: Vec3) {
+        self.current_wall_jumps += 1;
+        self.last_wall_normal = Some(wall_normal);
+    }
+
+    /// called whenever the player is grounded so that all landing paths restore the wall jumps
+    pub fn reset_wall_jumps(&mut self) {
+        self.current_wall_jumps = 0;
+        self.last_wall_normal = None;
+    }
+
+    /// one line summary of the main states and the velocity for the debug_text overlay
+    pub fn debug_summary(&self, velocity: Vec3) -> String {
+        format!(
+            "jumping: {}, sliding: {}, falling: {}, boost: {}, vel: {:06.3}, xz vel: {:06.3}",
+            self.jumping,
+            self.sliding,
+            self.falling,
+            self.boost,
+            velocity.length(),
+            velocity.xz().length()
+        )
     }
 
     pub fn start_sliding(&mut self) {
@@ -295,3 +539,211 @@ impl Default for FpsControllerBundle {
 //         valve_maps::bevy::ValveMapPlayer,
 //         RenderLayers::layer(1),
 //     ),
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_air_control_keeps_direction() {
+        let controller = FpsController {
+            air_control: 0.0,
+            ..default()
+        };
+
+        let mut velocity = Vec3::new(5.0, 2.0, 0.0);
+        for _ in 0..60 {
+            velocity = controller.air_move(velocity, Vec3::new(-10.0, 0.0, 10.0), 1.0 / 60.0);
+        }
+        assert_eq!(velocity, Vec3::new(5.0, 2.0, 0.0));
+
+        let controller = FpsController::default();
+        let velocity = controller.air_move(Vec3::new(5.0, 2.0, 0.0), Vec3::new(-10.0, 0.0, 10.0), 1.0 / 60.0);
+        assert!(velocity.x < 5.0 && velocity.z > 0.0);
+    }
+
+    #[test]
+    fn slam_boost_only_within_landing_window() {
+        let mut state = FpsControllerState::new();
+        state.slam_force = 2.5;
+        state.land_slam(0.2);
+        state.tick_timers(0.1);
+        assert_eq!(state.take_slam_boost(), Some(2.5));
+        assert_eq!(state.take_slam_boost(), None);
+
+        let mut state = FpsControllerState::new();
+        state.slam_force = 2.5;
+        state.land_slam(0.2);
+        state.tick_timers(0.1);
+        state.tick_timers(0.15);
+        assert_eq!(state.take_slam_boost(), None);
+        assert_eq!(state.slam_landing_force, 0.0);
+    }
+
+    #[test]
+    fn debug_summary_lists_states() {
+        let state = FpsControllerState {
+            sliding: true,
+            boost: true,
+            ..FpsControllerState::new()
+        };
+
+        let summary = state.debug_summary(Vec3::new(3.0, 4.0, 0.0));
+        assert!(summary.contains("jumping: false"));
+        assert!(summary.contains("sliding: true"));
+        assert!(summary.contains("falling: false"));
+        assert!(summary.contains("boost: true"));
+        assert!(summary.contains("vel: 05.000"));
+        assert!(summary.contains("xz vel: 03.000"));
+    }
+
+    #[test]
+    fn wall_jumps_are_capped() {
+        let controller = FpsController {
+            max_wall_jumps: 2,
+            ..default()
+        };
+        let mut state = FpsControllerState::new();
+
+        for i in 0..2 {
+            let normal = if i % 2 == 0 { Vec3::X } else { Vec3::NEG_X };
+            assert!(state.can_wall_jump(&controller, normal));
+            state.register_wall_jump(normal);
+        }
+        assert!(!state.can_wall_jump(&controller, Vec3::Z));
+
+        state.reset_wall_jumps();
+        assert!(state.can_wall_jump(&controller, Vec3::Z));
+    }
+
+    #[test]
+    fn same_wall_jumps_can_be_disallowed() {
+        let mut controller = FpsController::default();
+        let mut state = FpsControllerState::new();
+        state.register_wall_jump(Vec3::X);
+        assert!(state.can_wall_jump(&controller, Vec3::X));
+
+        controller.allow_same_wall_jumps = false;
+        assert!(!state.can_wall_jump(&controller, Vec3::X));
+        assert!(state.can_wall_jump(&controller, Vec3::NEG_X));
+    }
+
+    #[test]
+    fn riding_adds_platform_velocity() {
+        let platform = Entity::from_raw(7);
+        let mut state = FpsControllerState::new();
+        let mut velocity = Vec3::new(1.0, 0.0, 0.0);
+
+        state.update_ground(Some(platform), &mut velocity);
+        state.ride_platform(Vec3::new(0.0, 0.0, 3.0), &mut velocity);
+        assert_eq!(velocity, Vec3::new(1.0, 0.0, 3.0));
+
+        // the next frame starts from the player's own velocity again so the carry doesn't pile up
+        state.update_ground(Some(platform), &mut velocity);
+        assert_eq!(velocity, Vec3::new(1.0, 0.0, 0.0));
+        state.ride_platform(Vec3::new(0.0, 0.0, 3.0), &mut velocity);
+
+        // jumping off keeps the platform's momentum
+        state.update_ground(None, &mut velocity);
+        assert_eq!(velocity, Vec3::new(1.0, 0.0, 3.0));
+        assert_eq!(state.platform_velocity, Vec3::ZERO);
+    }
+
+    #[test]
+    fn reset_restores_initial_state() {
+        let mut state = FpsControllerState {
+            sliding: true,
+            boost: true,
+            boost_charge: 12.0,
+            jump_timer: 0.3,
+            boost_duration: 0.4,
+            ..FpsControllerState::new()
+        };
+        state.jump_cooldown.reset_with_duration(1.0);
+        state.jump_timers.coyote_timer = 0.1;
+        state.register_wall_jump(Vec3::X);
+
+        state.reset();
+        assert!(!state.sliding && !state.boost);
+        assert_eq!(state.boost_charge, 300.0);
+        assert_eq!(state.jump_timer, 0.0);
+        assert_eq!(state.jump_timers, JumpTimers::default());
+        assert_eq!(state.current_wall_jumps, 0);
+        assert_eq!(state.jump_cooldown.duration, 0.2);
+        assert_eq!(state.boost_duration, 0.4);
+
+        let mut transform = Transform::from_xyz(5.0, -40.0, 0.0);
+        let mut velocity = Velocity::linear(Vec3::NEG_Y * 50.0);
+        state.sliding = true;
+        state.respawn(Transform::from_xyz(0.0, 2.0, 0.0), &mut transform, &mut velocity);
+        assert!(!state.sliding);
+        assert_eq!(transform.translation, Vec3::new(0.0, 2.0, 0.0));
+        assert_eq!(velocity.linvel, Vec3::ZERO);
+    }
+
+    #[test]
+    fn footstep_every_stride_on_ground() {
+        let mut state = FpsControllerState::new();
+        let stride_length = 2.0;
+
+        // airborne movement doesn't count
+        assert_eq!(state.advance_stride(10.0, stride_length), 0);
+
+        let mut velocity = Vec3::ZERO;
+        state.update_ground(Some(Entity::from_raw(1)), &mut velocity);
+        let steps: u32 = (0..16).map(|_| state.advance_stride(0.25, stride_length)).sum();
+        assert_eq!(steps, 2);
+        assert_eq!(state.stride_distance, 0.0);
+    }
+
+    #[test]
+    fn teleport_clears_movement() {
+        let mut state = FpsControllerState {
+            boost: true,
+            boost_charge: 100.0,
+            jump_timer: 0.2,
+            stride_distance: 1.5,
+            ..FpsControllerState::new()
+        };
+        let mut transform = Transform::from_xyz(5.0, 1.0, 0.0);
+        let mut velocity = Velocity::linear(Vec3::new(20.0, 4.0, 0.0));
+
+        // standing on a platform moving along z
+        let platform = Entity::from_raw(1);
+        state.update_ground(Some(platform), &mut velocity.linvel);
+        state.ride_platform(Vec3::Z * 3.0, &mut velocity.linvel);
+
+        let teleport = TeleportPlayer {
+            translation: Vec3::new(0.0, 10.0, -30.0),
+            rotation: Quat::from_rotation_y(1.0),
+        };
+        state.teleport(teleport, &mut transform, &mut velocity);
+
+        assert_eq!(transform.translation, teleport.translation);
+        assert_eq!(transform.rotation, teleport.rotation);
+        assert_eq!(velocity.linvel, Vec3::ZERO);
+        assert!(!state.boost && state.ground_entity.is_none());
+        assert_eq!(state.jump_timer, 0.0);
+        assert_eq!(state.boost_charge, 100.0);
+
+        // landing on the same platform doesn't take away platform velocity that was never added
+        let mut next_velocity = Vec3::ZERO;
+        state.update_ground(Some(platform), &mut next_velocity);
+        assert_eq!(next_velocity, Vec3::ZERO);
+        assert_eq!(state.advance_stride(1.0, 2.0), 0);
+    }
+
+    #[test]
+    fn ground_check_sets_on_ground() {
+        let mut state = FpsControllerState::new();
+        let mut velocity = Vec3::ZERO;
+        assert!(!state.on_ground);
+
+        // what controller_move does after its ground check hits the floor
+        state.update_ground(Some(Entity::from_raw(3)), &mut velocity);
+        assert!(state.on_ground);
+
+        state.update_ground(None, &mut velocity);
+        assert!(!state.on_ground);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::input::FpsControllerStages;
+use crate::{input::FpsControllerStages, utils::physics::sync_rapier_gravity};
 use bevy::prelude::*;
 
 pub use self::components::*;
@@ -13,7 +13,22 @@ pub struct UltrakillControllerPlugin;
 impl Plugin for UltrakillControllerPlugin {
     fn build(&self, app: &mut App) {
         app.register_type::<FpsControllerState>()
-            .add_system(controller_move.in_set(FpsControllerStages::Logic))
+            .add_event::<FootstepEvent>()
+            .add_event::<TeleportPlayer>()
+            .add_systems(
+                (
+                    teleport_players,
+                    sync_rapier_gravity::<FpsController>,
+                    controller_move,
+                    carry_riders,
+                    emit_footsteps,
+                )
+                    .chain()
+                    .in_set(FpsControllerStages::Logic),
+            )
             .add_system(debug_ui);
+
+        #[cfg(feature = "debug_text_overlay")]
+        app.add_system(controller_debug_text);
     }
 }
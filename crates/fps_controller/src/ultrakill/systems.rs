@@ -1,12 +1,18 @@
 use std::collections::VecDeque;
 
 use crate::{
-    camera_shake::Shake3d, input::FpsControllerInput, time_controller::TimeScaleModificationEvent,
-    utils::math::move_towards, DebugLinesExt,
+    camera_shake::Shake3d,
+    get_cylinder_xz_pts, get_ground_cast_pts,
+    input::FpsControllerInput,
+    time_controller::TimeScaleModificationEvent,
+    utils::{
+        math::{clamp_delta, move_towards},
+        physics::{point_velocity, surface_info, wall_jump_velocity, wall_surface_normal},
+    },
+    DebugDrawBackend, DebugLinesExt, FpsDebugDraw,
 };
 use bevy::math::Vec3Swizzles;
 use bevy::prelude::*;
-use bevy_prototype_debug_lines::DebugLines;
 use bevy_rapier3d::prelude::*;
 use debug_text::screen_print;
 use egui_helper::bevy_inspector_egui::{
@@ -16,9 +22,80 @@ use egui_helper::bevy_inspector_egui::{
 
 use super::components::*;
 
+/// applies the last `TeleportPlayer` sent this frame to every player
+pub fn teleport_players(
+    mut events: EventReader<TeleportPlayer>,
+    mut query: Query<(&mut FpsControllerState, &mut Transform, &mut Velocity)>,
+) {
+    let Some(teleport) = events.iter().last().copied() else {
+        return;
+    };
+
+    for (mut state, mut transform, mut velocity) in &mut query {
+        state.teleport(teleport, &mut transform, &mut velocity);
+    }
+}
+
+/// adds the `Velocity` of the body the player stands on after `controller_move` so they move along with moving
+/// platforms. For spinning platforms the player's position is used as the contact point. The ground check hits
+/// colliders, so the `Velocity` has to live on the collider's entity.
+pub fn carry_riders(
+    time: Res<Time>,
+    mut riders: Query<(&FpsController, &mut FpsControllerState, &mut Transform, &mut Velocity)>,
+    platforms: Query<(&Velocity, &GlobalTransform), Without<FpsController>>,
+) {
+    for (controller, mut state, mut transform, mut velocity) in &mut riders {
+        if !controller.ride_platforms {
+            continue;
+        }
+
+        let Some((platform, platform_tf)) = state.ground_entity.and_then(|entity| platforms.get(entity).ok()) else {
+            continue;
+        };
+
+        let carry = point_velocity(
+            platform.linvel,
+            platform.angvel,
+            platform_tf.translation(),
+            transform.translation,
+        );
+        state.ride_platform(carry, &mut velocity.linvel);
+
+        if controller.yaw_with_platforms {
+            transform.rotate_y(platform.angvel.y * clamp_delta(time.delta_seconds(), controller.max_delta));
+        }
+    }
+}
+
+/// how far below the player the ground check casts
+const GROUND_CAST_DISTANCE: f32 = 0.125;
+/// the wall check cylinder is shorter but wider than the player
+const WALL_CHECK_HALF_HEIGHT: f32 = 0.4;
+const WALL_CHECK_RADIUS: f32 = 0.6;
+
+/// sends a `FootstepEvent` for every stride the grounded player walks. Only the player's own movement counts, riding a
+/// platform doesn't make footsteps.
+pub fn emit_footsteps(
+    time: Res<Time>,
+    mut query: Query<(&FpsController, &mut FpsControllerState, &Transform, &Velocity)>,
+    mut footsteps: EventWriter<FootstepEvent>,
+) {
+    for (controller, mut state, transform, velocity) in &mut query {
+        let dt = clamp_delta(time.delta_seconds(), controller.max_delta);
+        let distance = (velocity.linvel - state.platform_velocity).xz().length() * dt;
+
+        for _ in 0..state.advance_stride(distance, controller.stride_length) {
+            footsteps.send(FootstepEvent {
+                position: transform.translation,
+                surface: state.ground_entity,
+            });
+        }
+    }
+}
+
 pub fn controller_move(
     time: Res<Time>,
-    mut _lines: ResMut<DebugLines>,
+    mut lines: FpsDebugDraw,
     physics_context: Res<RapierContext>,
     mut query: Query<(
         Entity,
@@ -28,19 +105,21 @@ pub fn controller_move(
         &mut Collider,
         &mut Transform,
         &mut Velocity,
+        Option<&FpsControllerDebug>,
     )>,
     mut shake_q: Query<&mut Shake3d>,
     camera_q: Query<(&GlobalTransform, &Camera), With<crate::input::RenderPlayer>>,
     mut _evt_time_mod: EventWriter<TimeScaleModificationEvent>,
 ) {
-    let dt = time.delta_seconds();
     let mut shake = shake_q.single_mut();
-    let (entity, input, mut state, mut controller, mut collider, mut transform, mut velocity) = query.single_mut();
+    let (entity, input, mut state, mut controller, mut collider, mut transform, mut velocity, debug) =
+        query.single_mut();
+    let dt = clamp_delta(time.delta_seconds(), controller.max_delta);
     let Some(capsule) = collider.as_capsule() else { return };
 
     if input.shoot.pressed {
         let (tf, _camera) = camera_q.single();
-        let filter = QueryFilter::only_fixed().exclude_collider(entity).exclude_sensors();
+        let filter = controller.query_filter(entity);
 
         // alternate way using the camera
         // let vp_size = camera.logical_viewport_size().unwrap();
@@ -49,13 +128,15 @@ pub fn controller_move(
         // TODO: maybe sphere cast and reduce size of collider while grappling?
         if let Some(ray_hit) = physics_context.cast_ray(tf.translation(), tf.forward(), 1000.0, false, filter) {
             println!("hit: {:?}", ray_hit);
-            _lines.thick_line(tf.translation(), tf.translation() + tf.forward() * ray_hit.1, 2.0);
             state.grappling = true;
             state.grapple_target = tf.translation() + tf.forward() * ray_hit.1;
 
-            let perp = state.grapple_target.cross(tf.forward()).normalize();
-            _lines.thick_line(state.grapple_target, state.grapple_target + perp * 0.2, 2.0);
-            _lines.thick_line(state.grapple_target, state.grapple_target - perp * 0.2, 2.0);
+            if let Some(lines) = lines.draw() {
+                let perp = state.grapple_target.cross(tf.forward()).normalize();
+                lines.thick_line(tf.translation(), state.grapple_target, 2.0);
+                lines.thick_line(state.grapple_target, state.grapple_target + perp * 0.2, 2.0);
+                lines.thick_line(state.grapple_target, state.grapple_target - perp * 0.2, 2.0);
+            }
         }
     }
 
@@ -76,55 +157,52 @@ pub fn controller_move(
     let capsule = capsule.raw;
     let cast_capsule = Collider::capsule(capsule.segment.a.into(), capsule.segment.b.into(), capsule.radius * 0.9);
 
-    let filter = QueryFilter::only_fixed().exclude_rigid_body(entity).exclude_sensors();
-    let ground_cast = physics_context.cast_shape(
-        transform.translation,
-        transform.rotation,
-        -Vec3::Y,
+    let filter = controller.query_filter(entity);
+    // the wall check uses a cylinder that is shorter but wider than the player
+    let cast_cylinder = Collider::cylinder(WALL_CHECK_HALF_HEIGHT, WALL_CHECK_RADIUS);
+    let surfaces = surface_info(
+        &physics_context,
+        &transform,
         &cast_capsule,
-        0.125,
-        filter,
-    );
-    let on_ground = ground_cast.is_some();
-
-    // wall intersection check, we use a cylinder that is shorter but wider than the player
-    let cast_cylinder = Collider::cylinder(0.4, 0.6);
-    let filter = QueryFilter::only_fixed().exclude_rigid_body(entity).exclude_sensors();
-    let mut on_wall = false;
-    let mut closest_pt = Vec3::splat(f32::MAX);
-    let mut closest_dist = f32::MAX;
-    physics_context.intersections_with_shape(
-        transform.translation,
-        transform.rotation,
+        GROUND_CAST_DISTANCE,
         &cast_cylinder,
         filter,
-        |entity| {
-            let player_pos = transform.translation;
-
-            let predicate = &|e| e == entity;
-            let inner_filter = QueryFilter::only_fixed().predicate(&predicate);
-            if let Some(pt_res) = physics_context.project_point(transform.translation, true, inner_filter) {
-                let dist = player_pos.distance_squared(pt_res.1.point);
-                if dist < closest_dist {
-                    closest_dist = dist;
-                    closest_pt = pt_res.1.point;
-                }
-            }
+    );
+    let on_ground = surfaces.on_ground();
+    let on_wall = surfaces.on_wall();
+    state.update_ground(surfaces.ground.map(|ground| ground.entity), &mut velocity.linvel);
+    state.on_wall = on_wall;
+
+    if let (Some(_), Some(lines)) = (debug, lines.draw()) {
+        let (a, b): (Vec3, Vec3) = (capsule.segment.a.into(), capsule.segment.b.into());
+        let center = transform.translation + (a + b) * 0.5;
+        let half_height = a.distance(b) * 0.5 + capsule.radius * 0.9;
+        let ground_color = if on_ground { Color::GREEN } else { Color::RED };
+        let ground_pts = get_ground_cast_pts(center, half_height, capsule.radius * 0.9, GROUND_CAST_DISTANCE, 4);
+        lines.draw_pts(&ground_pts, 0.0, ground_color);
+
+        let wall_color = if on_wall { Color::GREEN } else { Color::YELLOW };
+        let wall_pts = get_cylinder_xz_pts(transform.translation, WALL_CHECK_HALF_HEIGHT, WALL_CHECK_RADIUS, 4);
+        lines.draw_pts(&wall_pts, 0.0, wall_color);
+
+        if let Some(wall) = surfaces.wall {
+            lines.thick_colored_line(transform.translation, wall.point, 0.0, Color::CYAN);
+        }
+    }
 
-            on_wall = true;
-            true
-        },
+    state.jump_timers.tick(
+        dt,
+        on_ground,
+        input.jump.pressed,
+        controller.coyote_timer_duration,
+        controller.jump_buffer_duration,
     );
 
     if on_ground {
         state.fall_time = 0.0;
         state.cling_fade = 0.0;
-        state.coyote_timer = controller.coyote_timer_duration;
+        state.reset_wall_jumps();
     } else {
-        state.coyote_timer = (state.coyote_timer - dt).max(0.0);
-        state.jump_buffer_timer =
-            if input.jump.pressed { controller.jump_buffer_duration } else { (state.jump_buffer_timer - dt).max(0.0) };
-
         if state.jump_timer > 0.0 {
             if input.jump.down {
                 velocity.linvel.y += controller.jump_down_speed;
@@ -148,7 +226,7 @@ pub fn controller_move(
         }
     }
 
-    let jump_requested = input.jump.pressed || state.jump_buffer_timer > 0.0;
+    let jump_requested = state.jump_timers.jump_requested(input.jump.pressed);
 
     // clamp max fall velocity
     if velocity.linvel.y < controller.max_fall_velocity {
@@ -195,12 +273,12 @@ pub fn controller_move(
     }
 
     // if jump_requested && !state.falling && on_ground && state.jump_cooldown.is_complete() {
-    let coyote_jump = jump_requested && !on_ground && state.coyote_timer > 0.0 && !on_wall;
+    let coyote_jump = jump_requested && state.jump_timers.can_coyote_jump(on_ground) && !on_wall;
     let normal_jump = jump_requested && !state.falling && on_ground;
     if (coyote_jump || normal_jump) && state.jump_cooldown.is_complete() {
         state.jump_timer = controller.jump_time;
-        state.jump_buffer_timer = 0.0;
-        state.current_wall_jumps = 0;
+        state.jump_timers.consume();
+        state.reset_wall_jumps();
         state.cling_fade = 0.0;
         state.jumping = true;
         state.falling = true;
@@ -235,7 +313,7 @@ pub fn controller_move(
         state.boost = false;
     }
 
-    if !on_ground && on_wall {
+    if let Some(wall) = surfaces.wall.filter(|_| !on_ground) {
         // check if movement direction is in the direction of the wall we are on
         if !state.heavy_fall
             && physics_context
@@ -252,22 +330,23 @@ pub fn controller_move(
             }
         }
 
-        if jump_requested && state.jump_cooldown.is_complete() && state.current_wall_jumps < 3 {
+        // push off along the actual surface normal
+        let wall_normal = (jump_requested && state.jump_cooldown.is_complete())
+            .then(|| wall_surface_normal(&physics_context, transform.translation, &wall, filter));
+
+        if let Some(wall_normal) = wall_normal.filter(|normal| state.can_wall_jump(&controller, *normal)) {
             state.jump_timer = controller.jump_time;
-            state.jump_buffer_timer = 0.0;
+            state.jump_timers.consume();
             state.jumping = true;
             state.not_jumping_cooldown.reset();
             state.jump_cooldown.reset_with_duration(0.1);
-            state.current_wall_jumps += 1;
+            state.register_wall_jump(wall_normal);
 
             if state.heavy_fall {
                 state.slam_storage = true;
             }
 
-            let jump_direction = (transform.translation - Vec3::NEG_Y - closest_pt).normalize();
-
-            velocity.linvel.y = 0.0;
-            velocity.linvel += Vec3::new(jump_direction.x, 1.0, jump_direction.z) * controller.wall_jump_speed;
+            velocity.linvel = wall_jump_velocity(velocity.linvel, wall_normal, controller.wall_jump_speed);
 
             state.boost = false;
         }
@@ -276,6 +355,11 @@ pub fn controller_move(
     if input.slide.pressed && on_ground && !state.sliding {
         state.start_sliding();
         // TODO: move to crouch
+
+        // sliding right after a ground slam lands converts the slam into slide speed
+        if let Some(slam_force) = state.take_slam_boost() {
+            state.pre_slide_speed = slam_force;
+        }
     }
 
     // skip the ground slam if slide is pressed and we are near the ground
@@ -313,6 +397,11 @@ pub fn controller_move(
             state.boost = true;
             state.boost_charge -= 100.0;
 
+            // dashing right after a ground slam lands converts the slam into a longer dash
+            if let Some(slam_force) = state.take_slam_boost() {
+                state.boost_left *= slam_force.clamp(1.0, 3.0);
+            }
+
             if state.heavy_fall {
                 state.fall_speed = 0.0;
                 state.heavy_fall = false;
@@ -356,6 +445,7 @@ pub fn controller_move(
                 transform.translation.y += velocity.linvel.y * toi.toi;
                 velocity.linvel = Vec3::ZERO;
                 state.super_jump_chance = 0.085;
+                state.land_slam(controller.slam_landing_window);
             }
         } else if !state.boost && state.falling && velocity.linvel.length() / 24.0 > state.pre_slide_speed {
             state.pre_slide_delay = 0.2;
@@ -372,8 +462,6 @@ pub fn controller_move(
     // Move()
     if !state.boost {
         if on_ground && !state.jumping {
-            state.current_wall_jumps = 0;
-
             let mut new_velocity = input.movement_dir * controller.walk_speed * dt;
             new_velocity.y = velocity.linvel.y - controller.gravity * dt;
             velocity.linvel = velocity.linvel.lerp(new_velocity, 0.25);
@@ -382,22 +470,9 @@ pub fn controller_move(
         } else {
             let wish_velocity = input.movement_dir * controller.walk_speed * dt;
 
-            let mut air_dir = Vec3::ZERO;
-            if (wish_velocity.x > 0.0 && velocity.linvel.x < wish_velocity.x)
-                || (wish_velocity.x < 0.0 && velocity.linvel.x > wish_velocity.x)
-            {
-                air_dir.x = wish_velocity.x;
-            }
-
-            if (wish_velocity.z > 0.0 && velocity.linvel.z < wish_velocity.z)
-                || (wish_velocity.z < 0.0 && velocity.linvel.z > wish_velocity.z)
-            {
-                air_dir.z = wish_velocity.z;
-            }
-
             // TODO: this can maybe use acceleration method with quake with_vel system?
             let vel_y = velocity.linvel.y - controller.gravity * dt;
-            velocity.linvel += air_dir.normalize_or_zero() * controller.air_acceleration * dt;
+            velocity.linvel = controller.air_move(velocity.linvel, wish_velocity, dt);
             velocity.linvel.y = vel_y;
             screen_print!(sec: 0.0, "air");
         }
@@ -431,11 +506,11 @@ pub fn controller_move(
         velocity.linvel = velocity.linvel.lerp(new_velocity, 0.4);
         velocity.linvel = new_velocity;
     } else {
-        if !on_ground && on_wall {
+        if let Some(wall) = surfaces.wall.filter(|_| !on_ground) {
             // fire off a ray in the direction of the closest wall point we are touching
             if let Some(ray_check) = physics_context.cast_ray_and_get_normal(
                 transform.translation,
-                (closest_pt - transform.translation).normalize_or_zero(), // input.dash_slide_dir,
+                (wall.point - transform.translation).normalize_or_zero(), // input.dash_slide_dir,
                 1.0,
                 false,
                 filter,
@@ -503,8 +578,8 @@ pub fn controller_move(
         controller.walk_speed
     };
 
-    if let Some((_, toi)) = ground_cast {
-        let has_traction = Vec3::dot(toi.normal1, Vec3::Y) > controller.traction_normal_cutoff;
+    if let Some(ground) = surfaces.ground {
+        let has_traction = Vec3::dot(ground.normal, Vec3::Y) > controller.traction_normal_cutoff;
 
         // Only apply friction after at least one tick, allows b-hopping without losing speed
         if has_traction {
@@ -534,7 +609,7 @@ pub fn controller_move(
 
         if has_traction {
             let linvel = velocity.linvel;
-            velocity.linvel -= Vec3::dot(linvel, toi.normal1) * toi.normal1;
+            velocity.linvel -= Vec3::dot(linvel, ground.normal) * ground.normal;
 
             // if input.jump_was_pressed {
             //     velocity.linvel.y = controller.jump_speed;
@@ -640,6 +715,8 @@ pub fn debug_ui(world: &mut World, mut enabled: Local<bool>, mut velocity_data:
                 ui.checkbox(&mut state.heavy_fall, "heavy_fall");
                 ui.checkbox(&mut state.falling, "falling");
                 ui.checkbox(&mut state.boost, "boost");
+                ui.checkbox(&mut state.on_ground, "on_ground");
+                ui.checkbox(&mut state.on_wall, "on_wall");
                 ui.spacing();
                 fn float_ui(ui: &mut egui::Ui, value: &mut f32, label: &str) {
                     ui.horizontal(|ui| {
@@ -654,6 +731,7 @@ pub fn debug_ui(world: &mut World, mut enabled: Local<bool>, mut velocity_data:
                 ui.checkbox(&mut state.slam_storage, "slam_storage");
                 float_ui(ui, &mut state.super_jump_chance, "super_jump_chance");
                 float_ui(ui, &mut state.extra_jump_chance, "extra_jump_chance");
+                float_ui(ui, &mut state.slam_landing_timer, "slam_landing_timer");
                 ui.spacing();
                 ui.label("Slide");
                 float_ui(ui, &mut state.pre_slide_delay, "pre_slide_delay");
@@ -669,8 +747,8 @@ pub fn debug_ui(world: &mut World, mut enabled: Local<bool>, mut velocity_data:
                     &mut state.not_jumping_cooldown.finished,
                     "not_jumping_cooldown.finished",
                 );
-                float_ui(ui, &mut state.jump_buffer_timer, "jump_buffer_timer");
-                float_ui(ui, &mut state.coyote_timer, "coyote_timer");
+                float_ui(ui, &mut state.jump_timers.jump_buffer_timer, "jump_buffer_timer");
+                float_ui(ui, &mut state.jump_timers.coyote_timer, "coyote_timer");
                 let mut tmp_wall_jumps = state.current_wall_jumps as f32;
                 float_ui(ui, &mut tmp_wall_jumps, "current_wall_jumps");
                 float_ui(ui, &mut state.cling_fade, "cling_fade");
@@ -699,6 +777,14 @@ pub fn debug_ui(world: &mut World, mut enabled: Local<bool>, mut velocity_data:
         });
 }
 
+/// text only version of `debug_ui` for builds without egui
+#[cfg(feature = "debug_text_overlay")]
+pub fn controller_debug_text(query: Query<(&FpsControllerState, &Velocity)>) {
+    for (state, velocity) in query.iter() {
+        screen_print!(sec: 0.0, "{}", state.debug_summary(velocity.linvel));
+    }
+}
+
 /// projectile motion, get velocity required to launch an object from start to end. has issues...doesnt always reach the target.
 /// revisit later for grapple hook thing or just fast teleport
 #[allow(dead_code)]
@@ -0,0 +1,77 @@
+use bevy::prelude::Reflect;
+
+/// coyote time and jump buffering, shared by the controllers so they behave the same. Both timers count down in
+/// seconds.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Reflect)]
+pub struct JumpTimers {
+    /// time left to jump after walking off a ledge
+    pub coyote_timer: f32,
+    /// time left in which a jump pressed in the air still fires once the player lands
+    pub jump_buffer_timer: f32,
+}
+
+impl JumpTimers {
+    /// call once per frame after the ground check. Standing on the ground refills the coyote time and a jump pressed
+    /// in the air is remembered for `jump_buffer_duration`.
+    pub fn tick(
+        &mut self,
+        dt: f32,
+        on_ground: bool,
+        jump_pressed: bool,
+        coyote_timer_duration: f32,
+        jump_buffer_duration: f32,
+    ) {
+        if on_ground {
+            self.coyote_timer = coyote_timer_duration;
+        } else {
+            self.coyote_timer = (self.coyote_timer - dt).max(0.0);
+            self.jump_buffer_timer =
+                if jump_pressed { jump_buffer_duration } else { (self.jump_buffer_timer - dt).max(0.0) };
+        }
+    }
+
+    /// jump was pressed this frame or is still buffered
+    pub fn jump_requested(&self, jump_pressed: bool) -> bool {
+        jump_pressed || self.jump_buffer_timer > 0.0
+    }
+
+    /// in the air but still within the coyote time
+    pub fn can_coyote_jump(&self, on_ground: bool) -> bool {
+        !on_ground && self.coyote_timer > 0.0
+    }
+
+    /// call when a jump starts so a buffered press or the coyote time can't fire a second one
+    pub fn consume(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buffered_jump_fires_on_landing() {
+        let dt = 1.0 / 60.0;
+        let mut timers = JumpTimers::default();
+
+        // pressed 3 frames before landing
+        timers.tick(dt, false, true, 0.2, 0.1);
+        for _ in 0..2 {
+            timers.tick(dt, false, false, 0.2, 0.1);
+        }
+        timers.tick(dt, true, false, 0.2, 0.1);
+        assert!(timers.jump_requested(false));
+
+        timers.consume();
+        assert!(!timers.jump_requested(false));
+
+        // pressed too early, the buffer runs out before landing
+        timers.tick(dt, false, true, 0.2, 0.1);
+        for _ in 0..10 {
+            timers.tick(dt, false, false, 0.2, 0.1);
+        }
+        timers.tick(dt, true, false, 0.2, 0.1);
+        assert!(!timers.jump_requested(false));
+    }
+}
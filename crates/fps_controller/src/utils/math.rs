@@ -6,6 +6,16 @@ pub fn move_towards(current: f32, target: f32, max_delta: f32) -> f32 {
     current + (target - current).signum() * max_delta
 }
 
+/// clamps a frame's delta time so a long stall (loading, breakpoint) doesn't get integrated as one huge step that sends
+/// the player through walls. A max_delta <= 0 disables the clamp.
+pub fn clamp_delta(dt: f32, max_delta: f32) -> f32 {
+    if max_delta > 0.0 {
+        dt.min(max_delta)
+    } else {
+        dt
+    }
+}
+
 /// moves current towards target by shift amount clamping the result. start can be less than or greater than end.
 /// example: start is 2, end is 10, shift is 4 results in 6
 pub fn approach(current: f32, target: f32, shift: f32) -> f32 {
@@ -25,3 +35,15 @@ pub fn map(value: f32, left_min: f32, left_max: f32, right_min: f32, right_max:
 pub fn map_01(value: f32, min: f32, max: f32) -> f32 {
     (value - min) / (max - min)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clamp_delta_caps_stalls() {
+        assert_eq!(clamp_delta(2.0, 1.0 / 30.0), 1.0 / 30.0);
+        assert_eq!(clamp_delta(1.0 / 60.0, 1.0 / 30.0), 1.0 / 60.0);
+        assert_eq!(clamp_delta(2.0, 0.0), 2.0);
+    }
+}
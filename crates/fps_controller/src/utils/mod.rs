@@ -1 +1,3 @@
-pub mod math;
\ No newline at end of file
+pub mod jump;
+pub mod math;
+pub mod physics;
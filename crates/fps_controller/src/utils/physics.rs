@@ -0,0 +1,290 @@
+use bevy::prelude::{Component, Entity, Query, Res, Transform, Vec3};
+use bevy_rapier3d::prelude::{Collider, QueryFilter, RapierConfiguration, RapierContext};
+
+/// filter used for the controllers ground/wall checks. Only hits fixed bodies and ignores sensors as well as the
+/// controller itself.
+pub fn self_filter(entity: Entity) -> QueryFilter<'static> {
+    QueryFilter::only_fixed().exclude_rigid_body(entity).exclude_sensors()
+}
+
+/// the controllers apply gravity as a magnitude along -y. This is that magnitude for Rapier's gravity vector, any
+/// sideways part of it is ignored.
+pub fn controller_gravity(rapier_gravity: Vec3) -> f32 {
+    -rapier_gravity.y
+}
+
+/// controllers whose gravity can follow Rapier's, see `sync_rapier_gravity`
+pub trait RapierGravity: Component {
+    fn use_rapier_gravity(&self) -> bool;
+    fn set_gravity(&mut self, gravity: f32);
+}
+
+/// copies `RapierConfiguration::gravity` onto the `C` controllers that opted into it with `use_rapier_gravity`
+pub fn sync_rapier_gravity<C: RapierGravity>(rapier_config: Res<RapierConfiguration>, mut query: Query<&mut C>) {
+    for mut controller in &mut query {
+        if controller.use_rapier_gravity() {
+            controller.set_gravity(controller_gravity(rapier_config.gravity));
+        }
+    }
+}
+
+/// velocity of `point` on a body moving with `linvel` and spinning with `angvel` around `center`
+pub fn point_velocity(linvel: Vec3, angvel: Vec3, center: Vec3, point: Vec3) -> Vec3 {
+    linvel + angvel.cross(point - center)
+}
+
+/// same as `self_filter` but also hits kinematic bodies so the player can stand on moving platforms
+pub fn self_filter_with_kinematic(entity: Entity) -> QueryFilter<'static> {
+    QueryFilter::exclude_dynamic()
+        .exclude_rigid_body(entity)
+        .exclude_sensors()
+}
+
+/// same as `self_filter` but also hits dynamic and kinematic bodies (crates, moving platforms)
+pub fn self_filter_all_bodies(entity: Entity) -> QueryFilter<'static> {
+    QueryFilter::default().exclude_rigid_body(entity).exclude_sensors()
+}
+
+/// direction to push the player away from a wall when wall jumping. Only the horizontal part of the surface normal
+/// is used so sloped walls don't weaken the push.
+pub fn wall_jump_direction(surface_normal: Vec3) -> Vec3 {
+    Vec3::new(surface_normal.x, 0.0, surface_normal.z).normalize_or_zero()
+}
+
+/// surface normal of the `wall` a player at `origin` pushes off from. The direction to the closest point alone is
+/// skewed on concave corners and can send the player back into the wall, so a ray is cast towards it to get the
+/// actual normal. Falls back to the direction away from the closest point if the ray misses.
+pub fn wall_surface_normal(
+    physics_context: &RapierContext,
+    origin: Vec3,
+    wall: &WallContact,
+    filter: QueryFilter,
+) -> Vec3 {
+    let to_wall = (wall.point - origin).normalize_or_zero();
+    physics_context
+        .cast_ray_and_get_normal(origin, to_wall, 1.0, false, filter)
+        .map_or(-to_wall, |(_, hit)| hit.normal)
+}
+
+/// velocity after wall jumping off a wall with `wall_normal`. Vertical velocity is dropped and the player is pushed
+/// up and away from the wall by `speed`, keeping their horizontal momentum.
+pub fn wall_jump_velocity(linvel: Vec3, wall_normal: Vec3, speed: f32) -> Vec3 {
+    let direction = wall_jump_direction(wall_normal);
+    Vec3::new(linvel.x, 0.0, linvel.z) + Vec3::new(direction.x, 1.0, direction.z) * speed
+}
+
+/// what the ground cast below the player hit
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GroundContact {
+    /// collider entity that was hit
+    pub entity: Entity,
+    /// normal of the hit, used for traction checks and to slide along slopes
+    pub normal: Vec3,
+    /// how far the cast travelled before hitting
+    pub distance: f32,
+}
+
+/// closest point of the walls overlapping the wall check
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WallContact {
+    pub point: Vec3,
+    /// distance from the player's origin to `point`
+    pub distance: f32,
+}
+
+/// ground and wall contacts of a controller for a single frame. Besides movement this is handy for animation and
+/// audio code that needs to know what the player is touching.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SurfaceInfo {
+    pub ground: Option<GroundContact>,
+    pub wall: Option<WallContact>,
+}
+
+impl SurfaceInfo {
+    pub fn on_ground(&self) -> bool {
+        self.ground.is_some()
+    }
+
+    pub fn on_wall(&self) -> bool {
+        self.wall.is_some()
+    }
+}
+
+/// runs the ground and wall checks for a controller at `transform`. `ground_shape` is cast `ground_distance` down
+/// and `wall_shape` is overlapped in place, the wall contact is the closest point of all the overlapped colliders.
+pub fn surface_info(
+    physics_context: &RapierContext,
+    transform: &Transform,
+    ground_shape: &Collider,
+    ground_distance: f32,
+    wall_shape: &Collider,
+    filter: QueryFilter,
+) -> SurfaceInfo {
+    let ground = physics_context
+        .cast_shape(
+            transform.translation,
+            transform.rotation,
+            -Vec3::Y,
+            ground_shape,
+            ground_distance,
+            filter,
+        )
+        .map(|(entity, toi)| GroundContact {
+            entity,
+            normal: toi.normal1,
+            distance: toi.toi,
+        });
+
+    let mut wall: Option<WallContact> = None;
+    physics_context.intersections_with_shape(
+        transform.translation,
+        transform.rotation,
+        wall_shape,
+        filter,
+        |entity| {
+            let predicate = |e| e == entity;
+            if let Some((_, projection)) =
+                physics_context.project_point(transform.translation, true, filter.predicate(&predicate))
+            {
+                let distance = transform.translation.distance(projection.point);
+                if !wall.is_some_and(|closest| closest.distance <= distance) {
+                    wall = Some(WallContact {
+                        point: projection.point,
+                        distance,
+                    });
+                }
+            }
+            true
+        },
+    );
+
+    SurfaceInfo { ground, wall }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        mod_fps::FpsController,
+        test_app::{step, test_world},
+    };
+    use bevy::prelude::{Quat, TransformBundle, With, Without};
+    use bevy_rapier3d::prelude::QueryFilterFlags;
+
+    #[test]
+    fn self_filter_excludes_entity() {
+        let entity = Entity::from_raw(42);
+
+        let filter = self_filter(entity);
+        assert_eq!(filter.exclude_rigid_body, Some(entity));
+        assert!(filter
+            .flags
+            .contains(QueryFilterFlags::ONLY_FIXED | QueryFilterFlags::EXCLUDE_SENSORS));
+
+        let filter = self_filter_all_bodies(entity);
+        assert_eq!(filter.exclude_rigid_body, Some(entity));
+        assert!(!filter.flags.contains(QueryFilterFlags::ONLY_FIXED));
+        assert!(filter.flags.contains(QueryFilterFlags::EXCLUDE_SENSORS));
+
+        let filter = self_filter_with_kinematic(entity);
+        assert_eq!(filter.exclude_rigid_body, Some(entity));
+        assert!(filter
+            .flags
+            .contains(QueryFilterFlags::EXCLUDE_DYNAMIC | QueryFilterFlags::EXCLUDE_SENSORS));
+    }
+
+    #[test]
+    fn spinning_platform_point_velocity() {
+        // 2 units off a platform spinning at 1.5 rad/s around +y
+        let velocity = point_velocity(
+            Vec3::ZERO,
+            Vec3::Y * 1.5,
+            Vec3::new(1.0, 0.0, 1.0),
+            Vec3::new(3.0, 0.5, 1.0),
+        );
+        assert!(velocity.abs_diff_eq(Vec3::new(0.0, 0.0, -3.0), 1e-6));
+
+        // the spin axis itself only gets the linear velocity
+        let velocity = point_velocity(Vec3::X, Vec3::Y * 1.5, Vec3::ZERO, Vec3::Y);
+        assert_eq!(velocity, Vec3::X);
+    }
+
+    /// wall jumps off a wall tilted by `tilt` radians whose face is `distance` away from a player in mid air and
+    /// returns the horizontal direction of the resulting velocity
+    fn wall_jump_off(tilt: f32, distance: f32) -> Vec3 {
+        let (mut app, player) = test_world(Vec3::new(0.0, 3.0, 0.0));
+        // the face of the wall passes `distance` away from the player, its normal points back towards them
+        let rotation = Quat::from_rotation_z(tilt);
+        let center = Vec3::new(0.0, 3.0, 0.0) + rotation * Vec3::X * (distance + 0.5);
+        app.world.spawn((
+            Collider::cuboid(0.5, 5.0, 5.0),
+            TransformBundle::from(Transform::from_translation(center).with_rotation(rotation)),
+        ));
+        step(&mut app, 1);
+
+        let transform = *app.world.get::<Transform>(player).unwrap();
+        let context = app.world.resource::<RapierContext>();
+        let filter = self_filter_all_bodies(player);
+        let ground_shape = Collider::capsule(Vec3::Y * -0.5, Vec3::Y * 0.5, 0.45);
+        let surfaces = surface_info(
+            context,
+            &transform,
+            &ground_shape,
+            0.125,
+            &Collider::cylinder(0.4, 0.6),
+            filter,
+        );
+        assert!(!surfaces.on_ground());
+        let wall = surfaces.wall.expect("wall check missed the wall");
+
+        let normal = wall_surface_normal(context, transform.translation, &wall, filter);
+        let velocity = wall_jump_velocity(Vec3::new(0.0, -5.0, 0.0), normal, 15.0);
+        assert!((velocity.y - 15.0).abs() < 1e-4, "velocity {velocity}");
+        Vec3::new(velocity.x, 0.0, velocity.z).normalize()
+    }
+
+    #[test]
+    fn wall_jump_pushes_along_wall_normal() {
+        let direction = wall_jump_off(0.0, 0.55);
+        assert!(direction.abs_diff_eq(Vec3::NEG_X, 1e-4), "pushed towards {direction}");
+
+        // a tilted wall still pushes straight out horizontally
+        let direction = wall_jump_off(0.1, 0.58);
+        assert!(direction.abs_diff_eq(Vec3::NEG_X, 1e-4), "pushed towards {direction}");
+    }
+
+    #[test]
+    fn surface_info_reports_ground_contact() {
+        let (mut app, player) = test_world(Vec3::new(0.0, 10.0, 0.0));
+        step(&mut app, 1);
+        let floor = app
+            .world
+            .query_filtered::<Entity, (With<Collider>, Without<FpsController>)>()
+            .single(&app.world);
+
+        let context = app.world.resource::<RapierContext>();
+        let ground_shape = Collider::capsule(Vec3::Y * -0.5, Vec3::Y * 0.5, 0.45);
+        let wall_shape = Collider::cylinder(0.4, 0.6);
+        let surfaces_at = |y: f32| {
+            let transform = Transform::from_xyz(0.0, y, 0.0);
+            surface_info(
+                context,
+                &transform,
+                &ground_shape,
+                0.125,
+                &wall_shape,
+                self_filter_all_bodies(player),
+            )
+        };
+
+        // the capsule hovers 0.05 above the floor, well within the cast distance
+        let surfaces = surfaces_at(1.0);
+        let ground = surfaces.ground.expect("ground cast missed the floor");
+        assert_eq!(ground.entity, floor);
+        assert!((ground.distance - 0.05).abs() < 1e-3, "hit after {}", ground.distance);
+        assert!(!surfaces.on_wall());
+
+        // out of reach of the cast
+        assert_eq!(surfaces_at(2.0), SurfaceInfo::default());
+    }
+}
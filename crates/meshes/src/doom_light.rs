@@ -38,14 +38,9 @@ fn update_lights(
         let cam_pos =
             (tf_inverse_mat * Vec4::new(cam_tf.translation.x, cam_tf.translation.y, cam_tf.translation.z, 1.)).xyz();
 
-        // is there any reason to not hardcode this?
-        // let u = doom_light.verts[1] - doom_light.verts[0];
-        // let v = doom_light.verts[2] - doom_light.verts[0];
-        // let quad_normal = u.cross(v).normalize();
-        let quad_normal = Vec3::new(0., 0., 1.);
-
-        let ctr_pt: Vec3 =
-            0.25 * (doom_light.verts[0] + doom_light.verts[1] + doom_light.verts[2] + doom_light.verts[3]);
+        // computed from the corners since from_quad allows any quad, not just the default square facing +Z
+        let quad_normal = doom_light.normal();
+        let ctr_pt = doom_light.center();
 
         let dot = (ctr_pt - cam_pos).normalize().dot(quad_normal);
         let sign = dot.signum();
@@ -113,6 +108,9 @@ fn map(value: f32, in_min: f32, in_max: f32, out_min: f32, out_max: f32) -> f32
     value.clamp(out_min, out_max)
 }
 
+/// material for the glow quads. It is always `AlphaMode::Blend`, which also keeps the glows out of the occlusion
+/// prepass of `custom_pass`: transparent materials are never queued there, so doom lights don't occlude anything and
+/// aren't classified as prepass lights or occluders even if those components end up on them.
 #[derive(AsBindGroup, TypeUuid, Debug, Clone, TypePath, Asset)]
 #[uuid = "f690fdae-d598-45ab-8225-97e2a3f056e0"]
 pub struct DoomLightMaterial {}
@@ -138,7 +136,8 @@ impl Material for DoomLightMaterial {
     }
 }
 
-// requires a MaterialMeshBundle with the DoomLightMesh: meshes.add(Mesh::from(DoomLightMesh))
+// requires a MaterialMeshBundle with the DoomLightMesh: meshes.add(Mesh::from(DoomLightMesh)) or, for lights made with
+// DoomLight::from_quad, meshes.add(light.mesh())
 // and DoomLightMaterial: doom_materials.add(DoomLightMaterial {}),
 #[derive(Component, Reflect)]
 pub struct DoomLight {
@@ -150,32 +149,56 @@ pub struct DoomLight {
 
 impl Default for DoomLight {
     fn default() -> Self {
-        let verts: Vec<_> = vec![
-            Vec3::new(-1.0, -1.0, 0.0),
-            Vec3::new(1.0, -1.0, 0.0),
-            Vec3::new(1.0, 1.0, 0.0),
-            Vec3::new(-1.0, 1.0, 0.0),
-            Vec3::new(-1.0, -1.0, 0.0),
-            Vec3::new(1.0, -1.0, 0.0),
-            Vec3::new(1.0, 1.0, 0.0),
-            Vec3::new(-1.0, 1.0, 0.0),
-            Vec3::new(-1.0, -1.0, 0.0),
-            Vec3::new(1.0, -1.0, 0.0),
-            Vec3::new(1.0, 1.0, 0.0),
-            Vec3::new(-1.0, 1.0, 0.0),
-            Vec3::new(-1.0, -1.0, 0.0),
-            Vec3::new(1.0, -1.0, 0.0),
-            Vec3::new(1.0, 1.0, 0.0),
-            Vec3::new(-1.0, 1.0, 0.0),
-        ];
+        Self::from_quad(
+            [
+                Vec3::new(-1.0, -1.0, 0.0),
+                Vec3::new(1.0, -1.0, 0.0),
+                Vec3::new(1.0, 1.0, 0.0),
+                Vec3::new(-1.0, 1.0, 0.0),
+            ],
+            0.3,
+        )
+    }
+}
 
+impl DoomLight {
+    /// light for an arbitrary quad. `corners` are wound counter-clockwise when looking at the front of the quad and
+    /// don't have to form a square. Use `mesh()` for a matching mesh.
+    pub fn from_quad(corners: [Vec3; 4], push_distance: f32) -> Self {
         Self {
-            push_distance: 0.3,
+            push_distance,
             quad_color: Color::rgba(1., 1., 1., 1.),
             edge_color: Color::rgba(0., 1., 1., 0.),
-            verts,
+            // the quad followed by the flap vertices that update_lights extrudes from each corner
+            verts: corners.repeat(4),
         }
     }
+
+    /// light using the first 4 vertex positions of `mesh` as the quad corners
+    pub fn from_mesh(mesh: &Mesh, push_distance: f32) -> Option<Self> {
+        let Some(VertexAttributeValues::Float32x3(positions)) = mesh.attribute(Mesh::ATTRIBUTE_POSITION) else {
+            return None;
+        };
+
+        let corners: [[f32; 3]; 4] = positions.get(..4)?.try_into().ok()?;
+        Some(Self::from_quad(corners.map(Vec3::from), push_distance))
+    }
+
+    pub fn center(&self) -> Vec3 {
+        0.25 * (self.verts[0] + self.verts[1] + self.verts[2] + self.verts[3])
+    }
+
+    /// front facing normal of the quad
+    pub fn normal(&self) -> Vec3 {
+        (self.verts[1] - self.verts[0])
+            .cross(self.verts[2] - self.verts[0])
+            .normalize_or_zero()
+    }
+
+    /// mesh with the 16 vertices `update_lights` animates, starting out at this light's corners
+    pub fn mesh(&self) -> Mesh {
+        doom_light_mesh(self.verts.iter().map(|v| v.to_array()).collect())
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -183,35 +206,50 @@ pub struct DoomLightMesh;
 
 impl From<DoomLightMesh> for Mesh {
     fn from(_doom_light: DoomLightMesh) -> Self {
-        let positions = vec![
-            [-1.0, -1.0, 0.0],
-            [1.0, -1.0, 0.0],
-            [1.0, 1.0, 0.0],
-            [-1.0, 1.0, 0.0],
-            [-1.0, -1.0, 0.0],
-            [1.0, -1.0, 0.0],
-            [1.0, 1.0, 0.0],
-            [-1.0, 1.0, 0.0],
-            [-1.0, -1.0, 0.0],
-            [1.0, -1.0, 0.0],
-            [1.0, 1.0, 0.0],
-            [-1.0, 1.0, 0.0],
-            [-1.0, -1.0, 0.0],
-            [1.0, -1.0, 0.0],
-            [1.0, 1.0, 0.0],
-            [-1.0, 1.0, 0.0],
+        DoomLight::default().mesh()
+    }
+}
+
+fn doom_light_mesh(positions: Vec<[f32; 3]>) -> Mesh {
+    let indices = Indices::U32(vec![
+        0, 1, 2, 0, 2, 3, // quad
+        0, 5, 7, 0, 7, 1, 1, 8, 10, 1, 10, 2, 2, 11, 13, 2, 13, 3, 3, 14, 4, 3, 4, 0, // Flaps
+        0, 4, 6, 0, 6, 5, 1, 7, 9, 1, 9, 8, 2, 10, 12, 2, 12, 11, 3, 13, 15, 3, 15, 14, // Connections
+    ]);
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.set_indices(Some(indices));
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, vec![[1., 1., 1., 1.]; positions.len()]);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quad_center_and_normal() {
+        // trapezoid facing +Z, wider at the bottom
+        let corners = [
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(4.0, 0.0, 0.0),
+            Vec3::new(3.0, 2.0, 0.0),
+            Vec3::new(1.0, 2.0, 0.0),
         ];
+        let light = DoomLight::from_quad(corners, 0.3);
+        assert_eq!(light.center(), Vec3::new(2.0, 1.0, 0.0));
+        assert_eq!(light.normal(), Vec3::Z);
+        assert_eq!(light.verts.len(), 16);
+
+        let from_mesh = DoomLight::from_mesh(&light.mesh(), 0.3).unwrap();
+        assert_eq!(from_mesh.verts, light.verts);
+        assert_eq!(DoomLight::default().normal(), Vec3::Z);
+    }
 
-        let indices = Indices::U32(vec![
-            0, 1, 2, 0, 2, 3, // quad
-            0, 5, 7, 0, 7, 1, 1, 8, 10, 1, 10, 2, 2, 11, 13, 2, 13, 3, 3, 14, 4, 3, 4, 0, // Flaps
-            0, 4, 6, 0, 6, 5, 1, 7, 9, 1, 9, 8, 2, 10, 12, 2, 12, 11, 3, 13, 15, 3, 15, 14, // Connections
-        ]);
-
-        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
-        mesh.set_indices(Some(indices));
-        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, vec![[1., 1., 1., 1.]; positions.len()]);
-        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
-        mesh
+    #[test]
+    fn material_is_transparent() {
+        // the occlusion prepass skips blended materials, see DoomLightMaterial
+        assert_eq!(DoomLightMaterial {}.alpha_mode(), AlphaMode::Blend);
     }
 }
@@ -2,7 +2,10 @@ use std::f32::consts::PI;
 
 use bevy::{
     prelude::*,
-    render::{mesh::Indices, render_resource::PrimitiveTopology},
+    render::{
+        mesh::{Indices, VertexAttributeValues},
+        render_resource::PrimitiveTopology,
+    },
 };
 
 pub mod doom_light;
@@ -24,6 +27,15 @@ impl SphericalHelix {
             width,
         };
     }
+
+    /// Point on the center line of the helix at `t` in [0, 1] from its start to its end, along with the outward
+    /// normal of the sphere there. The mesh is a `width` wide strip around this line.
+    pub fn sample(&self, t: f32) -> (Vec3, Vec3) {
+        let a = t * self.twist * PI * 2.0;
+        let latitude = -PI / 2.0 + PI * t;
+        let position = Vec3::new(a.cos() * latitude.cos(), a.sin() * latitude.cos(), latitude.sin()) * self.radius;
+        (position, position.normalize())
+    }
 }
 
 impl Default for SphericalHelix {
@@ -61,22 +73,12 @@ impl From<SphericalHelix> for Mesh {
         let mut normals = vec![Vec3::ZERO; helix.steps];
         let mut tangents = vec![Vec3::ZERO; helix.steps];
 
-        let a_max = helix.twist * std::f32::consts::PI * 2.0;
         for i in 0..helix.steps {
-            let a = i as f32 / (helix.steps as f32 - 1.0) * a_max;
-            let a_div_amax_pi = std::f32::consts::PI * (a / a_max);
-            let x = helix.radius * a.cos() * (-std::f32::consts::PI / 2.0 + a_div_amax_pi).cos();
-            let y = helix.radius * a.sin() * (-std::f32::consts::PI / 2.0 + a_div_amax_pi).cos();
-            let z = helix.radius * (-std::f32::consts::PI / 2.0 + a_div_amax_pi).sin();
-
-            spiral_pts[i] = Vec3 { x: x, y: y, z: z };
+            (spiral_pts[i], normals[i]) = helix.sample(i as f32 / (helix.steps as f32 - 1.0));
         }
 
-        // tangents and normals
+        // tangents
         for i in 0..helix.steps {
-            // edge
-            normals[i] = spiral_pts[i].normalize();
-
             let binormal = {
                 if i < spiral_pts.len() - 1 {
                     spiral_pts[i + 1] - spiral_pts[i]
@@ -125,10 +127,26 @@ impl From<SphericalHelix> for Mesh {
     }
 }
 
+/// Flat ring of radius 1 around the z axis, `width` wide.
 #[derive(Debug, Clone)]
 pub struct Ring {
     pub sides: usize,
     pub width: f32,
+    pub topology: RingTopology,
+    /// extrude the ring `depth` along z and close its inner and outer edges, making a watertight tube that can be
+    /// used as a collider. Only used with `RingTopology::Indexed`.
+    pub capped: bool,
+    pub depth: f32,
+}
+
+/// triangle layout of a [`Ring`] mesh
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RingTopology {
+    /// a closed `TriangleStrip` without indices
+    #[default]
+    Strip,
+    /// an indexed `TriangleList`, which colliders and merging meshes need
+    Indexed,
 }
 
 impl Ring {
@@ -136,18 +154,39 @@ impl Ring {
         return Ring {
             sides: sides,
             width: width,
+            ..default()
         };
     }
 }
 
+impl Ring {
+    /// Point on the outer edge of the ring at `angle` radians, going clockwise around z starting at +y like the mesh,
+    /// along with the outward normal the mesh uses there. For a capped ring that is the normal of its outer wall.
+    pub fn sample(&self, angle: f32) -> (Vec3, Vec3) {
+        let (x, y) = angle.sin_cos();
+        let normal = Vec3::new(x, y, 0.0);
+        (normal * (1.0 + self.width * 0.5), normal)
+    }
+}
+
 impl Default for Ring {
     fn default() -> Self {
-        return Ring { sides: 10, width: 0.5 };
+        return Ring {
+            sides: 10,
+            width: 0.5,
+            topology: RingTopology::Strip,
+            capped: false,
+            depth: 0.1,
+        };
     }
 }
 
 impl From<Ring> for Mesh {
     fn from(ring: Ring) -> Mesh {
+        if ring.topology == RingTopology::Indexed {
+            return indexed_ring(&ring);
+        }
+
         let mut positions: Vec<[f32; 3]> = Vec::with_capacity(ring.sides * 2 + 2);
         let mut normals: Vec<[f32; 3]> = Vec::with_capacity(ring.sides * 2 + 2);
         let mut uvs: Vec<[f32; 2]> = Vec::with_capacity(ring.sides * 2 + 2);
@@ -187,11 +226,73 @@ impl From<Ring> for Mesh {
     }
 }
 
+/// `Ring` as a `TriangleList`. Uncapped it has the same triangles as the strip, capped every face gets its own
+/// vertices so the tube stays flat shaded and the faces only share positions along their edges.
+fn indexed_ring(ring: &Ring) -> Mesh {
+    let mut positions: Vec<[f32; 3]> = Vec::new();
+    let mut normals: Vec<[f32; 3]> = Vec::new();
+    let mut uvs: Vec<[f32; 2]> = Vec::new();
+    let mut indices: Vec<u32> = Vec::new();
+
+    let angular_step = 2.0 * PI / ring.sides as f32;
+
+    // a band of quads from the circle `a` to the circle `b`, each given as (radius, z). The ring winds clockwise
+    // around z so the faces point along (b - a) x tangent.
+    let mut band = |a: (f32, f32), b: (f32, f32), vertex_normals: &dyn Fn(Vec3) -> [Vec3; 2]| {
+        let first = positions.len() as u32;
+        for i in 0..=ring.sides {
+            // the seam repeats the first vertices exactly so the edges line up
+            let angle = if i == ring.sides { 0.0 } else { angular_step * i as f32 };
+            let (x, y) = angle.sin_cos();
+            let dir = Vec3::new(x, y, 0.0);
+
+            let phase = i as f32 / ring.sides as f32;
+            positions.extend([dir * a.0 + Vec3::Z * a.1, dir * b.0 + Vec3::Z * b.1].map(<[f32; 3]>::from));
+            normals.extend(vertex_normals(dir).map(<[f32; 3]>::from));
+            uvs.extend([[phase, 1.0], [phase, 0.0]]);
+        }
+
+        for side in 0..ring.sides as u32 {
+            let a = first + side * 2;
+            let b = a + 1;
+            indices.extend([a, b, a + 2, a + 2, b, b + 2]);
+        }
+    };
+
+    let (inner, outer) = (1.0 - ring.width * 0.5, 1.0 + ring.width * 0.5);
+    if ring.capped {
+        let half_depth = ring.depth * 0.5;
+        band((outer, half_depth), (inner, half_depth), &|_| [Vec3::Z; 2]);
+        band((inner, -half_depth), (outer, -half_depth), &|_| [Vec3::NEG_Z; 2]);
+        band((outer, -half_depth), (outer, half_depth), &|dir| [dir; 2]);
+        band((inner, half_depth), (inner, -half_depth), &|dir| [-dir; 2]);
+    } else {
+        band((inner, 0.0), (outer, 0.0), &|dir| [-dir, dir]);
+    }
+
+    let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+    mesh.set_indices(Some(Indices::U32(indices)));
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    mesh
+}
+
+/// Cone standing on the xz plane with its tip at `height` on the y axis.
+///
+/// With `smooth` normals each side triangle gets its own tip vertex whose normal
+/// points out from the middle of that triangle, since a single shared tip has no
+/// meaningful normal. `smooth: false` gives every side triangle its own flat face
+/// normal.
 #[derive(Debug, Clone, Copy)]
 pub struct Cone {
     pub radius: f32,
     pub height: f32,
     pub subdivisions: usize,
+    /// close the bottom with a flat base
+    pub capped: bool,
+    /// averaged normals around the side instead of one normal per face
+    pub smooth: bool,
 }
 
 impl Default for Cone {
@@ -200,6 +301,8 @@ impl Default for Cone {
             radius: 1.0,
             height: 1.0,
             subdivisions: 32,
+            capped: true,
+            smooth: true,
         }
     }
 }
@@ -209,57 +312,65 @@ impl From<Cone> for Mesh {
         // code adapted from http://apparat-engine.blogspot.com/2013/04/procedural-meshes-torus.html
         // (source code at https://github.com/SEilers/Apparat)
 
-        let n_vertices = cone.subdivisions + 2;
+        let n_vertices = cone.subdivisions * 3 + if cone.capped { cone.subdivisions + 2 } else { 0 };
         let mut positions: Vec<[f32; 3]> = Vec::with_capacity(n_vertices);
         let mut normals: Vec<[f32; 3]> = Vec::with_capacity(n_vertices);
         let mut uvs: Vec<[f32; 2]> = Vec::with_capacity(n_vertices);
+        let mut indices: Vec<u32> = Vec::with_capacity(cone.subdivisions * if cone.capped { 6 } else { 3 });
 
         let side_stride = 2.0 * std::f32::consts::PI / cone.subdivisions as f32;
-
-        // Cone tip
-        positions.push([0.0, cone.height, 0.0]);
-        normals.push(Vec3::Y.into());
-        uvs.push([0.0, 1.0]);
-        // Bottom center
-        positions.push([0.0, 0.0, 0.0]);
-        normals.push(Vec3::new(0.0, -1.0, 0.0).into());
-        uvs.push([0.0, -1.0]);
-
-        for side in 0..=cone.subdivisions {
-            let phi = side_stride * side as f32;
-            let x = phi.cos() * cone.radius;
-            let y = 0.0;
-            let z = phi.sin() * cone.radius;
-
-            let vertex = Vec3::new(x, y, z);
-            let tangent = vertex.normalize().cross(Vec3::Y).normalize();
-            let edge = (Vec3::Y - vertex).normalize();
-            let normal = edge.cross(tangent).normalize();
-
-            positions.push([x, y, z]);
-            normals.push(normal.into());
-            uvs.push([side as f32 / cone.subdivisions as f32, 0.0]);
+        let rim = |phi: f32| Vec3::new(phi.cos() * cone.radius, 0.0, phi.sin() * cone.radius);
+        // perpendicular to the slant of the side at angle phi
+        let side_normal =
+            |phi: f32| Vec3::new(phi.cos() * cone.height, cone.radius, phi.sin() * cone.height).normalize();
+
+        for side in 0..cone.subdivisions {
+            let (phi_right, phi_left) = (side_stride * side as f32, side_stride * (side + 1) as f32);
+            let (u_right, u_left) = (
+                side as f32 / cone.subdivisions as f32,
+                (side + 1) as f32 / cone.subdivisions as f32,
+            );
+            let tip = Vec3::new(0.0, cone.height, 0.0);
+            let (right, left) = (rim(phi_right), rim(phi_left));
+
+            let (n_tip, n_left, n_right) = if cone.smooth {
+                (
+                    side_normal((phi_right + phi_left) * 0.5),
+                    side_normal(phi_left),
+                    side_normal(phi_right),
+                )
+            } else {
+                let face = (left - tip).cross(right - tip).normalize();
+                (face, face, face)
+            };
+
+            // every slice has its own tip and rim vertices so the side texture wraps from u = 0 to u = 1 without
+            // pinching at the tip or mirroring at the seam
+            let first = positions.len() as u32;
+            positions.extend([tip, left, right].map(<[f32; 3]>::from));
+            normals.extend([n_tip, n_left, n_right].map(<[f32; 3]>::from));
+            uvs.extend([[(u_right + u_left) * 0.5, 1.0], [u_left, 0.0], [u_right, 0.0]]);
+            indices.extend([first, first + 1, first + 2]);
         }
 
-        let n_triangles = cone.subdivisions * 2;
-        let n_indices = n_triangles * 3;
-
-        let mut indices: Vec<u32> = Vec::with_capacity(n_indices);
-
-        for point in 2..cone.subdivisions + 2 {
-            let top = 0;
-            let bottom = 1;
-
-            let left = point + 1;
-            let right = point;
-
-            indices.push(top as u32);
-            indices.push(left as u32);
-            indices.push(right as u32);
+        if cone.capped {
+            // the base gets its own ring of vertices so it stays flat shaded
+            let bottom = positions.len() as u32;
+            positions.push([0.0, 0.0, 0.0]);
+            normals.push(Vec3::NEG_Y.into());
+            uvs.push([0.5, 0.5]);
+
+            for side in 0..=cone.subdivisions {
+                let phi = side_stride * side as f32;
+                positions.push(rim(phi).into());
+                normals.push(Vec3::NEG_Y.into());
+                uvs.push([0.5 + phi.cos() * 0.5, 0.5 + phi.sin() * 0.5]);
+            }
 
-            indices.push(bottom as u32);
-            indices.push(right as u32);
-            indices.push(left as u32);
+            for side in 0..cone.subdivisions as u32 {
+                let right = bottom + 1 + side;
+                indices.extend([bottom, right, right + 1]);
+            }
         }
 
         let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
@@ -270,3 +381,216 @@ impl From<Cone> for Mesh {
         mesh
     }
 }
+
+/// Flips triangles of a `TriangleList` mesh whose winding disagrees with the stored normals, e.g. inside-out map
+/// brushes that only render back faces. A triangle is flipped when its counter-clockwise face normal points away from
+/// the average of its vertex normals. Meshes without indices get them added. Returns the number of flipped triangles.
+pub fn fix_winding(mesh: &mut Mesh) -> usize {
+    if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+        return 0;
+    }
+
+    let (Some(VertexAttributeValues::Float32x3(positions)), Some(VertexAttributeValues::Float32x3(normals))) = (
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION),
+        mesh.attribute(Mesh::ATTRIBUTE_NORMAL),
+    ) else {
+        return 0;
+    };
+
+    let mut indices: Vec<u32> = match mesh.indices() {
+        Some(indices) => indices.iter().map(|i| i as u32).collect(),
+        None => (0..positions.len() as u32).collect(),
+    };
+
+    let mut flipped = 0;
+    for triangle in indices.chunks_exact_mut(3) {
+        let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|i| Vec3::from(positions[i as usize]));
+        let face = (b - a).cross(c - a);
+        let stored: Vec3 = triangle.iter().map(|&i| Vec3::from(normals[i as usize])).sum();
+        if face.dot(stored) < 0.0 {
+            triangle.swap(1, 2);
+            flipped += 1;
+        }
+    }
+
+    if flipped > 0 || mesh.indices().is_none() {
+        let indices = match mesh.indices() {
+            Some(Indices::U16(_)) => Indices::U16(indices.into_iter().map(|i| i as u16).collect()),
+            _ => Indices::U32(indices),
+        };
+        mesh.set_indices(Some(indices));
+    }
+    flipped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangle_count(mesh: &Mesh) -> usize {
+        mesh.indices().unwrap().len() / 3
+    }
+
+    #[test]
+    fn uncapped_cone_has_no_base() {
+        let capped = Mesh::from(Cone::default());
+        let open = Mesh::from(Cone {
+            capped: false,
+            ..default()
+        });
+
+        assert_eq!(triangle_count(&capped), 64);
+        assert_eq!(triangle_count(&open), 32);
+
+        let Some(bevy::render::mesh::VertexAttributeValues::Float32x3(normals)) =
+            open.attribute(Mesh::ATTRIBUTE_NORMAL)
+        else {
+            panic!("cone without normals");
+        };
+        assert!(normals.iter().all(|n| n[1] > 0.0));
+    }
+
+    #[test]
+    fn side_uvs_wrap_monotonically() {
+        let cone = Cone {
+            subdivisions: 8,
+            capped: false,
+            ..default()
+        };
+        let mesh = Mesh::from(cone);
+        let Some(bevy::render::mesh::VertexAttributeValues::Float32x2(uvs)) = mesh.attribute(Mesh::ATTRIBUTE_UV_0)
+        else {
+            panic!("cone without uvs");
+        };
+
+        // each slice is [tip, left, right]
+        let mut last_u = 0.0;
+        for slice in uvs.chunks(3) {
+            let [tip, left, right] = [slice[0], slice[1], slice[2]];
+            assert_eq!(right[0], last_u);
+            assert!(left[0] > right[0]);
+            assert!(tip[0] > right[0] && tip[0] < left[0]);
+            last_u = left[0];
+        }
+        assert_eq!(last_u, 1.0);
+    }
+
+    fn float3s(mesh: &Mesh, attribute: bevy::render::mesh::MeshVertexAttribute) -> &[[f32; 3]] {
+        let name = attribute.name;
+        let Some(VertexAttributeValues::Float32x3(values)) = mesh.attribute(attribute) else {
+            panic!("mesh without {name}");
+        };
+        values
+    }
+
+    #[test]
+    fn helix_samples_match_mesh_ends() {
+        let helix = SphericalHelix::default();
+        let mesh = Mesh::from(helix.clone());
+        let (positions, normals) = (
+            float3s(&mesh, Mesh::ATTRIBUTE_POSITION),
+            float3s(&mesh, Mesh::ATTRIBUTE_NORMAL),
+        );
+        // the strip has two vertices per step, either side of the center line
+        let center = |step: usize| (Vec3::from(positions[step * 2]) + Vec3::from(positions[step * 2 + 1])) * 0.5;
+        let last = helix.steps - 1;
+
+        let (start, start_normal) = helix.sample(0.0);
+        let (end, end_normal) = helix.sample(1.0);
+        assert!(start.abs_diff_eq(center(0), 1e-5));
+        assert!(end.abs_diff_eq(center(last), 1e-5));
+        assert!(end_normal.abs_diff_eq(Vec3::from(normals[last * 2]), 1e-5));
+        // the mesh smooths the normals with the next step, which leaves only the last one as is
+        assert!(start_normal.dot(Vec3::from(normals[0])) > 0.99);
+    }
+
+    #[test]
+    fn ring_samples_match_outer_vertices() {
+        let ring = Ring::default();
+        let mesh = Mesh::from(ring.clone());
+        let (positions, normals) = (
+            float3s(&mesh, Mesh::ATTRIBUTE_POSITION),
+            float3s(&mesh, Mesh::ATTRIBUTE_NORMAL),
+        );
+
+        // every second vertex of the strip is on the outer edge, the last one closes the ring at a full turn
+        let angular_step = 2.0 * PI / ring.sides as f32;
+        for side in 0..=ring.sides {
+            let (position, normal) = ring.sample(angular_step * side as f32);
+            assert!(position.abs_diff_eq(Vec3::from(positions[side * 2 + 1]), 1e-5));
+            assert!(normal.abs_diff_eq(Vec3::from(normals[side * 2 + 1]), 1e-5));
+        }
+    }
+
+    #[test]
+    fn capped_indexed_ring_is_watertight() {
+        // every directed edge of a closed, consistently wound mesh shows up once in each direction
+        fn open_edges(mesh: &Mesh) -> usize {
+            let positions = float3s(mesh, Mesh::ATTRIBUTE_POSITION);
+            // faces have their own vertices, so edges are matched by position
+            let key = |i: usize| positions[i].map(|c| (c * 1e4).round() as i32);
+            let indices: Vec<usize> = mesh.indices().unwrap().iter().collect();
+
+            let mut edges = std::collections::HashMap::new();
+            for triangle in indices.chunks_exact(3) {
+                for (from, to) in [(0, 1), (1, 2), (2, 0)] {
+                    *edges.entry((key(triangle[from]), key(triangle[to]))).or_insert(0) += 1;
+                }
+            }
+            edges
+                .iter()
+                .filter(|((from, to), count)| **count != 1 || edges.get(&(*to, *from)) != Some(&1))
+                .count()
+        }
+
+        let ring = Ring {
+            topology: RingTopology::Indexed,
+            ..default()
+        };
+        let flat = Mesh::from(ring.clone());
+        assert_eq!(flat.primitive_topology(), PrimitiveTopology::TriangleList);
+        assert_eq!(triangle_count(&flat), 20);
+        assert!(open_edges(&flat) > 0);
+
+        let tube = Mesh::from(Ring { capped: true, ..ring });
+        assert_eq!(triangle_count(&tube), 80);
+        assert_eq!(open_edges(&tube), 0);
+        // the winding agrees with the normals, so it points out of the tube
+        assert_eq!(fix_winding(&mut tube.clone()), 0);
+    }
+
+    #[test]
+    fn fix_winding_flips_inverted_triangle() {
+        // two triangles facing +Y, the second one wound clockwise
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        mesh.insert_attribute(
+            Mesh::ATTRIBUTE_POSITION,
+            vec![[0.0, 0.0, 0.0], [0.0, 0.0, 1.0], [1.0, 0.0, 0.0], [1.0, 0.0, 1.0]],
+        );
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0.0, 1.0, 0.0]; 4]);
+        mesh.set_indices(Some(Indices::U16(vec![0, 1, 2, 2, 3, 1])));
+
+        assert_eq!(fix_winding(&mut mesh), 1);
+        let indices: Vec<usize> = mesh.indices().unwrap().iter().collect();
+        assert_eq!(indices, [0, 1, 2, 2, 1, 3]);
+        assert!(matches!(mesh.indices(), Some(Indices::U16(_))));
+
+        assert_eq!(fix_winding(&mut mesh), 0);
+        assert_eq!(fix_winding(&mut Mesh::from(Cone::default())), 0);
+    }
+
+    #[test]
+    fn fix_winding_indexes_unindexed_mesh() {
+        // a single triangle facing +Y that is already wound correctly
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        mesh.insert_attribute(
+            Mesh::ATTRIBUTE_POSITION,
+            vec![[0.0, 0.0, 0.0], [0.0, 0.0, 1.0], [1.0, 0.0, 0.0]],
+        );
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, vec![[0.0, 1.0, 0.0]; 3]);
+
+        assert_eq!(fix_winding(&mut mesh), 0);
+        let indices: Vec<usize> = mesh.indices().unwrap().iter().collect();
+        assert_eq!(indices, [0, 1, 2]);
+    }
+}
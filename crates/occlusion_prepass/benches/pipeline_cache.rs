@@ -0,0 +1,59 @@
+//! Queues many occluders sharing a single material, once doing the `SpecializedMeshPipelines` lookup for each of them
+//! and once going through `LastPipelineCache`.
+//!
+//! `cargo bench -p occlusion_prepass --bench pipeline_cache`
+
+use std::{hint::black_box, time::Instant};
+
+use bevy::{
+    pbr::{MaterialPipelineKey, MeshPipelineKey, StandardMaterialKey},
+    prelude::{shape, Mesh, StandardMaterial},
+    render::render_resource::CachedRenderPipelineId,
+    utils::HashMap,
+};
+use occlusion_prepass::pipeline_cache::LastPipelineCache;
+
+const OCCLUDERS: u32 = 10_000;
+const FRAMES: u32 = 200;
+
+fn bench(name: &str, mut queue_frame: impl FnMut()) {
+    queue_frame();
+
+    let start = Instant::now();
+    for _ in 0..FRAMES {
+        queue_frame();
+    }
+    let per_frame = start.elapsed() / FRAMES;
+    println!(
+        "{name:>20}: {per_frame:?} per frame, {:?} per occluder",
+        per_frame / OCCLUDERS
+    );
+}
+
+fn main() {
+    let layout = Mesh::from(shape::Cube::default()).get_mesh_vertex_buffer_layout();
+    let key = MaterialPipelineKey::<StandardMaterial> {
+        mesh_key: MeshPipelineKey::from_msaa_samples(4) | MeshPipelineKey::DEPTH_PREPASS,
+        bind_group_data: StandardMaterialKey::from(&StandardMaterial::default()),
+    };
+
+    // stands in for the cache `SpecializedMeshPipelines::specialize` looks the pipeline up in
+    let mut specialized = HashMap::default();
+    specialized.insert((key.clone(), layout.clone()), CachedRenderPipelineId::INVALID);
+
+    bench("specialize", || {
+        for _ in 0..OCCLUDERS {
+            black_box(specialized.get(&(black_box(key.clone()), layout.clone())));
+        }
+    });
+
+    bench("last pipeline cache", || {
+        let mut cache = LastPipelineCache::default();
+        for _ in 0..OCCLUDERS {
+            let id = cache.get_or_specialize(black_box(key.clone()), &layout, |key| {
+                Ok(specialized[&(key, layout.clone())])
+            });
+            black_box(id.ok());
+        }
+    });
+}
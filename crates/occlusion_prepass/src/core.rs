@@ -1,17 +1,67 @@
-use std::cmp::Reverse;
+use std::{
+    cmp::Reverse,
+    hash::{Hash, Hasher},
+};
 
 use bevy::prelude::*;
 use bevy::reflect::Reflect;
 use bevy::render::{
+    extract_component::ExtractComponent,
+    extract_resource::ExtractResource,
     render_phase::{CachedRenderPipelinePhaseItem, DrawFunctionId, PhaseItem},
     render_resource::{CachedRenderPipelineId, Extent3d, TextureFormat},
     texture::CachedTexture,
+    view::{ExtractedView, Msaa},
 };
-use bevy::utils::FloatOrd;
+use bevy::utils::{AHasher, FloatOrd, HashMap};
 
 pub const DEPTH_PREPASS_FORMAT: TextureFormat = TextureFormat::Depth32Float;
 pub const NORMAL_PREPASS_FORMAT: TextureFormat = TextureFormat::Rgb10a2Unorm;
 
+/// The sample count used for the prepass textures and their fallbacks. `Msaa::Off` takes the single-sample path without
+/// querying the device. Otherwise `depth_supports` is asked whether [`DEPTH_PREPASS_FORMAT`] can be multisampled that
+/// many times, since `Depth32Float` MSAA support varies per device.
+///
+/// # Panics
+///
+/// Panics if the sample count isn't supported by the depth format, instead of failing later with a GPU validation error.
+pub fn prepass_sample_count(msaa: Msaa, depth_supports: impl FnOnce(u32) -> bool) -> u32 {
+    if msaa == Msaa::Off {
+        return 1;
+    }
+
+    let samples = msaa.samples();
+    assert!(
+        depth_supports(samples),
+        "the occlusion prepass depth format {DEPTH_PREPASS_FORMAT:?} does not support {samples}x MSAA on this device. \
+         Lower the `Msaa` setting or remove the prepass components from the camera."
+    );
+    samples
+}
+
+/// Toggles the whole prepass at runtime (e.g. from a quality setting) without removing any plugins. When disabled no
+/// prepass phases are extracted and no meshes are queued. Enabled by default.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq, ExtractResource, Reflect)]
+#[reflect(Resource)]
+pub struct PrepassEnabled(pub bool);
+
+impl Default for PrepassEnabled {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+/// The format of the normal prepass texture and of the matching pipeline fragment target. Defaults to
+/// [`NORMAL_PREPASS_FORMAT`]; insert it before adding the prepass plugins to use e.g. `Rgba16Float` for more precision.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PrepassNormalFormat(pub TextureFormat);
+
+impl Default for PrepassNormalFormat {
+    fn default() -> Self {
+        Self(NORMAL_PREPASS_FORMAT)
+    }
+}
+
 /// If added to a [`crate::prelude::Camera3d`] then depth values will be copied to a separate texture available to the main pass.
 #[derive(Component, Default, Reflect)]
 pub struct OcclusionDepthPrepass;
@@ -21,6 +71,132 @@ pub struct OcclusionDepthPrepass;
 #[derive(Component, Default, Reflect)]
 pub struct OcclusionNormalPrepass;
 
+/// Marks a mesh as a static occluder. Static occluders are drawn once into a cached depth texture that is copied into
+/// the prepass depth each frame, so only the dynamic occluders get redrawn. The cache is redrawn once the camera, the
+/// texture size or the visible static occluders (added, removed, moved or given another mesh) stop changing. While
+/// they change from frame to frame, e.g. for a moving camera, the cache is bypassed and static occluders are drawn
+/// like any other.
+///
+/// Only opaque occluders in views without an [`OcclusionNormalPrepass`] are cached, otherwise static occluders are
+/// drawn every frame like any other.
+#[derive(Component, Clone, Copy, Default, Reflect, ExtractComponent)]
+pub struct StaticOccluder;
+
+/// Where an occluder is queued in the prepass of a view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OccluderQueue {
+    /// drawn into the prepass every frame
+    Dynamic,
+    /// drawn into the static depth cache, which is out of date this frame
+    Static,
+    /// already in the static depth cache
+    Cached,
+}
+
+impl OccluderQueue {
+    /// `static_dirty` is [`ViewStaticPrepass::dirty`] for views using the cache
+    pub fn new(cacheable: bool, static_dirty: Option<bool>) -> Self {
+        match static_dirty {
+            Some(true) if cacheable => Self::Static,
+            Some(false) if cacheable => Self::Cached,
+            _ => Self::Dynamic,
+        }
+    }
+}
+
+/// The static occluder depth cache of a view for the current frame. Only present on views that have visible
+/// [`StaticOccluder`]s, can cache them and whose cache key didn't change since the last frame.
+#[derive(Component, Clone)]
+pub struct ViewStaticPrepass {
+    /// depth of the static occluders, copied into the prepass depth texture before the dynamic occluders are drawn
+    pub texture: CachedTexture,
+    /// the static occluders changed since `texture` was drawn so they are drawn again this frame
+    pub dirty: bool,
+}
+
+/// The static occluder depth caches of all views. Render world entities are cleared every frame so the caches are kept
+/// in a resource, keyed by the view entity, and handed to the views as a [`ViewStaticPrepass`] each frame.
+#[derive(Resource, Default)]
+pub struct StaticPrepassCaches(pub HashMap<Entity, StaticPrepassCache>);
+
+/// See [`StaticPrepassCaches`]. Generic over the texture only so the bookkeeping can be tested without a GPU.
+pub struct StaticPrepassCache<T = CachedTexture> {
+    pub texture: T,
+    /// hash of everything the texture was drawn from, see [`static_prepass_key`]
+    key: Option<u64>,
+    /// key of the previous frame, the cache is only redrawn once the key stops changing
+    last_key: Option<u64>,
+    /// key the texture is being redrawn from this frame, see [`StaticPrepassCache::finish`]
+    drawing: Option<u64>,
+}
+
+impl<T> StaticPrepassCache<T> {
+    pub fn new(texture: T) -> Self {
+        Self {
+            texture,
+            key: None,
+            last_key: None,
+            drawing: None,
+        }
+    }
+
+    /// stores the key of this frame and returns whether the texture is out of date, or `None` if the cache should be
+    /// bypassed this frame because the key changed since the last one. Redrawing and copying the cache every frame
+    /// would cost more than drawing the static occluders with the dynamic ones.
+    pub fn update(&mut self, key: u64) -> Option<bool> {
+        let stable = self.last_key.replace(key) == Some(key);
+        self.drawing = None;
+        if self.key == Some(key) {
+            Some(false)
+        } else if stable {
+            // the texture is cleared before being redrawn, it only holds `key` once `finish` confirms it
+            self.key = None;
+            self.drawing = Some(key);
+            Some(true)
+        } else {
+            None
+        }
+    }
+
+    /// called once the static occluders of a dirty frame were queued. The texture only counts as up to date when
+    /// `pipelines_ready`, since items whose pipeline is still compiling are skipped when drawing. Otherwise it stays
+    /// dirty and is drawn again next frame.
+    pub fn finish(&mut self, pipelines_ready: bool) {
+        if let Some(key) = self.drawing.take() {
+            if pipelines_ready {
+                self.key = Some(key);
+            }
+        }
+    }
+}
+
+/// hashes everything the static depth of a view depends on: the view itself, the texture size and each visible static
+/// occluder with its transform and mesh. The order of the occluders doesn't matter, `VisibleEntities` are collected in
+/// parallel and change order from frame to frame.
+pub fn static_prepass_key<'a>(
+    view: &ExtractedView,
+    size: Extent3d,
+    occluders: impl IntoIterator<Item = (Entity, &'a Mat4, &'a Handle<Mesh>)>,
+) -> u64 {
+    let mut hasher = AHasher::default();
+    let hash_matrix = |matrix: &Mat4, hasher: &mut AHasher| {
+        matrix.to_cols_array().map(f32::to_bits).hash(hasher);
+    };
+
+    hash_matrix(&view.transform.compute_matrix(), &mut hasher);
+    hash_matrix(&view.projection, &mut hasher);
+    size.hash(&mut hasher);
+
+    let mut occluders: Vec<_> = occluders.into_iter().collect();
+    occluders.sort_unstable_by_key(|(entity, ..)| *entity);
+    for (entity, transform, mesh) in occluders {
+        entity.hash(&mut hasher);
+        hash_matrix(transform, &mut hasher);
+        mesh.id().hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
 /// Textures that are written to by the prepass.
 ///
 /// This component will only be present if any of the relevant prepass components are also present.
@@ -125,3 +301,143 @@ impl CachedRenderPipelinePhaseItem for AlphaMask3dPrepass {
         self.pipeline_id
     }
 }
+
+/// Opaque [`StaticOccluder`]s drawn into the [`ViewStaticPrepass`] texture on the frames it is out of date.
+///
+/// Sorted front-to-back by the z-distance in front of the camera.
+pub struct StaticOpaque3dPrepass {
+    pub distance: f32,
+    pub entity: Entity,
+    pub pipeline_id: CachedRenderPipelineId,
+    pub draw_function: DrawFunctionId,
+}
+
+impl PhaseItem for StaticOpaque3dPrepass {
+    // NOTE: Values increase towards the camera. Front-to-back ordering for opaque means we need a descending sort.
+    type SortKey = Reverse<FloatOrd>;
+
+    #[inline]
+    fn entity(&self) -> Entity {
+        self.entity
+    }
+
+    #[inline]
+    fn sort_key(&self) -> Self::SortKey {
+        Reverse(FloatOrd(self.distance))
+    }
+
+    #[inline]
+    fn draw_function(&self) -> DrawFunctionId {
+        self.draw_function
+    }
+
+    #[inline]
+    fn sort(items: &mut [Self]) {
+        // Key negated to match reversed SortKey ordering
+        radsort::sort_by_key(items, |item| -item.distance);
+    }
+}
+
+impl CachedRenderPipelinePhaseItem for StaticOpaque3dPrepass {
+    #[inline]
+    fn cached_pipeline(&self) -> CachedRenderPipelineId {
+        self.pipeline_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::render::render_resource::WgpuFeatures;
+
+    #[test]
+    fn msaa_off_selects_single_sample() {
+        assert_eq!(
+            prepass_sample_count(Msaa::Off, |_| panic!("Msaa::Off should not query the device")),
+            1
+        );
+
+        let features = DEPTH_PREPASS_FORMAT.guaranteed_format_features(WgpuFeatures::empty());
+        assert_eq!(
+            prepass_sample_count(Msaa::Sample4, |samples| features.flags.sample_count_supported(samples)),
+            4
+        );
+    }
+
+    #[test]
+    fn static_prepass_key_ignores_occluder_order() {
+        let view = ExtractedView {
+            projection: Mat4::perspective_infinite_reverse_rh(1.0, 1.0, 0.1),
+            transform: GlobalTransform::IDENTITY,
+            view_projection: None,
+            hdr: false,
+            viewport: UVec4::new(0, 0, 64, 64),
+            color_grading: default(),
+        };
+        let size = Extent3d {
+            width: 64,
+            height: 64,
+            depth_or_array_layers: 1,
+        };
+        let mesh = Handle::<Mesh>::default();
+        let (a, b) = (Entity::from_raw(1), Entity::from_raw(2));
+        let moved = Mat4::from_translation(Vec3::X);
+        let key = |occluders: &[(Entity, &Mat4)]| {
+            static_prepass_key(
+                &view,
+                size,
+                occluders.iter().map(|(e, transform)| (*e, *transform, &mesh)),
+            )
+        };
+
+        let ab = key(&[(a, &Mat4::IDENTITY), (b, &Mat4::IDENTITY)]);
+        assert_eq!(ab, key(&[(b, &Mat4::IDENTITY), (a, &Mat4::IDENTITY)]));
+        assert_ne!(ab, key(&[(a, &Mat4::IDENTITY), (b, &moved)]));
+        assert_ne!(ab, key(&[(a, &Mat4::IDENTITY)]));
+    }
+
+    #[test]
+    fn static_prepass_cache_redraws_once_key_is_stable() {
+        let mut cache = StaticPrepassCache::new(());
+
+        // a changing key, e.g. from a moving camera, bypasses the cache
+        assert_eq!(cache.update(1), None);
+        assert_eq!(cache.update(2), None);
+
+        // the same key twice redraws it, but it stays dirty while pipelines are pending
+        assert_eq!(cache.update(2), Some(true));
+        cache.finish(false);
+        assert_eq!(cache.update(2), Some(true));
+        cache.finish(true);
+        assert_eq!(cache.update(2), Some(false));
+        cache.finish(true);
+        assert_eq!(cache.update(2), Some(false));
+
+        // a new key is dirty again once it stops changing
+        assert_eq!(cache.update(3), None);
+        assert_eq!(cache.update(3), Some(true));
+        cache.finish(true);
+        assert_eq!(cache.update(3), Some(false));
+
+        // going back to a key whose redraw never finished doesn't reuse the partially drawn texture
+        assert_eq!(cache.update(4), None);
+        assert_eq!(cache.update(4), Some(true));
+        assert_eq!(cache.update(3), None);
+        assert_eq!(cache.update(3), Some(true));
+    }
+
+    #[test]
+    fn occluder_queue_follows_cache_state() {
+        assert_eq!(OccluderQueue::new(true, Some(true)), OccluderQueue::Static);
+        assert_eq!(OccluderQueue::new(true, Some(false)), OccluderQueue::Cached);
+        assert_eq!(OccluderQueue::new(true, None), OccluderQueue::Dynamic);
+        assert_eq!(OccluderQueue::new(false, Some(true)), OccluderQueue::Dynamic);
+        assert_eq!(OccluderQueue::new(false, Some(false)), OccluderQueue::Dynamic);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not support 8x MSAA")]
+    fn unsupported_sample_count_panics() {
+        prepass_sample_count(Msaa::Sample8, |_| false);
+    }
+}
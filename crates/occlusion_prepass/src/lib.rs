@@ -1,5 +1,6 @@
 pub mod core;
 pub mod node;
+pub mod pipeline_cache;
 
 use bevy::app::Plugin;
 use bevy::asset::{load_internal_asset, AssetServer, Handle, HandleUntyped};
@@ -14,6 +15,8 @@ use bevy::ecs::{
 };
 use bevy::prelude::Update;
 use bevy::reflect::TypeUuid;
+use bevy::render::extract_component::ExtractComponentPlugin;
+use bevy::render::extract_resource::ExtractResourcePlugin;
 use bevy::render::render_graph::RenderGraph;
 use bevy::render::Render;
 use bevy::render::{
@@ -32,10 +35,10 @@ use bevy::render::{
         MultisampleState, PipelineCache, PolygonMode, PrimitiveState, RenderPipelineDescriptor, Shader, ShaderDefVal,
         ShaderRef, ShaderStages, ShaderType, SpecializedMeshPipeline, SpecializedMeshPipelineError,
         SpecializedMeshPipelines, StencilFaceState, StencilState, TextureDescriptor, TextureDimension, TextureFormat,
-        TextureSampleType, TextureUsages, TextureViewDimension, VertexState,
+        TextureSampleType, TextureUsages, TextureViewDescriptor, TextureViewDimension, VertexState,
     },
-    renderer::RenderDevice,
-    texture::{FallbackImagesDepth, FallbackImagesMsaa, TextureCache},
+    renderer::{RenderAdapter, RenderDevice},
+    texture::{CachedTexture, FallbackImagesDepth, FallbackImagesMsaa, TextureCache},
     view::{ExtractedView, Msaa, ViewUniform, ViewUniformOffset, ViewUniforms, VisibleEntities},
     Extract, ExtractSchedule, RenderApp, RenderSet,
 };
@@ -46,10 +49,15 @@ use bevy::pbr::{
     RenderMaterials, SetMaterialBindGroup, SetMeshBindGroup, MAX_CASCADES_PER_LIGHT, MAX_DIRECTIONAL_LIGHTS,
 };
 use node::OcclusionPrepassNode;
+use pipeline_cache::LastPipelineCache;
 
-use crate::core::{AlphaMask3dPrepass, Opaque3dPrepass};
-use crate::core::{OcclusionDepthPrepass, OcclusionNormalPrepass, NORMAL_PREPASS_FORMAT};
-use crate::core::{OcclusionViewPrepassTextures, DEPTH_PREPASS_FORMAT};
+use crate::core::{
+    prepass_sample_count, static_prepass_key, OccluderQueue, OcclusionViewPrepassTextures, PrepassEnabled,
+    PrepassNormalFormat, StaticOccluder, StaticPrepassCache, StaticPrepassCaches, ViewStaticPrepass,
+    DEPTH_PREPASS_FORMAT,
+};
+use crate::core::{AlphaMask3dPrepass, Opaque3dPrepass, StaticOpaque3dPrepass};
+use crate::core::{OcclusionDepthPrepass, OcclusionNormalPrepass};
 use std::{hash::Hash, marker::PhantomData};
 
 pub const PREPASS_SHADER_HANDLE: HandleUntyped = HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 921124473254008984);
@@ -64,11 +72,25 @@ pub struct OcclusionPrepassPlugin;
 
 impl Plugin for OcclusionPrepassPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
+        app.init_resource::<PrepassEnabled>()
+            .register_type::<PrepassEnabled>()
+            .register_type::<StaticOccluder>()
+            .add_plugins((
+                ExtractResourcePlugin::<PrepassEnabled>::default(),
+                ExtractComponentPlugin::<StaticOccluder>::default(),
+            ));
+        insert_normal_format(app);
+
         let render_app = match app.get_sub_app_mut(RenderApp) {
             Ok(render_app) => render_app,
             Err(_) => return,
         };
 
+        render_app
+            .init_resource::<StaticPrepassCaches>()
+            .add_systems(Render, prepare_static_prepass.in_set(RenderSet::Prepare))
+            .add_systems(Render, finish_static_prepass.in_set(RenderSet::PhaseSort));
+
         let prepass_node = OcclusionPrepassNode::new(&mut render_app.world);
         let mut graph = render_app.world.resource_mut::<RenderGraph>();
         let core_3d_graph = graph.get_sub_graph_mut(core_3d::graph::NAME).unwrap();
@@ -89,6 +111,15 @@ impl Plugin for OcclusionPrepassPlugin {
     }
 }
 
+/// Copies the [`PrepassNormalFormat`] from the main app (inserting the default if missing) into the render app so the
+/// normal texture and the pipeline fragment target always agree on it.
+fn insert_normal_format(app: &mut bevy::app::App) {
+    let normal_format = *app.world.get_resource_or_insert_with(PrepassNormalFormat::default);
+    if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+        render_app.insert_resource(normal_format);
+    }
+}
+
 /// Sets up everything required to use the prepass pipeline.
 ///
 /// This does not add the actual prepasses, see [`PrepassPlugin`] for that.
@@ -121,6 +152,8 @@ where
             Shader::from_wgsl
         );
 
+        insert_normal_format(app);
+
         let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
             return;
         };
@@ -170,10 +203,16 @@ where
                 Render,
                 sort_phase_system::<AlphaMask3dPrepass>.in_set(RenderSet::PhaseSort),
             )
+            .add_systems(
+                Render,
+                sort_phase_system::<StaticOpaque3dPrepass>.in_set(RenderSet::PhaseSort),
+            )
             .init_resource::<DrawFunctions<Opaque3dPrepass>>()
             .init_resource::<DrawFunctions<AlphaMask3dPrepass>>()
+            .init_resource::<DrawFunctions<StaticOpaque3dPrepass>>()
             .add_render_command::<Opaque3dPrepass, DrawPrepass<M>>()
-            .add_render_command::<AlphaMask3dPrepass, DrawPrepass<M>>();
+            .add_render_command::<AlphaMask3dPrepass, DrawPrepass<M>>()
+            .add_render_command::<StaticOpaque3dPrepass, DrawPrepass<M>>();
     }
 }
 
@@ -186,6 +225,7 @@ pub struct PrepassPipeline<M: Material> {
     pub material_vertex_shader: Option<Handle<Shader>>,
     pub material_fragment_shader: Option<Handle<Shader>>,
     pub material_pipeline: MaterialPipeline<M>,
+    pub normal_format: TextureFormat,
     _marker: PhantomData<M>,
 }
 
@@ -229,6 +269,7 @@ impl<M: Material> FromWorld for PrepassPipeline<M> {
             },
             material_layout: M::bind_group_layout(render_device),
             material_pipeline: world.resource::<MaterialPipeline<M>>().clone(),
+            normal_format: world.resource::<PrepassNormalFormat>().0,
             _marker: PhantomData,
         }
     }
@@ -328,21 +369,11 @@ where
                 PREPASS_SHADER_HANDLE.typed::<Shader>()
             };
 
-            let mut targets = vec![];
-            // When the normal prepass is enabled we need a target to be able to write to it.
-            if key.mesh_key.contains(MeshPipelineKey::NORMAL_PREPASS) {
-                targets.push(Some(ColorTargetState {
-                    format: TextureFormat::Rgb10a2Unorm,
-                    blend: Some(BlendState::REPLACE),
-                    write_mask: ColorWrites::ALL,
-                }));
-            }
-
             Some(FragmentState {
                 shader: frag_shader_handle,
                 entry_point: "fragment".into(),
                 shader_defs: shader_defs.clone(),
-                targets,
+                targets: prepass_fragment_targets(key.mesh_key, self.normal_format),
             })
         } else {
             None
@@ -407,6 +438,23 @@ where
     }
 }
 
+/// The color targets of the prepass fragment shader. When the normal prepass is enabled we need a target to be able to
+/// write to it, using the same format as the normal texture.
+pub fn prepass_fragment_targets(
+    mesh_key: MeshPipelineKey,
+    normal_format: TextureFormat,
+) -> Vec<Option<ColorTargetState>> {
+    let mut targets = vec![];
+    if mesh_key.contains(MeshPipelineKey::NORMAL_PREPASS) {
+        targets.push(Some(ColorTargetState {
+            format: normal_format,
+            blend: Some(BlendState::REPLACE),
+            write_mask: ColorWrites::ALL,
+        }));
+    }
+    targets
+}
+
 pub fn get_bind_group_layout_entries(bindings: [u32; 2], multisampled: bool) -> [BindGroupLayoutEntry; 2] {
     [
         // Depth texture
@@ -441,14 +489,17 @@ pub fn get_bindings<'a>(
     msaa: &'a Msaa,
     bindings: [u32; 2],
 ) -> [BindGroupEntry<'a>; 2] {
+    // the sample count was validated against the depth format when the prepass textures were prepared
+    let samples = prepass_sample_count(*msaa, |_| true);
+
     let depth_view = match prepass_textures.and_then(|x| x.depth.as_ref()) {
         Some(texture) => &texture.default_view,
-        None => &fallback_depths.image_for_samplecount(msaa.samples()).texture_view,
+        None => &fallback_depths.image_for_samplecount(samples).texture_view,
     };
 
     let normal_view = match prepass_textures.and_then(|x| x.normal.as_ref()) {
         Some(texture) => &texture.default_view,
-        None => &fallback_images.image_for_samplecount(msaa.samples()).texture_view,
+        None => &fallback_images.image_for_samplecount(samples).texture_view,
     };
 
     [
@@ -466,6 +517,7 @@ pub fn get_bindings<'a>(
 // Extract the render phases for the prepass
 pub fn extract_camera_prepass_phase(
     mut commands: Commands,
+    prepass_enabled: Extract<Res<PrepassEnabled>>,
     cameras_3d: Extract<
         Query<
             (
@@ -479,24 +531,42 @@ pub fn extract_camera_prepass_phase(
     >,
 ) {
     for (entity, camera, depth_prepass, normal_prepass) in cameras_3d.iter() {
-        if !camera.is_active {
+        let wants_prepass =
+            prepass_enabled.0 && camera.is_active && (depth_prepass.is_some() || normal_prepass.is_some());
+        if !wants_prepass {
+            // the camera may have requested the prepass on a previous frame so drop anything that extract and
+            // prepare left on it, otherwise the node keeps rendering into stale targets
+            if let Some(mut entity) = commands.get_entity(entity) {
+                entity.remove::<(
+                    RenderPhase<Opaque3dPrepass>,
+                    RenderPhase<AlphaMask3dPrepass>,
+                    RenderPhase<StaticOpaque3dPrepass>,
+                    OcclusionDepthPrepass,
+                    OcclusionNormalPrepass,
+                    OcclusionViewPrepassTextures,
+                    ViewStaticPrepass,
+                )>();
+            }
             continue;
         }
 
         let mut entity = commands.get_or_spawn(entity);
-        if depth_prepass.is_some() || normal_prepass.is_some() {
-            entity.insert((
-                RenderPhase::<Opaque3dPrepass>::default(),
-                RenderPhase::<AlphaMask3dPrepass>::default(),
-            ));
-        }
+        entity.insert((
+            RenderPhase::<Opaque3dPrepass>::default(),
+            RenderPhase::<AlphaMask3dPrepass>::default(),
+            RenderPhase::<StaticOpaque3dPrepass>::default(),
+        ));
         if depth_prepass.is_some() {
             entity.insert(OcclusionDepthPrepass);
             println!("depth has");
+        } else {
+            entity.remove::<OcclusionDepthPrepass>();
         }
         if normal_prepass.is_some() {
             entity.insert(OcclusionNormalPrepass);
             println!("normal has");
+        } else {
+            entity.remove::<OcclusionNormalPrepass>();
         }
     }
 }
@@ -507,6 +577,8 @@ pub fn prepare_prepass_textures(
     mut texture_cache: ResMut<TextureCache>,
     msaa: Res<Msaa>,
     render_device: Res<RenderDevice>,
+    render_adapter: Res<RenderAdapter>,
+    normal_format: Res<PrepassNormalFormat>,
     views_3d: Query<
         (
             Entity,
@@ -520,6 +592,17 @@ pub fn prepare_prepass_textures(
         ),
     >,
 ) {
+    if views_3d.is_empty() {
+        return;
+    }
+
+    let samples = prepass_sample_count(*msaa, |samples| {
+        render_adapter
+            .get_texture_format_features(DEPTH_PREPASS_FORMAT)
+            .flags
+            .sample_count_supported(samples)
+    });
+
     let mut depth_textures = HashMap::default();
     let mut normal_textures = HashMap::default();
     for (entity, camera, depth_prepass, normal_prepass) in &views_3d {
@@ -542,7 +625,7 @@ pub fn prepare_prepass_textures(
                         label: Some("prepass_depth_texture"),
                         size,
                         mip_level_count: 1,
-                        sample_count: msaa.samples(),
+                        sample_count: samples,
                         dimension: TextureDimension::D2,
                         format: DEPTH_PREPASS_FORMAT,
                         usage: TextureUsages::COPY_DST
@@ -566,9 +649,9 @@ pub fn prepare_prepass_textures(
                             label: Some("prepass_normal_texture"),
                             size,
                             mip_level_count: 1,
-                            sample_count: msaa.samples(),
+                            sample_count: samples,
                             dimension: TextureDimension::D2,
-                            format: NORMAL_PREPASS_FORMAT,
+                            format: normal_format.0,
                             usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
                             view_formats: &[],
                         },
@@ -585,6 +668,97 @@ pub fn prepare_prepass_textures(
     }
 }
 
+/// Hands each view with visible [`StaticOccluder`]s its static depth cache, creating or resizing it as needed, and
+/// flags the caches that are out of date so the static occluders get queued again. Views whose cache key changed since
+/// the last frame get no [`ViewStaticPrepass`] and draw their static occluders with the dynamic ones. Caches of views
+/// that no longer have any visible static occluders are dropped.
+#[allow(clippy::type_complexity)]
+pub fn prepare_static_prepass(
+    mut commands: Commands,
+    mut caches: ResMut<StaticPrepassCaches>,
+    msaa: Res<Msaa>,
+    render_device: Res<RenderDevice>,
+    occluders: Query<(&MeshUniform, &Handle<Mesh>), With<StaticOccluder>>,
+    views: Query<
+        (Entity, &ExtractedView, &ExtractedCamera, &VisibleEntities),
+        (With<OcclusionDepthPrepass>, Without<OcclusionNormalPrepass>),
+    >,
+) {
+    // the sample count was validated against the depth format when the prepass textures were prepared
+    let samples = prepass_sample_count(*msaa, |_| true);
+
+    let mut previous = std::mem::take(&mut caches.0);
+    for (entity, view, camera, visible_entities) in &views {
+        let Some(physical_target_size) = camera.physical_target_size else {
+            continue;
+        };
+
+        let visible_occluders = visible_entities.entities.iter().filter_map(|entity| {
+            let (mesh_uniform, mesh) = occluders.get(*entity).ok()?;
+            Some((*entity, &mesh_uniform.transform, mesh))
+        });
+        let visible_occluders: Vec<_> = visible_occluders.collect();
+        if visible_occluders.is_empty() {
+            continue;
+        }
+
+        let size = Extent3d {
+            depth_or_array_layers: 1,
+            width: physical_target_size.x,
+            height: physical_target_size.y,
+        };
+        let mut cache = previous
+            .remove(&entity)
+            .filter(|cache| cache.texture.texture.size() == size && cache.texture.texture.sample_count() == samples)
+            .unwrap_or_else(|| {
+                let texture = render_device.create_texture(&TextureDescriptor {
+                    label: Some("occlusion_prepass_static_depth_texture"),
+                    size,
+                    mip_level_count: 1,
+                    sample_count: samples,
+                    dimension: TextureDimension::D2,
+                    format: DEPTH_PREPASS_FORMAT,
+                    usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+                    view_formats: &[],
+                });
+                StaticPrepassCache::new(CachedTexture {
+                    default_view: texture.create_view(&TextureViewDescriptor::default()),
+                    texture,
+                })
+            });
+
+        if let Some(dirty) = cache.update(static_prepass_key(view, size, visible_occluders)) {
+            commands.entity(entity).insert(ViewStaticPrepass {
+                texture: cache.texture.clone(),
+                dirty,
+            });
+        }
+        caches.0.insert(entity, cache);
+    }
+}
+
+/// Marks the static depth caches redrawn this frame as up to date, unless some of their static occluders are still
+/// waiting on their pipeline. The `PipelineCache` compiles asynchronously and skips drawing those, so the cache is
+/// kept dirty until a frame queued all of them with a ready pipeline.
+pub fn finish_static_prepass(
+    mut caches: ResMut<StaticPrepassCaches>,
+    pipeline_cache: Res<PipelineCache>,
+    views: Query<(Entity, &ViewStaticPrepass, &RenderPhase<StaticOpaque3dPrepass>)>,
+) {
+    for (entity, static_prepass, static_opaque_phase) in &views {
+        let Some(cache) = caches.0.get_mut(&entity) else {
+            continue;
+        };
+        if static_prepass.dirty {
+            let pipelines_ready = static_opaque_phase
+                .items
+                .iter()
+                .all(|item| pipeline_cache.get_render_pipeline(item.pipeline_id).is_some());
+            cache.finish(pipelines_ready);
+        }
+    }
+}
+
 #[derive(Default, Resource)]
 pub struct OcclusionPrepassViewBindGroup {
     bind_group: Option<BindGroup>,
@@ -594,8 +768,13 @@ pub fn queue_prepass_view_bind_group<M: Material>(
     render_device: Res<RenderDevice>,
     prepass_pipeline: Res<PrepassPipeline<M>>,
     view_uniforms: Res<ViewUniforms>,
+    prepass_enabled: Res<PrepassEnabled>,
     mut prepass_view_bind_group: ResMut<OcclusionPrepassViewBindGroup>,
 ) {
+    if !prepass_enabled.0 {
+        return;
+    }
+
     if let Some(view_binding) = view_uniforms.uniforms.binding() {
         prepass_view_bind_group.bind_group = Some(render_device.create_bind_group(&BindGroupDescriptor {
             entries: &[BindGroupEntry {
@@ -612,27 +791,49 @@ pub fn queue_prepass_view_bind_group<M: Material>(
 pub fn queue_prepass_material_meshes<M: Material>(
     opaque_draw_functions: Res<DrawFunctions<Opaque3dPrepass>>,
     alpha_mask_draw_functions: Res<DrawFunctions<AlphaMask3dPrepass>>,
+    static_opaque_draw_functions: Res<DrawFunctions<StaticOpaque3dPrepass>>,
     prepass_pipeline: Res<PrepassPipeline<M>>,
     mut pipelines: ResMut<SpecializedMeshPipelines<PrepassPipeline<M>>>,
     pipeline_cache: Res<PipelineCache>,
     msaa: Res<Msaa>,
+    prepass_enabled: Res<PrepassEnabled>,
     render_meshes: Res<RenderAssets<Mesh>>,
     render_materials: Res<RenderMaterials<M>>,
     material_meshes: Query<(&Handle<M>, &Handle<Mesh>, &MeshUniform)>,
+    static_occluders: Query<(), With<StaticOccluder>>,
     mut views: Query<(
         &ExtractedView,
         &VisibleEntities,
         &mut RenderPhase<Opaque3dPrepass>,
         &mut RenderPhase<AlphaMask3dPrepass>,
+        &mut RenderPhase<StaticOpaque3dPrepass>,
+        Option<&ViewStaticPrepass>,
         Option<&OcclusionDepthPrepass>,
         Option<&OcclusionNormalPrepass>,
     )>,
 ) where
     M::Data: PartialEq + Eq + Hash + Clone,
 {
+    if !prepass_enabled.0 {
+        return;
+    }
+
     let opaque_draw_prepass = opaque_draw_functions.read().get_id::<DrawPrepass<M>>().unwrap();
     let alpha_mask_draw_prepass = alpha_mask_draw_functions.read().get_id::<DrawPrepass<M>>().unwrap();
-    for (view, visible_entities, mut opaque_phase, mut alpha_mask_phase, depth_prepass, normal_prepass) in &mut views {
+    let static_opaque_draw_prepass = static_opaque_draw_functions.read().get_id::<DrawPrepass<M>>().unwrap();
+    // the view flags are part of the key so a single cache can be shared by all the views
+    let mut last_pipeline = LastPipelineCache::default();
+    for (
+        view,
+        visible_entities,
+        mut opaque_phase,
+        mut alpha_mask_phase,
+        mut static_opaque_phase,
+        static_prepass,
+        depth_prepass,
+        normal_prepass,
+    ) in &mut views
+    {
         let mut view_key = MeshPipelineKey::from_msaa_samples(msaa.samples());
         if depth_prepass.is_some() {
             view_key |= MeshPipelineKey::DEPTH_PREPASS;
@@ -661,15 +862,19 @@ pub fn queue_prepass_material_meshes<M: Material>(
                 AlphaMode::Blend | AlphaMode::Premultiplied | AlphaMode::Add | AlphaMode::Multiply => continue,
             }
 
-            let pipeline_id = pipelines.specialize(
-                &pipeline_cache,
-                &prepass_pipeline,
-                MaterialPipelineKey {
-                    mesh_key,
-                    bind_group_data: material.key.clone(),
-                },
-                &mesh.layout,
-            );
+            let cacheable = alpha_mode == AlphaMode::Opaque && static_occluders.contains(*visible_entity);
+            let queue = OccluderQueue::new(cacheable, static_prepass.map(|static_prepass| static_prepass.dirty));
+            if queue == OccluderQueue::Cached {
+                continue;
+            }
+
+            let key = MaterialPipelineKey {
+                mesh_key,
+                bind_group_data: material.key.clone(),
+            };
+            let pipeline_id = last_pipeline.get_or_specialize(key, &mesh.layout, |key| {
+                pipelines.specialize(&pipeline_cache, &prepass_pipeline, key, &mesh.layout)
+            });
             let pipeline_id = match pipeline_id {
                 Ok(id) => id,
                 Err(err) => {
@@ -680,6 +885,14 @@ pub fn queue_prepass_material_meshes<M: Material>(
 
             let distance = rangefinder.distance(&mesh_uniform.transform) + material.properties.depth_bias;
             match alpha_mode {
+                AlphaMode::Opaque if queue == OccluderQueue::Static => {
+                    static_opaque_phase.add(StaticOpaque3dPrepass {
+                        entity: *visible_entity,
+                        draw_function: static_opaque_draw_prepass,
+                        pipeline_id,
+                        distance,
+                    });
+                }
                 AlphaMode::Opaque => {
                     opaque_phase.add(Opaque3dPrepass {
                         entity: *visible_entity,
@@ -733,3 +946,74 @@ pub type DrawPrepass<M> = (
     SetMeshBindGroup<2>,
     DrawMesh,
 );
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::render::MainWorld;
+
+    fn extract_phases(prepass_enabled: bool) -> bool {
+        let mut main_world = MainWorld::default();
+        main_world.insert_resource(PrepassEnabled(prepass_enabled));
+        let camera = main_world
+            .spawn((Camera::default(), Camera3d::default(), OcclusionDepthPrepass))
+            .id();
+
+        let mut render_world = World::new();
+        render_world.insert_resource(main_world);
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(extract_camera_prepass_phase);
+        schedule.run(&mut render_world);
+
+        render_world
+            .get_entity(camera)
+            .is_some_and(|entity| entity.contains::<RenderPhase<Opaque3dPrepass>>())
+    }
+
+    #[test]
+    fn fragment_target_follows_normal_format() {
+        let key = MeshPipelineKey::NORMAL_PREPASS | MeshPipelineKey::DEPTH_PREPASS;
+        for format in [PrepassNormalFormat::default().0, TextureFormat::Rgba16Float] {
+            let targets = prepass_fragment_targets(key, format);
+            assert_eq!(targets.len(), 1);
+            assert_eq!(targets[0].as_ref().unwrap().format, format);
+        }
+
+        assert!(prepass_fragment_targets(MeshPipelineKey::DEPTH_PREPASS, TextureFormat::Rgba16Float).is_empty());
+    }
+
+    #[test]
+    fn disabled_prepass_adds_no_phases() {
+        assert!(extract_phases(true));
+        assert!(!extract_phases(false));
+    }
+
+    #[test]
+    fn removing_prepass_removes_phases() {
+        let mut main_world = MainWorld::default();
+        main_world.insert_resource(PrepassEnabled(true));
+        let camera = main_world
+            .spawn((Camera::default(), Camera3d::default(), OcclusionDepthPrepass))
+            .id();
+
+        let mut render_world = World::new();
+        render_world.insert_resource(main_world);
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(extract_camera_prepass_phase);
+        schedule.run(&mut render_world);
+        assert!(render_world.entity(camera).contains::<RenderPhase<Opaque3dPrepass>>());
+
+        render_world
+            .resource_mut::<MainWorld>()
+            .entity_mut(camera)
+            .remove::<OcclusionDepthPrepass>();
+        schedule.run(&mut render_world);
+
+        let camera = render_world.entity(camera);
+        assert!(!camera.contains::<RenderPhase<Opaque3dPrepass>>());
+        assert!(!camera.contains::<RenderPhase<StaticOpaque3dPrepass>>());
+        assert!(!camera.contains::<OcclusionDepthPrepass>());
+    }
+}
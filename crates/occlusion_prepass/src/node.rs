@@ -12,7 +12,7 @@ use bevy::render::{
     view::ExtractedView,
 };
 
-use crate::core::OcclusionViewPrepassTextures;
+use crate::core::{OcclusionViewPrepassTextures, StaticOpaque3dPrepass, ViewStaticPrepass};
 
 use super::{AlphaMask3dPrepass, Opaque3dPrepass};
 
@@ -25,7 +25,9 @@ pub struct OcclusionPrepassNode {
             &'static ExtractedCamera,
             &'static RenderPhase<Opaque3dPrepass>,
             &'static RenderPhase<AlphaMask3dPrepass>,
+            &'static RenderPhase<StaticOpaque3dPrepass>,
             &'static OcclusionViewPrepassTextures,
+            Option<&'static ViewStaticPrepass>,
         ),
         With<ExtractedView>,
     >,
@@ -62,7 +64,9 @@ impl Node for OcclusionPrepassNode {
             camera,
             opaque_prepass_phase,
             alpha_mask_prepass_phase,
+            static_opaque_prepass_phase,
             view_prepass_textures,
+            static_prepass,
         )) = self.main_view_query.get_manual(world, view_entity) else {
             println!("------- failed to run, no matching entities");
             return Ok(());
@@ -85,6 +89,39 @@ impl Node for OcclusionPrepassNode {
 
         // TODO: should depth be Option?
         if let Some(view_depth_texture) = &view_prepass_textures.depth {
+            // static occluders are only drawn when their cached depth is out of date, it then replaces the clear
+            if let Some(static_prepass) = static_prepass {
+                if static_prepass.dirty {
+                    let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+                        label: Some("occlusion_prepass_static"),
+                        color_attachments: &[],
+                        depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
+                            view: &static_prepass.texture.default_view,
+                            depth_ops: Some(Operations {
+                                load: LoadOp::Clear(0.0),
+                                store: true,
+                            }),
+                            stencil_ops: None,
+                        }),
+                    });
+
+                    if let Some(viewport) = camera.viewport.as_ref() {
+                        render_pass.set_camera_viewport(viewport);
+                    }
+                    static_opaque_prepass_phase.render(&mut render_pass, world, view_entity);
+                }
+
+                render_context.command_encoder().copy_texture_to_texture(
+                    static_prepass.texture.texture.as_image_copy(),
+                    view_depth_texture.texture.as_image_copy(),
+                    view_prepass_textures.size,
+                );
+            }
+            let depth_load = match static_prepass {
+                Some(_) => LoadOp::Load,
+                None => LoadOp::Clear(0.0),
+            };
+
             // Set up the pass descriptor with the depth attachment and optional color attachments
             let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
                 label: Some("occlusion_prepass"),
@@ -92,7 +129,7 @@ impl Node for OcclusionPrepassNode {
                 depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
                     view: &view_depth_texture.default_view,
                     depth_ops: Some(Operations {
-                        load: LoadOp::Clear(0.0),
+                        load: depth_load,
                         store: true,
                     }),
                     stencil_ops: None,
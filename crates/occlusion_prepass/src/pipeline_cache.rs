@@ -0,0 +1,91 @@
+use bevy::render::{
+    mesh::MeshVertexBufferLayout,
+    render_resource::{CachedRenderPipelineId, SpecializedMeshPipelineError},
+};
+
+/// Remembers the last specialized prepass pipeline while queueing a frame. Occluders usually come in long runs
+/// sharing a material and mesh layout, which then skip the hashing and lookup in `SpecializedMeshPipelines`.
+///
+/// The cached id is only reused when both the key and the vertex layout match since `specialize` depends on both.
+/// Errors are never cached so they keep getting reported for every entity hitting them.
+pub struct LastPipelineCache<K> {
+    last: Option<(K, MeshVertexBufferLayout, CachedRenderPipelineId)>,
+}
+
+impl<K> Default for LastPipelineCache<K> {
+    fn default() -> Self {
+        Self { last: None }
+    }
+}
+
+impl<K: PartialEq + Clone> LastPipelineCache<K> {
+    /// returns the pipeline id of the previous call if `key` and `layout` are the same, otherwise calls `specialize`
+    /// and remembers its result
+    pub fn get_or_specialize(
+        &mut self,
+        key: K,
+        layout: &MeshVertexBufferLayout,
+        specialize: impl FnOnce(K) -> Result<CachedRenderPipelineId, SpecializedMeshPipelineError>,
+    ) -> Result<CachedRenderPipelineId, SpecializedMeshPipelineError> {
+        if let Some((last_key, last_layout, id)) = &self.last {
+            if *last_key == key && last_layout == layout {
+                return Ok(*id);
+            }
+        }
+
+        let id = specialize(key.clone())?;
+        self.last = Some((key, layout.clone(), id));
+        Ok(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::prelude::{shape, Mesh};
+
+    #[test]
+    fn identical_keys_reuse_the_last_pipeline() {
+        let cube = Mesh::from(shape::Cube::default()).get_mesh_vertex_buffer_layout();
+        let mut no_uv_cube = Mesh::from(shape::Cube::default());
+        no_uv_cube.remove_attribute(Mesh::ATTRIBUTE_UV_0);
+        let no_uv_cube = no_uv_cube.get_mesh_vertex_buffer_layout();
+
+        let mut cache = LastPipelineCache::default();
+        let mut specialized = Vec::new();
+        for (key, layout) in [
+            (1, &cube),
+            (1, &cube),
+            (1, &no_uv_cube),
+            (2, &no_uv_cube),
+            (2, &no_uv_cube),
+            (1, &cube),
+        ] {
+            let id = cache.get_or_specialize(key, layout, |key| {
+                specialized.push(key);
+                Ok(CachedRenderPipelineId::INVALID)
+            });
+            assert_eq!(id.ok(), Some(CachedRenderPipelineId::INVALID));
+        }
+        assert_eq!(specialized, [1, 1, 2, 1]);
+    }
+
+    #[test]
+    fn errors_are_not_cached() {
+        let mut no_uv_cube = Mesh::from(shape::Cube::default());
+        no_uv_cube.remove_attribute(Mesh::ATTRIBUTE_UV_0);
+        let no_uv_cube = no_uv_cube.get_mesh_vertex_buffer_layout();
+
+        let mut cache = LastPipelineCache::default();
+        let mut calls = 0;
+        for _ in 0..2 {
+            let id = cache.get_or_specialize((), &no_uv_cube, |_| {
+                calls += 1;
+                no_uv_cube.get_layout(&[Mesh::ATTRIBUTE_UV_0.at_shader_location(0)])?;
+                Ok(CachedRenderPipelineId::INVALID)
+            });
+            assert!(id.is_err());
+        }
+        assert_eq!(calls, 2);
+    }
+}
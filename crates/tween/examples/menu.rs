@@ -82,12 +82,8 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                 )
                 .with_completed_event(INIT_TRANSITION_DONE);
 
-                let animator = if start_time_ms > 0 {
-                    let delay = Delay::new(Duration::from_millis(start_time_ms));
-                    Animator::new(delay.then(tween_scale))
-                } else {
-                    Animator::new(tween_scale)
-                };
+                let delay = Delay::new(Duration::from_millis(start_time_ms));
+                let animator = Animator::new(delay.then(tween_scale));
 
                 start_time_ms += 500;
                 container
@@ -113,7 +113,7 @@ fn setup_tween_sequence(
             continue;
         }
 
-        let delay = i * 5 + 1;
+        let delay = i * 5;
         let seq = Delay::new(Duration::from_millis(delay))
             .then(Tween::new(
                 EaseFunction::QuadraticIn,
@@ -0,0 +1,205 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{EaseMethod, Lens, Tween, TweenCompleted, TweenState, Tweenable, ValueTarget};
+
+/// Lens writing the eased ratio itself, which [`Crossfade`] then splits into
+/// the alpha of both materials.
+struct RatioLens;
+
+impl Lens<f32> for RatioLens {
+    fn lerp(&mut self, target: &mut f32, ratio: f32) {
+        *target = ratio;
+    }
+}
+
+/// Component crossfading two [`StandardMaterial`] assets, e.g. to blend
+/// between two LODs or meshes instead of popping. The alpha of `from` fades
+/// to zero while the alpha of `to` fades in, both driven by a single tween so
+/// they always add up to one.
+///
+/// The alpha of a material only shows with a transparent [`AlphaMode`], so
+/// [`crossfade_system`] switches `Opaque` and `Mask` materials to
+/// [`AlphaMode::Blend`] when it starts fading them, and restores the original
+/// mode of the materials left fully visible once the crossfade completes so
+/// they are drawn as opaque again. Like other asset
+/// animators, `crossfade_system` needs to be added by the application:
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tweening::*;
+/// App::new()
+///     .add_plugins((DefaultPlugins, TweeningPlugin))
+///     .add_systems(Update, crossfade_system.in_set(AnimationSystem::AnimationUpdate));
+/// ```
+#[derive(Component)]
+pub struct Crossfade {
+    /// Material faded out.
+    pub from: Handle<StandardMaterial>,
+    /// Material faded in.
+    pub to: Handle<StandardMaterial>,
+    tween: Tween<f32>,
+    ratio: f32,
+    completed: bool,
+    /// Alpha mode of `from` and `to` before they were switched to blend.
+    alpha_modes: [Option<AlphaMode>; 2],
+}
+
+impl Crossfade {
+    /// Create a crossfade from `from` to `to` over `duration`.
+    #[must_use]
+    pub fn new(
+        from: Handle<StandardMaterial>,
+        to: Handle<StandardMaterial>,
+        ease_function: impl Into<EaseMethod>,
+        duration: Duration,
+    ) -> Self {
+        Self {
+            from,
+            to,
+            tween: Tween::new(ease_function, duration, RatioLens),
+            ratio: 0.,
+            completed: false,
+            alpha_modes: [None; 2],
+        }
+    }
+
+    /// Raise a [`TweenCompleted`] event with `user_data` once the crossfade
+    /// completes. See [`Tween::with_completed_event()`].
+    #[must_use]
+    pub fn with_completed_event(mut self, user_data: u64) -> Self {
+        self.tween.set_completed_event(user_data);
+        self
+    }
+
+    /// Get the current eased ratio, from 0 showing only `from` to 1 showing
+    /// only `to`.
+    pub fn ratio(&self) -> f32 {
+        self.ratio
+    }
+
+    /// Check whether the crossfade completed.
+    pub fn is_completed(&self) -> bool {
+        self.completed
+    }
+}
+
+/// Animator system for [`Crossfade`].
+///
+/// Crossfades wait until both of their materials are loaded so the two alphas
+/// stay in sync, and stop touching the materials once completed.
+pub fn crossfade_system(
+    time: Res<Time>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut query: Query<(Entity, &mut Crossfade)>,
+    events: ResMut<Events<TweenCompleted>>,
+) {
+    let mut events: Mut<Events<TweenCompleted>> = events.into();
+    for (entity, mut crossfade) in query.iter_mut() {
+        if crossfade.completed || !materials.contains(&crossfade.from) || !materials.contains(&crossfade.to) {
+            continue;
+        }
+
+        let crossfade = crossfade.as_mut();
+        let mut ratio = crossfade.ratio;
+        let state = crossfade
+            .tween
+            .tick(time.delta(), &mut ValueTarget::new(&mut ratio), entity, &mut events);
+        crossfade.ratio = ratio;
+        crossfade.completed = state == TweenState::Completed;
+
+        let handles = [&crossfade.from, &crossfade.to];
+        for ((handle, alpha), original_mode) in handles
+            .into_iter()
+            .zip([1. - ratio, ratio])
+            .zip(&mut crossfade.alpha_modes)
+        {
+            let material = materials.get_mut(handle).unwrap();
+            if matches!(material.alpha_mode, AlphaMode::Opaque | AlphaMode::Mask(_)) {
+                *original_mode = Some(material.alpha_mode);
+                material.alpha_mode = AlphaMode::Blend;
+            }
+            material.base_color.set_a(alpha);
+
+            if crossfade.completed && alpha >= 1. {
+                if let Some(mode) = original_mode.take() {
+                    material.alpha_mode = mode;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::asset::AssetPlugin;
+
+    use super::*;
+    use crate::TweeningPlugin;
+
+    #[test]
+    fn halfway_crossfade_splits_alpha() {
+        let mut app = App::new();
+        app.add_plugins((TaskPoolPlugin::default(), AssetPlugin::default(), TweeningPlugin))
+            .init_asset::<StandardMaterial>()
+            .add_systems(Update, crossfade_system)
+            .insert_resource(Time::<()>::default());
+        let mut materials = app.world.resource_mut::<Assets<StandardMaterial>>();
+        let from = materials.add(StandardMaterial::default());
+        let to = materials.add(StandardMaterial::default());
+        let entity = app
+            .world
+            .spawn(Crossfade::new(
+                from.clone(),
+                to.clone(),
+                EaseMethod::Linear,
+                Duration::from_secs(1),
+            ))
+            .id();
+
+        app.world.resource_mut::<Time>().advance_by(Duration::from_millis(500));
+        app.update();
+
+        let materials = app.world.resource::<Assets<StandardMaterial>>();
+        for handle in [&from, &to] {
+            let material = materials.get(handle).unwrap();
+            assert!((material.base_color.a() - 0.5).abs() < 1e-5);
+            assert_eq!(material.alpha_mode, AlphaMode::Blend);
+        }
+        assert!((app.world.get::<Crossfade>(entity).unwrap().ratio() - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn completed_crossfade_restores_alpha_mode() {
+        let mut app = App::new();
+        app.add_plugins((TaskPoolPlugin::default(), AssetPlugin::default(), TweeningPlugin))
+            .init_asset::<StandardMaterial>()
+            .add_systems(Update, crossfade_system)
+            .insert_resource(Time::<()>::default());
+        let mut materials = app.world.resource_mut::<Assets<StandardMaterial>>();
+        let from = materials.add(StandardMaterial::default());
+        let to = materials.add(StandardMaterial {
+            alpha_mode: AlphaMode::Mask(0.5),
+            ..default()
+        });
+        app.world.spawn(Crossfade::new(
+            from.clone(),
+            to.clone(),
+            EaseMethod::Linear,
+            Duration::from_secs(1),
+        ));
+
+        for _ in 0..3 {
+            app.world.resource_mut::<Time>().advance_by(Duration::from_millis(600));
+            app.update();
+        }
+
+        let materials = app.world.resource::<Assets<StandardMaterial>>();
+        let (from, to) = (materials.get(&from).unwrap(), materials.get(&to).unwrap());
+        assert_eq!(from.alpha_mode, AlphaMode::Blend);
+        assert_eq!(from.base_color.a(), 0.);
+        assert_eq!(to.alpha_mode, AlphaMode::Mask(0.5));
+        assert_eq!(to.base_color.a(), 1.);
+    }
+}
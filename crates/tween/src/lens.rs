@@ -89,6 +89,41 @@ impl Lens<Transform> for TransformPositionLens {
     }
 }
 
+/// A lens to move the [`translation`] of a [`Transform`] component by
+/// `offset` from wherever it was when the animation started.
+///
+/// The base translation is captured on the first [`lerp()`] call, and the
+/// lens then writes `base + offset * ratio`. The base is kept when the tween
+/// is rewound or repeats, so the animation replays from the same place instead
+/// of drifting further each time. Create a new lens to start from a new base.
+///
+/// [`translation`]: https://docs.rs/bevy/0.10.0/bevy/transform/components/struct.Transform.html#structfield.translation
+/// [`lerp()`]: Lens::lerp
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TransformRelativePositionLens {
+    /// Translation added to the base at the end of the animation.
+    pub offset: Vec3,
+    base: Option<Vec3>,
+}
+
+impl TransformRelativePositionLens {
+    pub fn new(offset: Vec3) -> Self {
+        Self { offset, base: None }
+    }
+
+    /// The captured base translation, if the lens was already applied.
+    pub fn base(&self) -> Option<Vec3> {
+        self.base
+    }
+}
+
+impl Lens<Transform> for TransformRelativePositionLens {
+    fn lerp(&mut self, target: &mut Transform, ratio: f32) {
+        let base = *self.base.get_or_insert(target.translation);
+        target.translation = base + self.offset * ratio;
+    }
+}
+
 /// A lens to manipulate the [`rotation`] field of a [`Transform`] component.
 ///
 /// This lens interpolates the [`rotation`] field of a [`Transform`] component
@@ -112,6 +147,29 @@ impl Lens<Transform> for TransformRotationLens {
     }
 }
 
+/// A lens to move and turn a [`Transform`] component with a single tween.
+///
+/// The [`translation`] is interpolated linearly and the [`rotation`] with
+/// [`Quat::slerp()`] like [`TransformRotationLens`], so it takes the shortest
+/// path as well.
+///
+/// [`translation`]: https://docs.rs/bevy/0.10.0/bevy/transform/components/struct.Transform.html#structfield.translation
+/// [`rotation`]: https://docs.rs/bevy/0.10.0/bevy/transform/components/struct.Transform.html#structfield.rotation
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct TransformPositionRotationLens {
+    /// Start value of the translation and rotation.
+    pub start: (Vec3, Quat),
+    /// End value of the translation and rotation.
+    pub end: (Vec3, Quat),
+}
+
+impl Lens<Transform> for TransformPositionRotationLens {
+    fn lerp(&mut self, target: &mut Transform, ratio: f32) {
+        target.translation = self.start.0.lerp(self.end.0, ratio);
+        target.rotation = self.start.1.slerp(self.end.1, ratio);
+    }
+}
+
 /// A lens to rotate a [`Transform`] component around its local X axis.
 ///
 /// This lens interpolates the rotation angle of a [`Transform`] component from
@@ -241,3 +299,700 @@ impl Lens<Transform> for TransformScaleLens {
         target.scale = value;
     }
 }
+
+/// A lens to manipulate the [`color`] field of a [`Sprite`] component. Each
+/// RGBA component is interpolated linearly in sRGB space, the other sprite
+/// fields are left untouched.
+///
+/// [`color`]: https://docs.rs/bevy/0.10.0/bevy/sprite/struct.Sprite.html#structfield.color
+/// [`Sprite`]: https://docs.rs/bevy/0.10.0/bevy/sprite/struct.Sprite.html
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SpriteColorLens {
+    /// Start color.
+    pub start: Color,
+    /// End color.
+    pub end: Color,
+}
+
+impl Lens<Sprite> for SpriteColorLens {
+    fn lerp(&mut self, target: &mut Sprite, ratio: f32) {
+        let (start, end) = (Vec4::from(self.start), Vec4::from(self.end));
+        let value = start + (end - start) * ratio;
+        target.color = value.into();
+    }
+}
+
+/// A lens to manipulate the [`custom_size`] field of a [`Sprite`] component. If
+/// the sprite has no custom size it will grow/shrink from `start`.
+///
+/// [`custom_size`]: https://docs.rs/bevy/0.10.0/bevy/sprite/struct.Sprite.html#structfield.custom_size
+/// [`Sprite`]: https://docs.rs/bevy/0.10.0/bevy/sprite/struct.Sprite.html
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SpriteCustomSizeLens {
+    /// Start value of the size.
+    pub start: Vec2,
+    /// End value of the size.
+    pub end: Vec2,
+}
+
+impl Lens<Sprite> for SpriteCustomSizeLens {
+    fn lerp(&mut self, target: &mut Sprite, ratio: f32) {
+        target.custom_size = Some(self.start.lerp(self.end, ratio));
+    }
+}
+
+/// A lens to manipulate the [`color`] field of a [`ColorMaterial`] asset. Each
+/// RGBA component is interpolated linearly in sRGB space and clamped to
+/// `[0, 1]`, so easings overshooting the ends like `BackOut` can't produce
+/// invalid colors. Add `asset_animator_system::<ColorMaterial>` to drive it
+/// from an [`AssetAnimator`].
+///
+/// [`color`]: https://docs.rs/bevy/0.10.0/bevy/sprite/struct.ColorMaterial.html#structfield.color
+/// [`ColorMaterial`]: https://docs.rs/bevy/0.10.0/bevy/sprite/struct.ColorMaterial.html
+/// [`AssetAnimator`]: crate::AssetAnimator
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ColorMaterialColorLens {
+    /// Start color.
+    pub start: Color,
+    /// End color.
+    pub end: Color,
+}
+
+impl Lens<ColorMaterial> for ColorMaterialColorLens {
+    fn lerp(&mut self, target: &mut ColorMaterial, ratio: f32) {
+        let value = Vec4::from(self.start).lerp(Vec4::from(self.end), ratio);
+        target.color = value.clamp(Vec4::ZERO, Vec4::ONE).into();
+    }
+}
+
+/// A lens to manipulate the [`base_color`] field of a [`StandardMaterial`]
+/// asset, e.g. to flash or fade 3D meshes. Each RGBA component is interpolated
+/// linearly in sRGB space. Add `asset_animator_system::<StandardMaterial>` to
+/// drive it from an [`AssetAnimator`].
+///
+/// [`base_color`]: https://docs.rs/bevy/0.10.0/bevy/pbr/struct.StandardMaterial.html#structfield.base_color
+/// [`StandardMaterial`]: https://docs.rs/bevy/0.10.0/bevy/pbr/struct.StandardMaterial.html
+/// [`AssetAnimator`]: crate::AssetAnimator
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct StandardMaterialBaseColorLens {
+    /// Start color.
+    pub start: Color,
+    /// End color.
+    pub end: Color,
+}
+
+impl Lens<StandardMaterial> for StandardMaterialBaseColorLens {
+    fn lerp(&mut self, target: &mut StandardMaterial, ratio: f32) {
+        let (start, end) = (Vec4::from(self.start), Vec4::from(self.end));
+        let value = start + (end - start) * ratio;
+        target.base_color = value.into();
+    }
+}
+
+/// A lens to manipulate the [`intensity`] field of a [`PointLight`] component,
+/// e.g. to pulse map lights. Only the intensity is written so it can be
+/// combined with a [`PointLightColorLens`] in a `Tracks`. [`TweeningPlugin`]
+/// doesn't animate `PointLight`, add `component_animator_system::<PointLight>`
+/// to use it.
+///
+/// [`intensity`]: https://docs.rs/bevy/0.10.0/bevy/pbr/struct.PointLight.html#structfield.intensity
+/// [`PointLight`]: https://docs.rs/bevy/0.10.0/bevy/pbr/struct.PointLight.html
+/// [`TweeningPlugin`]: crate::TweeningPlugin
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PointLightIntensityLens {
+    /// Start intensity.
+    pub start: f32,
+    /// End intensity.
+    pub end: f32,
+}
+
+impl Lens<PointLight> for PointLightIntensityLens {
+    fn lerp(&mut self, target: &mut PointLight, ratio: f32) {
+        target.intensity = (self.end - self.start).mul_add(ratio, self.start);
+    }
+}
+
+/// A lens to manipulate the [`color`] field of a [`PointLight`] component. Each
+/// RGBA component is interpolated linearly in sRGB space and only the color is
+/// written, see [`PointLightIntensityLens`].
+///
+/// [`color`]: https://docs.rs/bevy/0.10.0/bevy/pbr/struct.PointLight.html#structfield.color
+/// [`PointLight`]: https://docs.rs/bevy/0.10.0/bevy/pbr/struct.PointLight.html
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PointLightColorLens {
+    /// Start color.
+    pub start: Color,
+    /// End color.
+    pub end: Color,
+}
+
+impl Lens<PointLight> for PointLightColorLens {
+    fn lerp(&mut self, target: &mut PointLight, ratio: f32) {
+        let (start, end) = (Vec4::from(self.start), Vec4::from(self.end));
+        let value = start + (end - start) * ratio;
+        target.color = value.into();
+    }
+}
+
+/// A lens to manipulate the [`scale`] field of an orthographic [`Projection`]
+/// for smooth 2D zooms. Perspective projections are left untouched.
+/// [`TweeningPlugin`] doesn't animate `Projection`, add
+/// `component_animator_system::<Projection>` to use it.
+///
+/// [`scale`]: https://docs.rs/bevy/0.10.0/bevy/render/camera/struct.OrthographicProjection.html#structfield.scale
+/// [`Projection`]: https://docs.rs/bevy/0.10.0/bevy/render/camera/enum.Projection.html
+/// [`TweeningPlugin`]: crate::TweeningPlugin
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct OrthographicScaleLens {
+    /// Start value of the scale.
+    pub start: f32,
+    /// End value of the scale.
+    pub end: f32,
+}
+
+impl Lens<Projection> for OrthographicScaleLens {
+    fn lerp(&mut self, target: &mut Projection, ratio: f32) {
+        if let Projection::Orthographic(ortho) = target {
+            ortho.scale = self.start + (self.end - self.start) * ratio;
+        }
+    }
+}
+
+/// A lens to manipulate the [`fov`] field of a [`PerspectiveProjection`], in
+/// radians. Use [`ProjectionFovLens`] for cameras using the [`Projection`]
+/// enum.
+///
+/// [`fov`]: https://docs.rs/bevy/0.10.0/bevy/render/camera/struct.PerspectiveProjection.html#structfield.fov
+/// [`PerspectiveProjection`]: https://docs.rs/bevy/0.10.0/bevy/render/camera/struct.PerspectiveProjection.html
+/// [`Projection`]: https://docs.rs/bevy/0.10.0/bevy/render/camera/enum.Projection.html
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PerspectiveFovLens {
+    /// Start field of view, in radians.
+    pub start: f32,
+    /// End field of view, in radians.
+    pub end: f32,
+}
+
+impl Lens<PerspectiveProjection> for PerspectiveFovLens {
+    fn lerp(&mut self, target: &mut PerspectiveProjection, ratio: f32) {
+        target.fov = self.start + (self.end - self.start) * ratio;
+    }
+}
+
+/// A lens to manipulate the [`fov`] field of a perspective [`Projection`], in
+/// radians. Orthographic projections are left untouched. [`TweeningPlugin`]
+/// doesn't animate `Projection`, add `component_animator_system::<Projection>`
+/// to use it.
+///
+/// [`fov`]: https://docs.rs/bevy/0.10.0/bevy/render/camera/struct.PerspectiveProjection.html#structfield.fov
+/// [`Projection`]: https://docs.rs/bevy/0.10.0/bevy/render/camera/enum.Projection.html
+/// [`TweeningPlugin`]: crate::TweeningPlugin
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ProjectionFovLens {
+    /// Start field of view, in radians.
+    pub start: f32,
+    /// End field of view, in radians.
+    pub end: f32,
+}
+
+impl Lens<Projection> for ProjectionFovLens {
+    fn lerp(&mut self, target: &mut Projection, ratio: f32) {
+        if let Projection::Perspective(perspective) = target {
+            PerspectiveFovLens {
+                start: self.start,
+                end: self.end,
+            }
+            .lerp(perspective, ratio);
+        }
+    }
+}
+
+/// A lens to manipulate the [`margin`] field of a UI [`Style`] component. Each
+/// side is interpolated separately, see [`StylePaddingLens`] for how `Val`
+/// units are handled.
+///
+/// [`margin`]: https://docs.rs/bevy/0.10.0/bevy/ui/struct.Style.html#structfield.margin
+/// [`Style`]: https://docs.rs/bevy/0.10.0/bevy/ui/struct.Style.html
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct StyleMarginLens {
+    /// Start value of the margin.
+    pub start: UiRect,
+    /// End value of the margin.
+    pub end: UiRect,
+}
+
+impl Lens<Style> for StyleMarginLens {
+    fn lerp(&mut self, target: &mut Style, ratio: f32) {
+        lerp_ui_rect(&mut target.margin, &self.start, &self.end, ratio);
+    }
+}
+
+/// A lens to manipulate the [`padding`] field of a UI [`Style`] component.
+///
+/// Each side is interpolated separately when `start` and `end` use the same
+/// `Val` unit. Sides where either value is `Val::Auto` are left untouched. When
+/// the units differ (e.g. `Px` to `Percent`) the side keeps the start value and
+/// a warning is logged once.
+///
+/// [`padding`]: https://docs.rs/bevy/0.10.0/bevy/ui/struct.Style.html#structfield.padding
+/// [`Style`]: https://docs.rs/bevy/0.10.0/bevy/ui/struct.Style.html
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct StylePaddingLens {
+    /// Start value of the padding.
+    pub start: UiRect,
+    /// End value of the padding.
+    pub end: UiRect,
+}
+
+impl Lens<Style> for StylePaddingLens {
+    fn lerp(&mut self, target: &mut Style, ratio: f32) {
+        lerp_ui_rect(&mut target.padding, &self.start, &self.end, ratio);
+    }
+}
+
+/// A lens interpolating through any number of color stops, e.g. a green →
+/// yellow → red health bar. `setter` writes the sampled color into the target
+/// so the lens works with anything that has a color.
+///
+/// Stops are `(ratio, color)` pairs and are sorted by ratio on creation. Ratios
+/// before the first or after the last stop clamp to that stop's color. Colors
+/// are interpolated per component in sRGB space.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_tweening::lens::ColorGradientLens;
+/// let lens = ColorGradientLens::new(
+///     vec![(0.0, Color::GREEN), (0.5, Color::YELLOW), (1.0, Color::RED)],
+///     |sprite: &mut Sprite, color| sprite.color = color,
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct ColorGradientLens<T> {
+    /// `(ratio, color)` stops sorted by ratio.
+    pub stops: Vec<(f32, Color)>,
+    /// Writes the sampled color into the target.
+    pub setter: fn(&mut T, Color),
+}
+
+impl<T> ColorGradientLens<T> {
+    /// Create a lens from unsorted `stops`.
+    pub fn new(mut stops: Vec<(f32, Color)>, setter: fn(&mut T, Color)) -> Self {
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { stops, setter }
+    }
+
+    /// Color of the gradient at `ratio`, or `None` when there are no stops.
+    pub fn sample(&self, ratio: f32) -> Option<Color> {
+        let (first, last) = (self.stops.first()?, self.stops.last()?);
+        if ratio <= first.0 {
+            return Some(first.1);
+        }
+        if ratio >= last.0 {
+            return Some(last.1);
+        }
+
+        // index of the first stop past ratio, never 0 or len here
+        let next = self.stops.partition_point(|(stop, _)| *stop <= ratio);
+        let ((start_ratio, start), (end_ratio, end)) = (self.stops[next - 1], self.stops[next]);
+        let t = (ratio - start_ratio) / (end_ratio - start_ratio);
+        Some(Vec4::from(start).lerp(Vec4::from(end), t).into())
+    }
+}
+
+impl<T> Lens<T> for ColorGradientLens<T> {
+    fn lerp(&mut self, target: &mut T, ratio: f32) {
+        if let Some(color) = self.sample(ratio) {
+            (self.setter)(target, color);
+        }
+    }
+}
+
+/// A lens applying two other lenses to the same target, e.g. to animate both
+/// the translation and the scale of a [`Transform`] with a single tween
+/// instead of a [`Tracks`] of two tweens. Both lenses see the same `ratio`,
+/// `first` is applied before `second`.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_tweening::lens::*;
+/// let lens = CompositeLens::new(
+///     TransformPositionLens {
+///         start: Vec3::ZERO,
+///         end: Vec3::X,
+///     },
+///     TransformScaleLens {
+///         start: Vec3::ONE,
+///         end: Vec3::splat(2.),
+///     },
+/// );
+/// ```
+///
+/// [`Tracks`]: crate::Tracks
+pub struct CompositeLens<T> {
+    first: Box<dyn Lens<T> + Send + Sync + 'static>,
+    second: Box<dyn Lens<T> + Send + Sync + 'static>,
+}
+
+impl<T> CompositeLens<T> {
+    /// Create a lens applying `first` then `second`.
+    pub fn new<A, B>(first: A, second: B) -> Self
+    where
+        A: Lens<T> + Send + Sync + 'static,
+        B: Lens<T> + Send + Sync + 'static,
+    {
+        Self {
+            first: Box::new(first),
+            second: Box::new(second),
+        }
+    }
+}
+
+impl<T> Lens<T> for CompositeLens<T> {
+    fn lerp(&mut self, target: &mut T, ratio: f32) {
+        self.first.lerp(target, ratio);
+        self.second.lerp(target, ratio);
+    }
+}
+
+fn lerp_ui_rect(target: &mut UiRect, start: &UiRect, end: &UiRect, ratio: f32) {
+    let sides = [
+        (&mut target.left, start.left, end.left),
+        (&mut target.right, start.right, end.right),
+        (&mut target.top, start.top, end.top),
+        (&mut target.bottom, start.bottom, end.bottom),
+    ];
+    for (side, start, end) in sides {
+        if let Some(value) = lerp_val(start, end, ratio) {
+            *side = value;
+        }
+    }
+}
+
+/// Interpolates two `Val`s of the same unit. Returns `None` if either is
+/// `Val::Auto`.
+fn lerp_val(start: Val, end: Val, ratio: f32) -> Option<Val> {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    static WARNED_MIXED_UNITS: AtomicBool = AtomicBool::new(false);
+
+    let lerp = |a: f32, b: f32| a + (b - a) * ratio;
+    match (start, end) {
+        (Val::Auto, _) | (_, Val::Auto) => None,
+        (Val::Px(a), Val::Px(b)) => Some(Val::Px(lerp(a, b))),
+        (Val::Percent(a), Val::Percent(b)) => Some(Val::Percent(lerp(a, b))),
+        (Val::Vw(a), Val::Vw(b)) => Some(Val::Vw(lerp(a, b))),
+        (Val::Vh(a), Val::Vh(b)) => Some(Val::Vh(lerp(a, b))),
+        (Val::VMin(a), Val::VMin(b)) => Some(Val::VMin(lerp(a, b))),
+        (Val::VMax(a), Val::VMax(b)) => Some(Val::VMax(lerp(a, b))),
+        _ => {
+            if !WARNED_MIXED_UNITS.swap(true, Ordering::Relaxed) {
+                warn!("can't tween between Val units {start:?} and {end:?}, keeping the start value");
+            }
+            Some(start)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        f32::consts::{FRAC_PI_2, FRAC_PI_4},
+        time::Duration,
+    };
+
+    use super::*;
+    use crate::{AssetTarget, EaseMethod, Tracks, Tween, TweenCompleted, Tweenable, ValueTarget};
+
+    #[test]
+    fn transform_relative_position() {
+        let mut lens = TransformRelativePositionLens::new(Vec3::X);
+        let mut transform = Transform::from_xyz(5.0, 1.0, 0.0);
+
+        lens.lerp(&mut transform, 0.0);
+        assert_eq!(lens.base(), Some(Vec3::new(5.0, 1.0, 0.0)));
+        lens.lerp(&mut transform, 0.5);
+        assert_eq!(transform.translation, Vec3::new(5.5, 1.0, 0.0));
+
+        // replaying keeps the base instead of stacking the offset
+        lens.lerp(&mut transform, 1.0);
+        lens.lerp(&mut transform, 0.0);
+        assert_eq!(transform.translation, Vec3::new(5.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn composite_lens_applies_both() {
+        let mut lens = CompositeLens::new(
+            TransformPositionLens {
+                start: Vec3::ZERO,
+                end: Vec3::new(2.0, 4.0, 0.0),
+            },
+            TransformScaleLens {
+                start: Vec3::ONE,
+                end: Vec3::splat(3.0),
+            },
+        );
+        let mut transform = Transform::default();
+
+        lens.lerp(&mut transform, 0.5);
+        assert_eq!(transform.translation, Vec3::new(1.0, 2.0, 0.0));
+        assert_eq!(transform.scale, Vec3::splat(2.0));
+        assert_eq!(transform.rotation, Quat::IDENTITY);
+    }
+
+    #[test]
+    fn sprite_color_tween_midpoint() {
+        let mut world = World::new();
+        world.init_resource::<Events<TweenCompleted>>();
+        let entity = world.spawn_empty().id();
+        let mut events = world.resource_mut::<Events<TweenCompleted>>();
+
+        let mut tween = Tween::new(
+            EaseMethod::Linear,
+            Duration::from_secs(1),
+            SpriteColorLens {
+                start: Color::rgba(1.0, 0.0, 0.0, 1.0),
+                end: Color::rgba(0.0, 0.0, 1.0, 0.0),
+            },
+        );
+        let mut sprite = Sprite {
+            flip_x: true,
+            ..default()
+        };
+        tween.tick(
+            Duration::from_millis(500),
+            &mut ValueTarget::new(&mut sprite),
+            entity,
+            &mut events,
+        );
+
+        assert!(Vec4::from(sprite.color).abs_diff_eq(Vec4::new(0.5, 0.0, 0.5, 0.5), 1e-5));
+        assert!(sprite.flip_x);
+    }
+
+    #[test]
+    fn standard_material_base_color_through_asset_target() {
+        let mut world = World::new();
+        world.init_resource::<Events<TweenCompleted>>();
+        world.init_resource::<Assets<StandardMaterial>>();
+        let entity = world.spawn_empty().id();
+        let handle = world.resource_mut::<Assets<StandardMaterial>>().add(StandardMaterial {
+            perceptual_roughness: 0.25,
+            ..default()
+        });
+
+        let mut tween = Tween::new(
+            EaseMethod::Linear,
+            Duration::from_secs(1),
+            StandardMaterialBaseColorLens {
+                start: Color::rgba(0.0, 1.0, 0.0, 1.0),
+                end: Color::rgba(1.0, 0.0, 0.5, 0.0),
+            },
+        );
+
+        for (millis, expected) in [
+            (250, Vec4::new(0.25, 0.75, 0.125, 0.75)),
+            (250, Vec4::new(0.5, 0.5, 0.25, 0.5)),
+            (500, Vec4::new(1.0, 0.0, 0.5, 0.0)),
+        ] {
+            world.resource_scope(|world, assets: Mut<Assets<StandardMaterial>>| {
+                let mut target = AssetTarget::new(assets);
+                target.handle = handle.clone();
+                let mut events = world.resource_mut::<Events<TweenCompleted>>();
+                tween.tick(Duration::from_millis(millis), &mut target, entity, &mut events);
+            });
+
+            let material = world.resource::<Assets<StandardMaterial>>().get(&handle).unwrap();
+            assert!(Vec4::from(material.base_color).abs_diff_eq(expected, 1e-5));
+            assert_eq!(material.perceptual_roughness, 0.25);
+        }
+    }
+
+    #[test]
+    fn point_light_tracks_animate_independently() {
+        let mut world = World::new();
+        world.init_resource::<Events<TweenCompleted>>();
+        let entity = world.spawn_empty().id();
+        let mut events = world.resource_mut::<Events<TweenCompleted>>();
+
+        let mut tracks = Tracks::new([
+            Tween::new(
+                EaseMethod::Linear,
+                Duration::from_secs(1),
+                PointLightIntensityLens {
+                    start: 100.0,
+                    end: 500.0,
+                },
+            ),
+            Tween::new(
+                EaseMethod::Linear,
+                Duration::from_secs(2),
+                PointLightColorLens {
+                    start: Color::rgb(1.0, 0.0, 0.0),
+                    end: Color::rgb(0.0, 0.0, 1.0),
+                },
+            ),
+        ]);
+        let mut light = PointLight {
+            range: 5.0,
+            ..default()
+        };
+        tracks.tick(
+            Duration::from_millis(500),
+            &mut ValueTarget::new(&mut light),
+            entity,
+            &mut events,
+        );
+
+        assert_eq!(light.intensity, 300.0);
+        assert!(Vec4::from(light.color).abs_diff_eq(Vec4::new(0.75, 0.0, 0.25, 1.0), 1e-5));
+        assert_eq!(light.range, 5.0);
+    }
+
+    #[test]
+    fn color_material_color_clamped() {
+        let mut lens = ColorMaterialColorLens {
+            start: Color::rgba(0.0, 0.2, 1.0, 0.0),
+            end: Color::rgba(1.0, 0.6, 0.0, 1.0),
+        };
+
+        let mut material = ColorMaterial::default();
+        lens.lerp(&mut material, 0.5);
+        assert!(Vec4::from(material.color).abs_diff_eq(Vec4::new(0.5, 0.4, 0.5, 0.5), 1e-5));
+
+        // an overshooting easing past the end
+        lens.lerp(&mut material, 1.5);
+        assert!(Vec4::from(material.color).abs_diff_eq(Vec4::new(1.0, 0.8, 0.0, 1.0), 1e-5));
+    }
+
+    #[test]
+    fn transform_position_rotation_halfway() {
+        let mut lens = TransformPositionRotationLens {
+            start: (Vec3::ZERO, Quat::IDENTITY),
+            end: (Vec3::new(2.0, 4.0, 0.0), Quat::from_rotation_y(FRAC_PI_2)),
+        };
+
+        let mut transform = Transform::default();
+        lens.lerp(&mut transform, 0.5);
+        assert!(transform.translation.abs_diff_eq(Vec3::new(1.0, 2.0, 0.0), 1e-5));
+        let halfway = Quat::from_rotation_y(FRAC_PI_2 * 0.5);
+        assert!(transform.rotation.abs_diff_eq(halfway, 1e-5));
+    }
+
+    #[test]
+    fn sprite_custom_size() {
+        let mut lens = SpriteCustomSizeLens {
+            start: Vec2::new(10.0, 20.0),
+            end: Vec2::new(30.0, 60.0),
+        };
+
+        let mut sprite = Sprite::default();
+        assert!(sprite.custom_size.is_none());
+
+        lens.lerp(&mut sprite, 0.0);
+        assert_eq!(sprite.custom_size, Some(Vec2::new(10.0, 20.0)));
+
+        lens.lerp(&mut sprite, 0.5);
+        assert_eq!(sprite.custom_size, Some(Vec2::new(20.0, 40.0)));
+
+        lens.lerp(&mut sprite, 1.0);
+        assert_eq!(sprite.custom_size, Some(Vec2::new(30.0, 60.0)));
+    }
+
+    #[test]
+    fn orthographic_scale() {
+        let mut lens = OrthographicScaleLens { start: 1.0, end: 3.0 };
+
+        let mut projection = Projection::Orthographic(OrthographicProjection::default());
+        lens.lerp(&mut projection, 0.5);
+        let Projection::Orthographic(ortho) = &projection else {
+            unreachable!()
+        };
+        assert_eq!(ortho.scale, 2.0);
+
+        let perspective = PerspectiveProjection::default();
+        let mut projection = Projection::Perspective(perspective.clone());
+        lens.lerp(&mut projection, 0.5);
+        let Projection::Perspective(unchanged) = &projection else {
+            panic!("lens changed the projection kind");
+        };
+        assert_eq!(unchanged.fov, perspective.fov);
+    }
+
+    #[test]
+    fn projection_fov() {
+        let mut lens = ProjectionFovLens {
+            start: FRAC_PI_4,
+            end: FRAC_PI_2,
+        };
+
+        let mut projection = Projection::Perspective(PerspectiveProjection::default());
+        lens.lerp(&mut projection, 0.5);
+        let Projection::Perspective(perspective) = &projection else {
+            unreachable!()
+        };
+        assert!((perspective.fov - (FRAC_PI_4 + FRAC_PI_2) / 2.0).abs() < 1e-6);
+
+        let orthographic = OrthographicProjection::default();
+        let mut projection = Projection::Orthographic(orthographic.clone());
+        lens.lerp(&mut projection, 0.5);
+        let Projection::Orthographic(unchanged) = &projection else {
+            panic!("lens changed the projection kind");
+        };
+        assert_eq!(unchanged.scale, orthographic.scale);
+        assert_eq!(unchanged.area, orthographic.area);
+    }
+
+    #[test]
+    fn style_padding_px() {
+        let mut lens = StylePaddingLens {
+            start: UiRect::all(Val::Px(0.0)),
+            end: UiRect::new(Val::Px(10.0), Val::Px(20.0), Val::Px(40.0), Val::Px(80.0)),
+        };
+
+        let mut style = Style::default();
+        lens.lerp(&mut style, 0.5);
+        assert_eq!(
+            style.padding,
+            UiRect::new(Val::Px(5.0), Val::Px(10.0), Val::Px(20.0), Val::Px(40.0))
+        );
+
+        // auto sides are left alone and mixed units keep the start value
+        let mut lens = StyleMarginLens {
+            start: UiRect::new(Val::Auto, Val::Px(10.0), Val::Px(0.0), Val::Px(0.0)),
+            end: UiRect::new(Val::Px(50.0), Val::Percent(50.0), Val::Px(10.0), Val::Px(10.0)),
+        };
+        style.margin.left = Val::Px(3.0);
+        lens.lerp(&mut style, 0.5);
+        assert_eq!(
+            style.margin,
+            UiRect::new(Val::Px(3.0), Val::Px(10.0), Val::Px(5.0), Val::Px(5.0))
+        );
+    }
+
+    #[test]
+    fn color_gradient_stops() {
+        // stops out of order on purpose
+        let mut lens = ColorGradientLens::new(
+            vec![(1.0, Color::BLUE), (0.0, Color::RED), (0.5, Color::GREEN)],
+            |sprite: &mut Sprite, color| sprite.color = color,
+        );
+        let sample = |lens: &ColorGradientLens<Sprite>, ratio| Vec4::from(lens.sample(ratio).unwrap());
+
+        assert_eq!(sample(&lens, 0.0), Vec4::new(1.0, 0.0, 0.0, 1.0));
+        assert_eq!(sample(&lens, 0.25), Vec4::new(0.5, 0.5, 0.0, 1.0));
+        assert_eq!(sample(&lens, 0.5), Vec4::new(0.0, 1.0, 0.0, 1.0));
+        assert_eq!(sample(&lens, 0.75), Vec4::new(0.0, 0.5, 0.5, 1.0));
+        assert_eq!(sample(&lens, 1.0), Vec4::new(0.0, 0.0, 1.0, 1.0));
+
+        // clamped outside the stops
+        assert_eq!(sample(&lens, -1.0), Vec4::new(1.0, 0.0, 0.0, 1.0));
+        assert_eq!(sample(&lens, 2.0), Vec4::new(0.0, 0.0, 1.0, 1.0));
+
+        let mut sprite = Sprite::default();
+        lens.lerp(&mut sprite, 0.5);
+        assert_eq!(sprite.color, Color::GREEN);
+
+        lens.stops.clear();
+        lens.lerp(&mut sprite, 0.0);
+        assert_eq!(sprite.color, Color::GREEN);
+    }
+}
@@ -63,6 +63,8 @@
 //!   other.
 //! - [`Tracks`] - A collection of tweenables executing in parallel.
 //! - [`Delay`] - A time delay. This doesn't animate anything.
+//! - [`Spring`] - A spring pulling a lens from its start to its end, without a
+//!   fixed duration.
 //!
 //! ## Chaining animations
 //!
@@ -113,6 +115,10 @@
 //! components it animates. This limits the proliferation of generic types when
 //! animating e.g. both the position and rotation of an entity.
 //!
+//! Each animated component type needs its animator system to run, which
+//! [`TweeningPlugin`] only adds for [`Transform`]. Other types are registered
+//! with `app.add_tweening_component::<T>()` from [`TweeningAppExt`].
+//!
 //! ## Assets animation
 //!
 //! Assets are animated in a similar way to component, via the [`AssetAnimator`]
@@ -132,7 +138,7 @@
 //! commonly animated fields, like the components of a [`Transform`]. A custom
 //! lens can also be created by implementing the trait, allowing to animate
 //! virtually any field of any Bevy component or asset.
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
 use bevy::prelude::*;
 
@@ -142,12 +148,18 @@ pub use interpolation::{EaseFunction, Lerp};
 
 pub mod unit_sphere;
 
+pub use crossfade::{crossfade_system, Crossfade};
 pub use lens::Lens;
-pub use plugin::{component_animator_system, AnimationSystem, TweeningPlugin};
+pub use plugin::{
+    additive_animator_system, asset_animator_system, component_animator_system, tweening_unpaused, AnimationSystem,
+    TweeningAppExt, TweeningPlugin, TweeningSettings,
+};
 pub use tweenable::{
-    BoxedTweenable, Delay, Sequence, Targetable, TotalDuration, Tracks, Tween, TweenCompleted, TweenState, Tweenable,
+    AssetTarget, BoxedTweenable, Delay, Sequence, Spring, Targetable, TotalDuration, Tracks, Tween, TweenCompleted,
+    TweenState, Tweenable, ValueTarget,
 };
 
+mod crossfade;
 pub mod lens;
 mod plugin;
 mod tweenable;
@@ -231,8 +243,35 @@ impl std::ops::Not for AnimatorState {
     }
 }
 
+/// How an [`Animator`] writes the animated value to its target.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum AnimatorBlend {
+    /// The lens output overwrites the target. This is the default.
+    #[default]
+    Replace,
+    /// Only the change of the lens output since the previous tick is added to
+    /// the target, so the animation is layered on top of whatever else moves
+    /// the component. Requires the target to implement [`Additive`].
+    Additive,
+}
+
+/// Components that can be animated with [`AnimatorBlend::Additive`].
+pub trait Additive: Clone + Send + Sync {
+    /// Add the change going from `from` to `to` onto `self`.
+    fn add_delta(&mut self, from: &Self, to: &Self);
+}
+
+impl Additive for Transform {
+    fn add_delta(&mut self, from: &Self, to: &Self) {
+        self.translation += to.translation - from.translation;
+        self.rotation = (to.rotation * from.rotation.inverse() * self.rotation).normalize();
+        // a zero scale can't be divided out, leave that axis alone
+        self.scale *= Vec3::select(from.scale.cmpeq(Vec3::ZERO), Vec3::ONE, to.scale / from.scale);
+    }
+}
+
 /// Describe how eased value should be computed.
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub enum EaseMethod {
     /// Follow `EaseFunction`.
     EaseFunction(EaseFunction),
@@ -241,14 +280,41 @@ pub enum EaseMethod {
     /// Discrete interpolation, eased value will jump from start to end when
     /// stepping over the discrete limit.
     Discrete(f32),
+    /// Quantize the ratio into this many equal steps, holding each value until
+    /// the next step is reached, for retro/stepped animations. The end value is
+    /// only reached at the very end, so `Steps(1)` holds the start value for
+    /// the whole animation. `Steps(0)` behaves like `Steps(1)`.
+    Steps(u32),
     /// Use a custom function to interpolate the value.
     CustomFunction(fn(f32) -> f32),
+    /// Use a custom closure to interpolate the value. Unlike
+    /// [`CustomFunction`], the closure can capture state, like the parameters
+    /// of a bespoke curve.
+    ///
+    /// [`CustomFunction`]: EaseMethod::CustomFunction
+    Custom(Arc<dyn Fn(f32) -> f32 + Send + Sync>),
+    /// Follow a cubic Bézier curve defined by two control points, with the
+    /// same semantic as the CSS `cubic-bezier(x1, y1, x2, y2)` timing
+    /// function. The curve implicitly starts at `(0, 0)` and ends at `(1, 1)`.
+    ///
+    /// The `x1` and `x2` coordinates are clamped to \[0:1\] so the curve stays
+    /// a function of time; `y1` and `y2` can overshoot to produce a bounce.
+    CubicBezier {
+        /// X coordinate of the first control point.
+        x1: f32,
+        /// Y coordinate of the first control point.
+        y1: f32,
+        /// X coordinate of the second control point.
+        x2: f32,
+        /// Y coordinate of the second control point.
+        y2: f32,
+    },
 }
 
 impl EaseMethod {
     #[must_use]
-    fn sample(self, x: f32) -> f32 {
-        match self {
+    fn sample(&self, x: f32) -> f32 {
+        match *self {
             Self::EaseFunction(function) => x.calc(function),
             Self::Linear => x,
             Self::Discrete(limit) => {
@@ -258,9 +324,72 @@ impl EaseMethod {
                     0.
                 }
             }
+            Self::Steps(steps) => {
+                let steps = steps.max(1) as f32;
+                (x.clamp(0., 1.) * steps).floor() / steps
+            }
             Self::CustomFunction(function) => function(x),
+            Self::Custom(ref function) => function(x),
+            Self::CubicBezier { x1, y1, x2, y2 } => cubic_bezier(x1.clamp(0., 1.), y1, x2.clamp(0., 1.), y2, x),
+        }
+    }
+}
+
+/// Evaluate one coordinate of a cubic Bézier curve with endpoints 0 and 1
+/// and control values `p1` and `p2` at the curve parameter `t`.
+fn bezier_coord(p1: f32, p2: f32, t: f32) -> f32 {
+    let u = 1. - t;
+    3. * u * u * t * p1 + 3. * u * t * t * p2 + t * t * t
+}
+
+/// Derivative of [`bezier_coord()`] with respect to `t`.
+fn bezier_coord_slope(p1: f32, p2: f32, t: f32) -> f32 {
+    let u = 1. - t;
+    3. * u * u * p1 + 6. * u * t * (p2 - p1) + 3. * t * t * (1. - p2)
+}
+
+/// Sample a CSS-style cubic Bézier timing function at time `x`.
+///
+/// The curve parameter `t` matching `x` is found with a few Newton-Raphson
+/// iterations, falling back to bisection when the slope is too flat for
+/// Newton to converge.
+fn cubic_bezier(x1: f32, y1: f32, x2: f32, y2: f32, x: f32) -> f32 {
+    const EPSILON: f32 = 1e-6;
+
+    let x = x.clamp(0., 1.);
+    if x <= 0. || x >= 1. {
+        return x;
+    }
+
+    let mut t = x;
+    for _ in 0..8 {
+        let err = bezier_coord(x1, x2, t) - x;
+        if err.abs() < EPSILON {
+            return bezier_coord(y1, y2, t);
+        }
+        let slope = bezier_coord_slope(x1, x2, t);
+        if slope.abs() < EPSILON {
+            break;
+        }
+        t = (t - err / slope).clamp(0., 1.);
+    }
+
+    let (mut lo, mut hi) = (0., 1.);
+    t = x;
+    for _ in 0..32 {
+        let err = bezier_coord(x1, x2, t) - x;
+        if err.abs() < EPSILON {
+            break;
         }
+        if err > 0. {
+            hi = t;
+        } else {
+            lo = t;
+        }
+        t = (lo + hi) * 0.5;
     }
+
+    bezier_coord(y1, y2, t)
 }
 
 impl Default for EaseMethod {
@@ -399,6 +528,9 @@ pub struct Animator<T: Component> {
     pub state: AnimatorState,
     tweenable: BoxedTweenable<T>,
     speed: f32,
+    blend: AnimatorBlend,
+    /// Lens output of the previous additive tick.
+    last_additive_value: Option<T>,
 }
 
 impl<T: Component + std::fmt::Debug> std::fmt::Debug for Animator<T> {
@@ -415,8 +547,294 @@ impl<T: Component> Animator<T> {
             state: default(),
             tweenable: Box::new(tween),
             speed: 1.,
+            blend: default(),
+            last_additive_value: None,
+        }
+    }
+
+    /// Set how the animator writes to its target. See [`AnimatorBlend`].
+    ///
+    /// Bevy only allows a single `Animator<T>` per entity, so an additive
+    /// animator layers on top of gameplay code or other systems moving the
+    /// component. Several additive animators can also be combined on a single
+    /// value by ticking them manually with [`tick_additive()`].
+    ///
+    /// [`tick_additive()`]: Animator::tick_additive
+    #[must_use]
+    pub fn with_blend(mut self, blend: AnimatorBlend) -> Self {
+        self.blend = blend;
+        self
+    }
+
+    /// Get how the animator writes to its target.
+    pub fn blend(&self) -> AnimatorBlend {
+        self.blend
+    }
+
+    animator_impl!();
+}
+
+/// Component to control the animation of an asset.
+///
+/// The animator holds the [`Handle<T>`] of the asset it animates, so it can be
+/// put on any entity. Animators whose asset isn't loaded yet are skipped.
+#[derive(Component)]
+pub struct AssetAnimator<T: Asset> {
+    /// Control if this animation is played or not.
+    pub state: AnimatorState,
+    tweenable: BoxedTweenable<T>,
+    handle: Handle<T>,
+    speed: f32,
+}
+
+impl<T: Asset + std::fmt::Debug> std::fmt::Debug for AssetAnimator<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AssetAnimator").field("state", &self.state).finish()
+    }
+}
+
+impl<T: Asset> AssetAnimator<T> {
+    /// Create a new asset animator component animating the asset of `handle`
+    /// with a single tweenable.
+    #[must_use]
+    pub fn new(handle: Handle<T>, tween: impl Tweenable<T> + 'static) -> Self {
+        Self {
+            state: default(),
+            tweenable: Box::new(tween),
+            handle,
+            speed: 1.,
         }
     }
 
+    /// Get the handle of the animated asset.
+    #[must_use]
+    pub fn handle(&self) -> &Handle<T> {
+        &self.handle
+    }
+
+    /// Set the handle of the animated asset, keeping the tweenable and its
+    /// progress.
+    pub fn set_handle(&mut self, handle: Handle<T>) {
+        self.handle = handle;
+    }
+
     animator_impl!();
 }
+
+impl<T: Component + Additive> Animator<T> {
+    /// Tick the tweenable and add the change of its lens output since the
+    /// previous tick onto `target`. On the first tick the lens is evaluated on
+    /// top of the current value of `target`, which acts as the base of the
+    /// animation.
+    pub fn tick_additive(
+        &mut self,
+        delta: Duration,
+        target: &mut T,
+        entity: Entity,
+        events: &mut Mut<Events<TweenCompleted>>,
+    ) -> TweenState {
+        let mut last = match self.last_additive_value.take() {
+            Some(last) => last,
+            None => {
+                let mut base = target.clone();
+                self.tweenable.peek(&mut base);
+                base
+            }
+        };
+
+        let mut value = last.clone();
+        let state = self
+            .tweenable
+            .tick(delta, &mut ValueTarget::new(&mut value), entity, events);
+        target.add_delta(&last, &value);
+
+        last = value;
+        self.last_additive_value = Some(last);
+        state
+    }
+}
+
+/// Insert a new [`Animator`] on each entity in `entities`, for effects like
+/// flashing all enemies at once.
+///
+/// Tweenables own their playback state (elapsed time, completion, repeat
+/// count) and are not `Clone`, so a single tween can't be shared between
+/// entities. `make_tween` is called once per entity to build an independent
+/// tween for each [`Animator`].
+pub fn apply_tween_to<T: Component>(
+    commands: &mut Commands,
+    entities: impl IntoIterator<Item = Entity>,
+    make_tween: impl Fn() -> Tween<T>,
+) {
+    for entity in entities {
+        commands.entity(entity).insert(Animator::new(make_tween()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::CommandQueue;
+
+    use super::*;
+    use crate::lens::TransformPositionLens;
+
+    fn assert_approx(expected: f32, actual: f32) {
+        assert!((expected - actual).abs() < 1e-3, "expected {expected}, got {actual}");
+    }
+
+    #[test]
+    fn cubic_bezier_endpoints() {
+        let ease = EaseMethod::CubicBezier {
+            x1: 0.42,
+            y1: 0.,
+            x2: 0.58,
+            y2: 1.,
+        };
+        assert_eq!(0., ease.sample(0.));
+        assert_eq!(1., ease.sample(1.));
+        assert_eq!(0., ease.sample(-1.));
+        assert_eq!(1., ease.sample(2.));
+    }
+
+    #[test]
+    fn cubic_bezier_linear() {
+        let ease = EaseMethod::CubicBezier {
+            x1: 0.,
+            y1: 0.,
+            x2: 1.,
+            y2: 1.,
+        };
+        for i in 0..=10 {
+            let x = i as f32 / 10.;
+            assert_approx(x, ease.sample(x));
+        }
+    }
+
+    #[test]
+    fn cubic_bezier_ease_in_out() {
+        // CSS `ease-in-out`
+        let ease = EaseMethod::CubicBezier {
+            x1: 0.42,
+            y1: 0.,
+            x2: 0.58,
+            y2: 1.,
+        };
+        assert_approx(0.5, ease.sample(0.5));
+        assert_approx(0.0816, ease.sample(0.2));
+        assert_approx(0.9184, ease.sample(0.8));
+    }
+
+    #[test]
+    fn cubic_bezier_flat_slope() {
+        // Zero slope at both ends forces the bisection fallback
+        let ease = EaseMethod::CubicBezier {
+            x1: 1.,
+            y1: 0.,
+            x2: 0.,
+            y2: 1.,
+        };
+        assert_approx(0.5, ease.sample(0.5));
+        assert!(ease.sample(0.01) < 0.01);
+    }
+
+    #[test]
+    fn cubic_bezier_clamps_control_x() {
+        // Out of range x values would loop back in time, they behave like 0 and 1
+        let clamped = EaseMethod::CubicBezier {
+            x1: -0.5,
+            y1: 0.,
+            x2: 1.5,
+            y2: 1.,
+        };
+        let expected = EaseMethod::CubicBezier {
+            x1: 0.,
+            y1: 0.,
+            x2: 1.,
+            y2: 1.,
+        };
+        let mut last = 0.;
+        for i in 0..=20 {
+            let x = i as f32 / 20.;
+            let y = clamped.sample(x);
+            assert_approx(expected.sample(x), y);
+            assert!(y >= last);
+            last = y;
+        }
+    }
+
+    #[test]
+    fn steps_quantize_ratio() {
+        let ease = EaseMethod::Steps(4);
+        let mut values: Vec<f32> = (0..100).map(|i| ease.sample(i as f32 / 100.)).collect();
+        values.dedup();
+        assert_eq!(values, vec![0., 0.25, 0.5, 0.75]);
+        assert_eq!(1., ease.sample(1.));
+
+        let ease = EaseMethod::Steps(1);
+        assert_eq!(0., ease.sample(0.999));
+        assert_eq!(1., ease.sample(1.));
+    }
+
+    #[test]
+    fn custom_closure() {
+        let linear = EaseMethod::Custom(Arc::new(|t| t));
+        let exponent = 2;
+        let squared = EaseMethod::Custom(Arc::new(move |t: f32| t.powi(exponent)));
+        for (x, y) in [(0.25, 0.0625), (0.5, 0.25), (0.75, 0.5625)] {
+            assert_eq!(x, linear.sample(x));
+            assert_approx(y, squared.sample(x));
+        }
+    }
+
+    #[test]
+    fn apply_tween_to_many_entities() {
+        let mut world = World::new();
+        let entities: Vec<Entity> = (0..5).map(|_| world.spawn(Transform::default()).id()).collect();
+
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        apply_tween_to(&mut commands, entities.iter().copied(), || {
+            Tween::new(
+                EaseFunction::QuadraticInOut,
+                Duration::from_secs(1),
+                TransformPositionLens {
+                    start: Vec3::ZERO,
+                    end: Vec3::ONE,
+                },
+            )
+        });
+        queue.apply(&mut world);
+
+        for entity in entities {
+            assert!(world.get::<Animator<Transform>>(entity).is_some());
+        }
+    }
+
+    #[test]
+    fn additive_animators_sum_offsets() {
+        let mut world = World::new();
+        world.init_resource::<Events<TweenCompleted>>();
+        let entity = world.spawn_empty().id();
+        let mut events = world.resource_mut::<Events<TweenCompleted>>();
+
+        let offset = |end: Vec3| {
+            Animator::new(Tween::new(
+                EaseMethod::Linear,
+                Duration::from_secs(1),
+                TransformPositionLens { start: Vec3::ZERO, end },
+            ))
+            .with_blend(AnimatorBlend::Additive)
+        };
+        let mut breathing = offset(Vec3::new(0., 2., 0.));
+        let mut lean = offset(Vec3::new(4., 0., 0.));
+
+        let mut target = Transform::from_xyz(10., 10., 10.);
+        for _ in 0..2 {
+            let delta = Duration::from_millis(250);
+            breathing.tick_additive(delta, &mut target, entity, &mut events);
+            lean.tick_additive(delta, &mut target, entity, &mut events);
+        }
+
+        assert!(target.translation.abs_diff_eq(Vec3::new(12., 11., 10.), 1e-5));
+    }
+}
@@ -1,46 +1,130 @@
-use crate::{tweenable::ComponentTarget, Animator, AnimatorState, TweenCompleted};
-use bevy::{ecs::component::Component, prelude::*};
+use crate::{
+    tweenable::{AssetTarget, ComponentTarget},
+    Additive, Animator, AnimatorBlend, AnimatorState, AssetAnimator, TweenCompleted,
+};
+use bevy::{ecs::component::Component, prelude::*, window::Window};
 
 /// Plugin to add systems related to tweening of common components and assets.
 ///
-/// This plugin adds systems for a predefined set of components and assets, to
-/// allow their respective animators to be updated each frame:
-/// - [`Transform`]
-/// - [`Text`]
-/// - [`Style`]
-/// - [`Sprite`]
-/// - [`ColorMaterial`]
-///
-/// This ensures that all predefined lenses work as intended, as well as any
-/// custom lens animating the same component or asset type.
+/// This plugin creates the [`Events<TweenCompleted>`] resource and adds the
+/// animator systems for [`Transform`], so the `Transform` lenses work as
+/// intended, as well as any custom lens animating it.
 ///
 /// For other components and assets, including custom ones, the relevant system
-/// needs to be added manually by the application:
-/// - For components, add [`component_animator_system::<T>`] where `T:
-///   Component`
-/// - For assets, add [`asset_animator_system::<T>`] where `T: Asset`
+/// needs to be registered by the application with [`TweeningAppExt`]:
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_tweening::*;
+/// App::new()
+///     .add_plugins((DefaultPlugins, TweeningPlugin))
+///     .add_tweening_component::<Sprite>()
+///     .add_tweening_asset::<StandardMaterial>();
+/// ```
+///
+/// This is the same as adding [`component_animator_system::<T>`] and
+/// [`asset_animator_system::<T>`] to the [`AnimationSystem::AnimationUpdate`]
+/// set manually.
 ///
 /// This plugin is entirely optional. If you want more control, you can instead
 /// add manually the relevant systems for the exact set of components and assets
 /// actually animated.
 ///
+/// The plugin also inserts the [`TweeningSettings`] resource, which can be used
+/// to pause all animations while the window is unfocused.
+///
 /// [`Transform`]: https://docs.rs/bevy/0.10.0/bevy/transform/components/struct.Transform.html
-/// [`Text`]: https://docs.rs/bevy/0.10.0/bevy/text/struct.Text.html
-/// [`Style`]: https://docs.rs/bevy/0.10.0/bevy/ui/struct.Style.html
-/// [`Sprite`]: https://docs.rs/bevy/0.10.0/bevy/sprite/struct.Sprite.html
-/// [`ColorMaterial`]: https://docs.rs/bevy/0.10.0/bevy/sprite/struct.ColorMaterial.html
 #[derive(Debug, Clone, Copy)]
 pub struct TweeningPlugin;
 
 impl Plugin for TweeningPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<TweenCompleted>().add_systems(
+        app.add_event::<TweenCompleted>()
+            .init_resource::<TweeningSettings>()
+            .configure_sets(Update, AnimationSystem::AnimationUpdate.run_if(tweening_unpaused))
+            .add_systems(
+                Update,
+                (
+                    component_animator_system::<Transform>,
+                    additive_animator_system::<Transform>,
+                )
+                    .in_set(AnimationSystem::AnimationUpdate),
+            );
+    }
+}
+
+/// Extension trait registering the animator systems of components and assets
+/// not animated by [`TweeningPlugin`] out of the box. The systems are added to
+/// the [`AnimationSystem::AnimationUpdate`] set so they respect
+/// [`TweeningSettings`] like the predefined ones.
+///
+/// Each type must only be registered once, [`Transform`] is already registered
+/// by the plugin.
+///
+/// [`Transform`]: https://docs.rs/bevy/0.10.0/bevy/transform/components/struct.Transform.html
+pub trait TweeningAppExt {
+    /// Adds [`component_animator_system::<T>`] to tick the [`Animator<T>`]
+    /// components.
+    ///
+    /// That system skips animators using [`AnimatorBlend::Additive`], use
+    /// [`add_tweening_additive_component()`] for components animated
+    /// additively.
+    ///
+    /// [`add_tweening_additive_component()`]: TweeningAppExt::add_tweening_additive_component
+    fn add_tweening_component<T: Component>(&mut self) -> &mut Self;
+
+    /// Adds both [`component_animator_system::<T>`] and
+    /// [`additive_animator_system::<T>`], to tick the [`Animator<T>`]
+    /// components whatever their [`AnimatorBlend`].
+    fn add_tweening_additive_component<T: Component + Additive>(&mut self) -> &mut Self;
+
+    /// Adds [`asset_animator_system::<T>`] to tick the [`AssetAnimator<T>`]
+    /// components.
+    fn add_tweening_asset<T: Asset>(&mut self) -> &mut Self;
+}
+
+impl TweeningAppExt for App {
+    fn add_tweening_component<T: Component>(&mut self) -> &mut Self {
+        self.add_systems(
+            Update,
+            component_animator_system::<T>.in_set(AnimationSystem::AnimationUpdate),
+        )
+    }
+
+    fn add_tweening_additive_component<T: Component + Additive>(&mut self) -> &mut Self {
+        self.add_systems(
+            Update,
+            (component_animator_system::<T>, additive_animator_system::<T>).in_set(AnimationSystem::AnimationUpdate),
+        )
+    }
+
+    fn add_tweening_asset<T: Asset>(&mut self) -> &mut Self {
+        self.add_systems(
             Update,
-            component_animator_system::<Transform>.in_set(AnimationSystem::AnimationUpdate),
-        );
+            asset_animator_system::<T>.in_set(AnimationSystem::AnimationUpdate),
+        )
     }
 }
 
+/// Global settings for the animator systems, inserted by [`TweeningPlugin`].
+#[derive(Resource, Debug, Default, Clone, Copy)]
+pub struct TweeningSettings {
+    /// Pause all animations while no window has focus. The frame delta can get
+    /// huge while the app is in the background, which makes everything jump
+    /// ahead after an alt-tab.
+    pub pause_when_unfocused: bool,
+}
+
+/// Run condition of the [`AnimationSystem::AnimationUpdate`] set, false while
+/// [`TweeningSettings::pause_when_unfocused`] is set and no window has focus.
+///
+/// Animator systems added manually outside of that set can use it as well.
+pub fn tweening_unpaused(settings: Option<Res<TweeningSettings>>, windows: Query<&Window>) -> bool {
+    !settings.is_some_and(|settings| settings.pause_when_unfocused)
+        || windows.is_empty()
+        || windows.iter().any(|window| window.focused)
+}
+
 /// Label enum for the systems relating to animations
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, SystemSet)]
 pub enum AnimationSystem {
@@ -59,7 +143,7 @@ pub fn component_animator_system<T: Component>(
 ) {
     let mut events: Mut<Events<TweenCompleted>> = events.into();
     for (entity, target, mut animator) in query.iter_mut() {
-        if animator.state != AnimatorState::Paused {
+        if animator.state != AnimatorState::Paused && animator.blend() == AnimatorBlend::Replace {
             let speed = animator.speed();
             let mut target = ComponentTarget::new(target);
             animator
@@ -68,3 +152,221 @@ pub fn component_animator_system<T: Component>(
         }
     }
 }
+
+/// Animator system for assets.
+///
+/// This system ticks all [`AssetAnimator<T>`] components to animate the asset
+/// of their handle. The asset is accessed mutably each tick so it is marked as
+/// modified, which is what makes e.g. material changes show up. Animators whose
+/// asset isn't loaded are skipped.
+pub fn asset_animator_system<T: Asset>(
+    time: Res<Time>,
+    assets: ResMut<Assets<T>>,
+    mut query: Query<(Entity, &mut AssetAnimator<T>)>,
+    events: ResMut<Events<TweenCompleted>>,
+) {
+    let mut events: Mut<Events<TweenCompleted>> = events.into();
+    let mut target = AssetTarget::new(assets.into());
+    for (entity, mut animator) in query.iter_mut() {
+        if animator.state != AnimatorState::Paused {
+            target.handle = animator.handle().clone();
+            if !target.is_valid() {
+                continue;
+            }
+            let speed = animator.speed();
+            animator
+                .tweenable_mut()
+                .tick(time.delta().mul_f32(speed), &mut target, entity, &mut events);
+        }
+    }
+}
+
+/// Animator system for components using [`AnimatorBlend::Additive`].
+///
+/// [`component_animator_system`] skips additive animators, so this system
+/// needs to be added as well for components animated additively.
+pub fn additive_animator_system<T: Component + Additive>(
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut T, &mut Animator<T>)>,
+    events: ResMut<Events<TweenCompleted>>,
+) {
+    let mut events: Mut<Events<TweenCompleted>> = events.into();
+    for (entity, mut target, mut animator) in query.iter_mut() {
+        if animator.state != AnimatorState::Paused && animator.blend() == AnimatorBlend::Additive {
+            let delta = time.delta().mul_f32(animator.speed());
+            animator.tick_additive(delta, &mut target, entity, &mut events);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{
+        lens::{Lens, StandardMaterialBaseColorLens, TransformPositionLens},
+        EaseMethod, Tween,
+    };
+
+    #[derive(Component, Default, Clone)]
+    struct Health(f32);
+
+    impl Additive for Health {
+        fn add_delta(&mut self, from: &Self, to: &Self) {
+            self.0 += to.0 - from.0;
+        }
+    }
+
+    struct HealthLens {
+        start: f32,
+        end: f32,
+    }
+
+    impl Lens<Health> for HealthLens {
+        fn lerp(&mut self, target: &mut Health, ratio: f32) {
+            target.0 = self.start + (self.end - self.start) * ratio;
+        }
+    }
+
+    #[test]
+    fn registered_component_is_animated() {
+        let mut app = App::new();
+        app.add_plugins(TweeningPlugin)
+            .add_tweening_component::<Health>()
+            .insert_resource(Time::<()>::default());
+        let entity = app
+            .world
+            .spawn((
+                Health::default(),
+                Animator::new(
+                    Tween::new(
+                        EaseMethod::Linear,
+                        Duration::from_secs(1),
+                        HealthLens { start: 0.0, end: 100.0 },
+                    )
+                    .with_completed_event(0),
+                ),
+            ))
+            .id();
+
+        app.world.resource_mut::<Time>().advance_by(Duration::from_millis(250));
+        app.update();
+        assert_eq!(app.world.get::<Health>(entity).unwrap().0, 25.0);
+
+        app.world.resource_mut::<Time>().advance_by(Duration::from_secs(1));
+        app.update();
+        assert_eq!(app.world.get::<Health>(entity).unwrap().0, 100.0);
+        assert_eq!(app.world.resource::<Events<TweenCompleted>>().len(), 1);
+    }
+
+    #[test]
+    fn registered_additive_component_is_animated() {
+        let mut app = App::new();
+        app.add_plugins(TweeningPlugin)
+            .add_tweening_additive_component::<Health>()
+            .insert_resource(Time::<()>::default());
+        let health_tween = || {
+            Tween::new(
+                EaseMethod::Linear,
+                Duration::from_secs(1),
+                HealthLens { start: 0.0, end: 100.0 },
+            )
+        };
+        let additive = app
+            .world
+            .spawn((
+                Health(10.0),
+                Animator::new(health_tween()).with_blend(AnimatorBlend::Additive),
+            ))
+            .id();
+        let replaced = app.world.spawn((Health(10.0), Animator::new(health_tween()))).id();
+
+        app.world.resource_mut::<Time>().advance_by(Duration::from_millis(250));
+        app.update();
+        assert_eq!(app.world.get::<Health>(additive).unwrap().0, 35.0);
+        assert_eq!(app.world.get::<Health>(replaced).unwrap().0, 25.0);
+    }
+
+    #[test]
+    fn asset_animator_modifies_its_asset() {
+        let mut app = App::new();
+        app.add_plugins((TaskPoolPlugin::default(), AssetPlugin::default(), TweeningPlugin))
+            .init_asset::<StandardMaterial>()
+            .add_tweening_asset::<StandardMaterial>()
+            .insert_resource(Time::<()>::default());
+        let handle = app
+            .world
+            .resource_mut::<Assets<StandardMaterial>>()
+            .add(StandardMaterial::default());
+        app.world.spawn(AssetAnimator::new(
+            handle.clone(),
+            Tween::new(
+                EaseMethod::Linear,
+                Duration::from_secs(1),
+                StandardMaterialBaseColorLens {
+                    start: Color::BLACK,
+                    end: Color::WHITE,
+                },
+            ),
+        ));
+        app.update();
+        app.world.resource_mut::<Events<AssetEvent<StandardMaterial>>>().clear();
+
+        app.world.resource_mut::<Time>().advance_by(Duration::from_millis(500));
+        app.update();
+
+        let material = app.world.resource::<Assets<StandardMaterial>>().get(&handle).unwrap();
+        assert!(Vec4::from(material.base_color).abs_diff_eq(Vec4::new(0.5, 0.5, 0.5, 1.0), 1e-5));
+        let modified = app
+            .world
+            .resource::<Events<AssetEvent<StandardMaterial>>>()
+            .iter_current_update_events()
+            .any(|event| *event == AssetEvent::Modified { id: handle.id() });
+        assert!(modified);
+    }
+
+    #[test]
+    fn unfocused_window_pauses_animators() {
+        let mut app = App::new();
+        app.add_plugins(TweeningPlugin)
+            .insert_resource(TweeningSettings {
+                pause_when_unfocused: true,
+            })
+            .insert_resource(Time::<()>::default());
+        let window = app.world.spawn(Window::default()).id();
+        let entity = app
+            .world
+            .spawn((
+                Transform::default(),
+                Animator::new(Tween::new(
+                    EaseMethod::Linear,
+                    Duration::from_secs(1),
+                    TransformPositionLens {
+                        start: Vec3::ZERO,
+                        end: Vec3::X,
+                    },
+                )),
+            ))
+            .id();
+
+        let step = |app: &mut App| {
+            app.world.resource_mut::<Time>().advance_by(Duration::from_millis(100));
+            app.update();
+            app.world
+                .get::<Animator<Transform>>(entity)
+                .unwrap()
+                .tweenable()
+                .elapsed()
+        };
+
+        let focused = step(&mut app);
+        assert!(focused > Duration::ZERO);
+
+        app.world.get_mut::<Window>(window).unwrap().focused = false;
+        assert_eq!(step(&mut app), focused);
+
+        app.world.get_mut::<Window>(window).unwrap().focused = true;
+        assert!(step(&mut app) > focused);
+    }
+}
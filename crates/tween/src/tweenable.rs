@@ -94,6 +94,16 @@ pub struct TweenCompleted {
     /// [`with_completed_event()`]: Tween::with_completed_event
     /// [`set_completed_event()`]: Tween::set_completed_event
     pub user_data: u64,
+    /// How many times the tweenable completed so far, including the
+    /// completion raising this event, as reported by
+    /// [`Tweenable::times_completed()`]. This tells apart the iterations of a
+    /// looping [`Tween`].
+    ///
+    /// Only [`Tween`] can loop. [`Sequence`], [`Tracks`], [`Spring`] and
+    /// [`Delay`] complete once per playback and always report `1`, replaying
+    /// them with [`Tweenable::rewind()`] starts counting over like it does for
+    /// a [`Tween`].
+    pub times_completed: u32,
 }
 
 /// Calculate the progress fraction in \[0:1\] of the ratio between two
@@ -217,6 +227,53 @@ impl<'a, T: Component> Targetable<T> for ComponentTarget<'a, T> {
     }
 }
 
+/// A [`Targetable`] over one asset of an [`Assets`] collection, selected by
+/// `handle`. Mutating the asset through [`Assets::get_mut`] sends its
+/// `AssetEvent::Modified` event.
+pub struct AssetTarget<'a, T: Asset> {
+    assets: Mut<'a, Assets<T>>,
+    /// Handle of the asset to animate.
+    pub handle: Handle<T>,
+}
+
+impl<'a, T: Asset> AssetTarget<'a, T> {
+    pub fn new(assets: Mut<'a, Assets<T>>) -> Self {
+        Self {
+            assets,
+            handle: Handle::default(),
+        }
+    }
+
+    /// Is `handle` pointing to an existing asset?
+    pub fn is_valid(&self) -> bool {
+        self.assets.contains(&self.handle)
+    }
+}
+
+impl<'a, T: Asset> Targetable<T> for AssetTarget<'a, T> {
+    fn target_mut(&mut self) -> &mut T {
+        self.assets.get_mut(&self.handle).unwrap()
+    }
+}
+
+/// A [`Targetable`] over a plain value that isn't stored in the world, like
+/// the scratch copy used by additive animators.
+pub struct ValueTarget<'a, T> {
+    target: &'a mut T,
+}
+
+impl<'a, T> ValueTarget<'a, T> {
+    pub fn new(target: &'a mut T) -> Self {
+        Self { target }
+    }
+}
+
+impl<'a, T: Send + Sync> Targetable<T> for ValueTarget<'a, T> {
+    fn target_mut(&mut self) -> &mut T {
+        self.target
+    }
+}
+
 /// An animatable entity, either a single [`Tween`] or a collection of them.
 pub trait Tweenable<T>: Send + Sync {
     /// Get the duration of a single iteration of the animation.
@@ -332,6 +389,37 @@ pub trait Tweenable<T>: Send + Sync {
     fn times_completed(&self) -> u32 {
         (self.elapsed().as_nanos() / self.duration().as_nanos()) as u32
     }
+
+    /// Write the value the animation applies at its current progress into
+    /// `scratch`, without advancing the animation or touching the real
+    /// target. Useful to inspect a tweenable while debugging.
+    ///
+    /// This takes `&mut self` only because [`Lens::lerp()`] does; the state of
+    /// the tweenable is left unchanged. The default implementation leaves
+    /// `scratch` untouched, which is what tweenables that don't animate
+    /// anything (like [`Delay`]) want.
+    fn peek(&mut self, scratch: &mut T) {
+        let _ = scratch;
+    }
+
+    /// Pause or resume the animation.
+    ///
+    /// While paused, [`tick()`] leaves the tweenable and its target untouched,
+    /// so resuming continues from the exact elapsed time it was paused at.
+    /// The default implementation ignores the call, for tweenables that can't
+    /// be paused.
+    ///
+    /// [`tick()`]: Tweenable::tick
+    fn set_paused(&mut self, paused: bool) {
+        let _ = paused;
+    }
+
+    /// Check whether the animation is paused. See [`set_paused()`].
+    ///
+    /// [`set_paused()`]: Tweenable::set_paused
+    fn is_paused(&self) -> bool {
+        false
+    }
 }
 
 macro_rules! impl_boxed {
@@ -348,20 +436,29 @@ impl_boxed!(Tween<T>);
 impl_boxed!(Sequence<T>);
 impl_boxed!(Tracks<T>);
 impl_boxed!(Delay<T>);
+impl_boxed!(Spring<T>);
 
 /// Type of a callback invoked when a [`Tween`] or [`Delay`] has completed.
 ///
 /// See [`Tween::set_completed()`] or [`Delay::set_completed()`] for usage.
 pub type CompletedCallback<T> = dyn Fn(Entity, &T) + Send + Sync + 'static;
 
+/// Type of a callback invoked when a [`Tween`] starts playing.
+///
+/// See [`Tween::set_started()`] for usage.
+pub type StartedCallback<T> = dyn Fn(Entity, &T) + Send + Sync + 'static;
+
 /// Single tweening animation instance.
 pub struct Tween<T> {
     ease_function: EaseMethod,
     clock: AnimClock,
     direction: TweeningDirection,
     lens: Box<dyn Lens<T> + Send + Sync + 'static>,
+    on_started: Option<Box<StartedCallback<Tween<T>>>>,
     on_completed: Option<Box<CompletedCallback<Tween<T>>>>,
     event_data: Option<u64>,
+    paused: bool,
+    speed: f32,
 }
 
 impl<T: 'static> Tween<T> {
@@ -424,8 +521,11 @@ impl<T> Tween<T> {
             clock: AnimClock::new(duration),
             direction: TweeningDirection::Forward,
             lens: Box::new(lens),
+            on_started: None,
             on_completed: None,
             event_data: None,
+            paused: false,
+            speed: 1.,
         }
     }
 
@@ -505,6 +605,25 @@ impl<T> Tween<T> {
         self
     }
 
+    /// Set a callback invoked when the animation starts.
+    ///
+    /// The callback fires on the first tick advancing the tween from zero
+    /// elapsed time, so a tween placed after a [`Delay`] in a [`Sequence`]
+    /// only starts once the delay is over. It fires again after [`rewind()`].
+    ///
+    /// See [`set_started()`] for details.
+    ///
+    /// [`rewind()`]: Tweenable::rewind
+    /// [`set_started()`]: Tween::set_started
+    #[must_use]
+    pub fn with_started<C>(mut self, callback: C) -> Self
+    where
+        C: Fn(Entity, &Self) + Send + Sync + 'static,
+    {
+        self.on_started = Some(Box::new(callback));
+        self
+    }
+
     /// Set the playback direction of the tween.
     ///
     /// The playback direction influences the mapping of the progress ratio (in
@@ -553,6 +672,60 @@ impl<T> Tween<T> {
         self
     }
 
+    /// Play the animation forward, then backward once, and stop at the start
+    /// value.
+    ///
+    /// This is a shortcut for [`RepeatCount::Finite(2)`] with
+    /// [`RepeatStrategy::MirroredRepeat`]. Each way counts as one iteration, so
+    /// a completed event or callback fires twice: once at the end value and
+    /// once when back at the start value. Only the second one leaves the tween
+    /// in [`TweenState::Completed`].
+    ///
+    /// [`RepeatCount::Finite(2)`]: RepeatCount::Finite
+    #[must_use]
+    pub fn ping_pong(self) -> Self {
+        self.with_repeat_count(RepeatCount::Finite(2))
+            .with_repeat_strategy(RepeatStrategy::MirroredRepeat)
+    }
+
+    /// Set the playback speed of this tween. See [`set_speed()`] for details.
+    ///
+    /// [`set_speed()`]: Tween::set_speed
+    #[must_use]
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.set_speed(speed);
+        self
+    }
+
+    /// Set the playback speed of this tween. Defaults to 1.
+    ///
+    /// The speed scales the time the tween advances by on each tick, on top of
+    /// any [`Animator`] speed, so a speed of 0.5 plays it in slow motion and a
+    /// speed of 0 holds it in place. [`duration()`] and [`total_duration()`]
+    /// keep reporting the unscaled values. Negative speeds are not supported
+    /// and are clamped to 0.
+    ///
+    /// [`Animator`]: crate::Animator
+    /// [`duration()`]: Tweenable::duration
+    /// [`total_duration()`]: Tweenable::total_duration
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = if speed >= 0. {
+            speed
+        } else {
+            warn!("tween speed must not be negative, got {speed}; clamping to 0");
+            0.
+        };
+    }
+
+    /// Get the playback speed of this tween.
+    ///
+    /// See [`set_speed()`] for a definition of what the playback speed is.
+    ///
+    /// [`set_speed()`]: Tween::set_speed
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
     /// Set a callback invoked when the animation completes.
     ///
     /// The callback when invoked receives as parameters the [`Entity`] on which
@@ -576,6 +749,27 @@ impl<T> Tween<T> {
         self.on_completed = None;
     }
 
+    /// Set a callback invoked when the animation starts.
+    ///
+    /// The callback when invoked receives as parameters the [`Entity`] on which
+    /// the target and the animator are, as well as a reference to the
+    /// current [`Tween`].
+    pub fn set_started<C>(&mut self, callback: C)
+    where
+        C: Fn(Entity, &Self) + Send + Sync + 'static,
+    {
+        self.on_started = Some(Box::new(callback));
+    }
+
+    /// Clear the callback invoked when the animation starts.
+    ///
+    /// See also [`set_started()`].
+    ///
+    /// [`set_started()`]: Tween::set_started
+    pub fn clear_started(&mut self) {
+        self.on_started = None;
+    }
+
     /// Enable or disable raising a completed event.
     ///
     /// If enabled, the tween will raise a [`TweenCompleted`] event when the
@@ -598,6 +792,13 @@ impl<T> Tween<T> {
     pub fn clear_completed_event(&mut self) {
         self.event_data = None;
     }
+
+    /// Map a playback progress to the ratio passed to the lens, taking into
+    /// account the playback direction and the easing.
+    fn lens_factor(&self, progress: f32) -> f32 {
+        let factor = if self.direction.is_backward() { 1. - progress } else { progress };
+        self.ease_function.sample(factor)
+    }
 }
 
 impl<T> Tweenable<T> for Tween<T> {
@@ -624,11 +825,14 @@ impl<T> Tweenable<T> for Tween<T> {
         entity: Entity,
         events: &mut Mut<Events<TweenCompleted>>,
     ) -> TweenState {
-        if self.clock.state() == TweenState::Completed {
-            return TweenState::Completed;
+        if self.paused || self.clock.state() == TweenState::Completed {
+            return self.clock.state();
         }
 
         // Tick the animation clock
+        // Skip scaling at the default speed, multiplying by a float would round the delta
+        let delta = if self.speed == 1. { delta } else { delta.mul_f64(self.speed.into()) };
+        let starting = self.clock.elapsed() == Duration::ZERO && delta > Duration::ZERO;
         let (state, times_completed) = self.clock.tick(delta);
         let (progress, times_completed_for_direction) = match state {
             TweenState::Active => (self.progress(), times_completed),
@@ -639,20 +843,23 @@ impl<T> Tweenable<T> for Tween<T> {
         }
 
         // Apply the lens, even if the animation finished, to ensure the state is consistent
-        let mut factor = progress;
-        if self.direction.is_backward() {
-            factor = 1. - factor;
-        }
-        let factor = self.ease_function.sample(factor);
+        let factor = self.lens_factor(progress);
         let target = target.target_mut();
         self.lens.lerp(target, factor);
 
+        if starting {
+            if let Some(cb) = &self.on_started {
+                cb(entity, self);
+            }
+        }
+
         // If completed at least once this frame, notify the user
         if times_completed > 0 {
             if let Some(user_data) = &self.event_data {
                 events.send(TweenCompleted {
                     entity,
                     user_data: *user_data,
+                    times_completed: self.clock.times_completed(),
                 });
             }
             if let Some(cb) = &self.on_completed {
@@ -698,6 +905,19 @@ impl<T> Tweenable<T> for Tween<T> {
     fn times_completed(&self) -> u32 {
         (self.elapsed().as_nanos() / self.duration().as_nanos()) as u32
     }
+
+    fn peek(&mut self, scratch: &mut T) {
+        let factor = self.lens_factor(self.progress());
+        self.lens.lerp(scratch, factor);
+    }
+
+    fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    fn is_paused(&self) -> bool {
+        self.paused
+    }
 }
 
 /// A sequence of tweens played back in order one after the other.
@@ -706,6 +926,8 @@ pub struct Sequence<T> {
     index: usize,
     duration: Duration,
     elapsed: Duration,
+    event_data: Option<u64>,
+    paused: bool,
 }
 
 impl<T> Sequence<T> {
@@ -720,6 +942,8 @@ impl<T> Sequence<T> {
             index: 0,
             duration,
             elapsed: Duration::ZERO,
+            event_data: None,
+            paused: false,
         }
     }
 
@@ -733,6 +957,8 @@ impl<T> Sequence<T> {
             index: 0,
             duration,
             elapsed: Duration::ZERO,
+            event_data: None,
+            paused: false,
         }
     }
 
@@ -744,6 +970,8 @@ impl<T> Sequence<T> {
             index: 0,
             duration: Duration::ZERO,
             elapsed: Duration::ZERO,
+            event_data: None,
+            paused: false,
         }
     }
 
@@ -766,6 +994,50 @@ impl<T> Sequence<T> {
     pub fn current(&self) -> &dyn Tweenable<T> {
         self.tweens[self.index()].as_ref()
     }
+
+    /// Jump to the start of the tween at `index`, e.g. to skip part of a
+    /// cutscene. The tweens before it count as played and all tweens are
+    /// rewound, so the one at `index` starts over on the next tick. Indices
+    /// past the end are clamped to the last tween.
+    ///
+    /// Like [`set_progress()`], this does not apply the change to the
+    /// animated target until the next [`tick()`].
+    ///
+    /// [`set_progress()`]: Tweenable::set_progress
+    /// [`tick()`]: Tweenable::tick
+    pub fn seek_to_index(&mut self, index: usize) {
+        let index = index.min(self.tweens.len().saturating_sub(1));
+        self.elapsed = self.tweens[..index].iter().map(|tween| tween.duration()).sum();
+        for tween in &mut self.tweens {
+            tween.rewind();
+        }
+        self.index = index;
+    }
+
+    /// Enable raising a completed event.
+    ///
+    /// If enabled, the sequence will raise a single [`TweenCompleted`] event
+    /// once its last tweenable completed, in addition to any event raised by
+    /// the individual tweenables it contains.
+    #[must_use]
+    pub fn with_completed_event(mut self, user_data: u64) -> Self {
+        self.event_data = Some(user_data);
+        self
+    }
+
+    /// Enable or disable raising a completed event.
+    ///
+    /// See [`with_completed_event()`] for details.
+    ///
+    /// [`with_completed_event()`]: Sequence::with_completed_event
+    pub fn set_completed_event(&mut self, user_data: u64) {
+        self.event_data = Some(user_data);
+    }
+
+    /// Clear the event sent when the sequence completes.
+    pub fn clear_completed_event(&mut self) {
+        self.event_data = None;
+    }
 }
 
 impl<T> Tweenable<T> for Sequence<T> {
@@ -812,6 +1084,10 @@ impl<T> Tweenable<T> for Sequence<T> {
         entity: Entity,
         events: &mut Mut<Events<TweenCompleted>>,
     ) -> TweenState {
+        let was_completed = self.index >= self.tweens.len();
+        if self.paused {
+            return if was_completed { TweenState::Completed } else { TweenState::Active };
+        }
         self.elapsed = self.elapsed.saturating_add(delta).min(self.duration);
         while self.index < self.tweens.len() {
             let tween = &mut self.tweens[self.index];
@@ -825,6 +1101,16 @@ impl<T> Tweenable<T> for Sequence<T> {
             self.index += 1;
         }
 
+        if !was_completed {
+            if let Some(user_data) = &self.event_data {
+                events.send(TweenCompleted {
+                    entity,
+                    user_data: *user_data,
+                    times_completed: self.times_completed(),
+                });
+            }
+        }
+
         TweenState::Completed
     }
 
@@ -836,13 +1122,30 @@ impl<T> Tweenable<T> for Sequence<T> {
             tween.rewind();
         }
     }
+
+    fn peek(&mut self, scratch: &mut T) {
+        let index = self.index();
+        self.tweens[index].peek(scratch);
+    }
+
+    fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    fn is_paused(&self) -> bool {
+        self.paused
+    }
 }
 
 /// A collection of [`Tweenable`] executing in parallel.
 pub struct Tracks<T> {
     tracks: Vec<BoxedTweenable<T>>,
+    /// Start time of each track, parallel to `tracks`.
+    offsets: Vec<Duration>,
     duration: Duration,
     elapsed: Duration,
+    event_data: Option<u64>,
+    paused: bool,
 }
 
 impl<T> Tracks<T> {
@@ -851,13 +1154,68 @@ impl<T> Tracks<T> {
     #[must_use]
     pub fn new(items: impl IntoIterator<Item = impl Into<BoxedTweenable<T>>>) -> Self {
         let tracks: Vec<_> = items.into_iter().map(Into::into).collect();
-        let duration = tracks.iter().map(AsRef::as_ref).map(Tweenable::duration).max().unwrap();
+        let offsets = vec![Duration::ZERO; tracks.len()];
+        Self::with_offsets(tracks, offsets)
+    }
+
+    /// Create a new [`Tracks`] where each tweenable starts after its offset,
+    /// to stagger animations without prepending a [`Delay`] to each track.
+    ///
+    /// A track stays idle without touching the target until the tracks have
+    /// been playing for its offset. The duration of the tracks is the longest
+    /// offset plus duration of a track.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there isn't exactly one offset per tweenable.
+    #[must_use]
+    pub fn with_offsets(
+        items: impl IntoIterator<Item = impl Into<BoxedTweenable<T>>>,
+        offsets: impl IntoIterator<Item = Duration>,
+    ) -> Self {
+        let tracks: Vec<_> = items.into_iter().map(Into::into).collect();
+        let offsets: Vec<_> = offsets.into_iter().collect();
+        assert_eq!(tracks.len(), offsets.len(), "expected one offset per track");
+        let duration = tracks
+            .iter()
+            .zip(&offsets)
+            .map(|(track, offset)| *offset + track.duration())
+            .max()
+            .unwrap();
         Self {
             tracks,
+            offsets,
             duration,
             elapsed: Duration::ZERO,
+            event_data: None,
+            paused: false,
         }
     }
+
+    /// Enable raising a completed event.
+    ///
+    /// If enabled, the tracks will raise a single [`TweenCompleted`] event
+    /// once all of their tweenables completed, in addition to any event raised
+    /// by the individual tweenables they contain.
+    #[must_use]
+    pub fn with_completed_event(mut self, user_data: u64) -> Self {
+        self.event_data = Some(user_data);
+        self
+    }
+
+    /// Enable or disable raising a completed event.
+    ///
+    /// See [`with_completed_event()`] for details.
+    ///
+    /// [`with_completed_event()`]: Tracks::with_completed_event
+    pub fn set_completed_event(&mut self, user_data: u64) {
+        self.event_data = Some(user_data);
+    }
+
+    /// Clear the event sent when the tracks complete.
+    pub fn clear_completed_event(&mut self) {
+        self.event_data = None;
+    }
 }
 
 impl<T> Tweenable<T> for Tracks<T> {
@@ -872,8 +1230,8 @@ impl<T> Tweenable<T> for Tracks<T> {
     fn set_elapsed(&mut self, elapsed: Duration) {
         self.elapsed = elapsed;
 
-        for tweenable in &mut self.tracks {
-            tweenable.set_elapsed(elapsed);
+        for (tweenable, offset) in self.tracks.iter_mut().zip(&self.offsets) {
+            tweenable.set_elapsed(elapsed.saturating_sub(*offset));
         }
     }
 
@@ -888,17 +1246,38 @@ impl<T> Tweenable<T> for Tracks<T> {
         entity: Entity,
         events: &mut Mut<Events<TweenCompleted>>,
     ) -> TweenState {
-        self.elapsed = self.elapsed.saturating_add(delta).min(self.duration);
+        let was_completed = self.elapsed >= self.duration;
+        if self.paused {
+            return if was_completed { TweenState::Completed } else { TweenState::Active };
+        }
+        let previous = self.elapsed;
+        let end = previous.saturating_add(delta);
+        self.elapsed = end.min(self.duration);
         let mut any_active = false;
-        for tweenable in &mut self.tracks {
+        for (tweenable, offset) in self.tracks.iter_mut().zip(&self.offsets) {
+            // Tracks that didn't reach their offset yet stay idle
+            if end < *offset {
+                any_active = true;
+                continue;
+            }
+            let delta = end - previous.max(*offset);
             let state = tweenable.tick(delta, target, entity, events);
             any_active = any_active || (state == TweenState::Active);
         }
         if any_active {
-            TweenState::Active
-        } else {
-            TweenState::Completed
+            return TweenState::Active;
         }
+
+        if !was_completed {
+            if let Some(user_data) = &self.event_data {
+                events.send(TweenCompleted {
+                    entity,
+                    user_data: *user_data,
+                    times_completed: self.times_completed(),
+                });
+            }
+        }
+        TweenState::Completed
     }
 
     fn rewind(&mut self) {
@@ -907,6 +1286,22 @@ impl<T> Tweenable<T> for Tracks<T> {
             tween.rewind();
         }
     }
+
+    fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    fn peek(&mut self, scratch: &mut T) {
+        for (tweenable, offset) in self.tracks.iter_mut().zip(&self.offsets) {
+            if self.elapsed >= *offset {
+                tweenable.peek(scratch);
+            }
+        }
+    }
 }
 
 /// A time delay that doesn't animate anything.
@@ -919,6 +1314,7 @@ pub struct Delay<T> {
     timer: Timer,
     on_completed: Option<Box<CompletedCallback<Delay<T>>>>,
     event_data: Option<u64>,
+    paused: bool,
 }
 
 impl<T: 'static> Delay<T> {
@@ -933,16 +1329,16 @@ impl<T: 'static> Delay<T> {
 impl<T> Delay<T> {
     /// Create a new [`Delay`] with a given duration.
     ///
-    /// # Panics
-    ///
-    /// Panics if the duration is zero.
+    /// The duration can be zero, in which case the delay completes on its
+    /// first tick, raising its completed event and invoking its completed
+    /// callback once like any other delay.
     #[must_use]
     pub fn new(duration: Duration) -> Self {
-        assert!(!duration.is_zero());
         Self {
             timer: Timer::new(duration, TimerMode::Once),
             on_completed: None,
             event_data: None,
+            paused: false,
         }
     }
 
@@ -1097,6 +1493,11 @@ impl<T> Tweenable<T> for Delay<T> {
         self.timer.elapsed()
     }
 
+    fn times_completed(&self) -> u32 {
+        // the default divides by the duration, which can be zero
+        u32::from(self.is_completed())
+    }
+
     fn tick(
         &mut self,
         delta: Duration,
@@ -1104,6 +1505,10 @@ impl<T> Tweenable<T> for Delay<T> {
         entity: Entity,
         events: &mut Mut<Events<TweenCompleted>>,
     ) -> TweenState {
+        if self.paused {
+            return self.state();
+        }
+
         let was_completed = self.is_completed();
 
         self.timer.tick(delta);
@@ -1116,6 +1521,7 @@ impl<T> Tweenable<T> for Delay<T> {
                 events.send(TweenCompleted {
                     entity,
                     user_data: *user_data,
+                    times_completed: self.times_completed(),
                 });
             }
             if let Some(cb) = &self.on_completed {
@@ -1129,4 +1535,612 @@ impl<T> Tweenable<T> for Delay<T> {
     fn rewind(&mut self) {
         self.timer.reset();
     }
+
+    fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    fn is_paused(&self) -> bool {
+        self.paused
+    }
+}
+
+/// A spring animation driving a [`Lens`] with a damped harmonic oscillator
+/// instead of a fixed-duration easing.
+///
+/// The spring starts at rest at the start of the lens (ratio `0`) and is
+/// pulled toward its end (ratio `1`), overshooting if it is underdamped. It
+/// completes once both its displacement from the end and its velocity drop
+/// below a small epsilon. A damping of `2 * sqrt(stiffness)` is critically
+/// damped, reaching the end as fast as possible without overshooting, which is
+/// the default.
+///
+/// Springs have no fixed duration. [`total_duration()`] is
+/// [`TotalDuration::Infinite`], and [`duration()`] reports the time the spring
+/// has been running so far, which makes them a poor fit for [`Sequence`] and
+/// [`Tracks`].
+///
+/// [`duration()`]: Tweenable::duration
+/// [`total_duration()`]: Tweenable::total_duration
+pub struct Spring<T> {
+    lens: Box<dyn Lens<T> + Send + Sync + 'static>,
+    stiffness: f32,
+    damping: f32,
+    position: f32,
+    velocity: f32,
+    elapsed: Duration,
+    completed: bool,
+    event_data: Option<u64>,
+    paused: bool,
+}
+
+impl<T> Spring<T> {
+    /// Displacement and velocity, in lens ratio units, below which the spring
+    /// is considered settled.
+    const SETTLE_EPSILON: f32 = 1e-3;
+
+    /// Longest integration step, larger ticks are split to keep stiff springs
+    /// stable.
+    const MAX_STEP: f32 = 1. / 240.;
+
+    /// Create a new critically damped spring with a stiffness of 100.
+    #[must_use]
+    pub fn new<L>(lens: L) -> Self
+    where
+        L: Lens<T> + Send + Sync + 'static,
+    {
+        Self {
+            lens: Box::new(lens),
+            stiffness: 100.,
+            damping: 20.,
+            position: 0.,
+            velocity: 0.,
+            elapsed: Duration::ZERO,
+            completed: false,
+            event_data: None,
+            paused: false,
+        }
+    }
+
+    /// Set the stiffness of the spring, how strongly it is pulled toward the
+    /// end of the lens. Higher values are faster.
+    #[must_use]
+    pub fn with_stiffness(mut self, stiffness: f32) -> Self {
+        self.stiffness = stiffness;
+        self
+    }
+
+    /// Set the damping of the spring, how strongly its velocity is slowed
+    /// down. Lower values than `2 * sqrt(stiffness)` make the spring bounce
+    /// around the end before settling.
+    #[must_use]
+    pub fn with_damping(mut self, damping: f32) -> Self {
+        self.damping = damping;
+        self
+    }
+
+    /// Enable raising a completed event.
+    ///
+    /// If enabled, the spring will raise a [`TweenCompleted`] event when it
+    /// settled.
+    #[must_use]
+    pub fn with_completed_event(mut self, user_data: u64) -> Self {
+        self.event_data = Some(user_data);
+        self
+    }
+
+    /// Get the stiffness of the spring.
+    pub fn stiffness(&self) -> f32 {
+        self.stiffness
+    }
+
+    /// Get the damping of the spring.
+    pub fn damping(&self) -> f32 {
+        self.damping
+    }
+
+    /// Get the current velocity of the spring, in lens ratio per second.
+    pub fn velocity(&self) -> f32 {
+        self.velocity
+    }
+
+    /// Integrate the spring over `delta`, then snap it to the end if it
+    /// settled.
+    fn step(&mut self, delta: Duration) {
+        let mut remaining = delta.as_secs_f32();
+        while remaining > 0. {
+            let dt = remaining.min(Self::MAX_STEP);
+            let acceleration = self.stiffness * (1. - self.position) - self.damping * self.velocity;
+            self.velocity += acceleration * dt;
+            self.position += self.velocity * dt;
+            remaining -= dt;
+        }
+
+        if (1. - self.position).abs() < Self::SETTLE_EPSILON && self.velocity.abs() < Self::SETTLE_EPSILON {
+            self.position = 1.;
+            self.velocity = 0.;
+            self.completed = true;
+        }
+    }
+
+    fn state(&self) -> TweenState {
+        if self.completed {
+            TweenState::Completed
+        } else {
+            TweenState::Active
+        }
+    }
+}
+
+impl<T> Tweenable<T> for Spring<T> {
+    fn duration(&self) -> Duration {
+        self.elapsed
+    }
+
+    fn total_duration(&self) -> TotalDuration {
+        TotalDuration::Infinite
+    }
+
+    /// Simulate the spring from rest for `elapsed`.
+    fn set_elapsed(&mut self, elapsed: Duration) {
+        self.rewind();
+        self.elapsed = elapsed;
+        self.step(elapsed);
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    fn tick(
+        &mut self,
+        delta: Duration,
+        target: &mut dyn Targetable<T>,
+        entity: Entity,
+        events: &mut Mut<Events<TweenCompleted>>,
+    ) -> TweenState {
+        if self.paused || self.completed {
+            return self.state();
+        }
+
+        self.elapsed = self.elapsed.saturating_add(delta);
+        self.step(delta);
+        self.lens.lerp(target.target_mut(), self.position);
+
+        if self.completed {
+            if let Some(user_data) = &self.event_data {
+                events.send(TweenCompleted {
+                    entity,
+                    user_data: *user_data,
+                    times_completed: self.times_completed(),
+                });
+            }
+        }
+
+        self.state()
+    }
+
+    fn rewind(&mut self) {
+        self.position = 0.;
+        self.velocity = 0.;
+        self.elapsed = Duration::ZERO;
+        self.completed = false;
+    }
+
+    /// The position of the spring between the start and end of the lens,
+    /// clamped to \[0:1\] while it overshoots.
+    fn progress(&self) -> f32 {
+        self.position.clamp(0., 1.)
+    }
+
+    fn times_completed(&self) -> u32 {
+        self.completed as u32
+    }
+
+    fn peek(&mut self, scratch: &mut T) {
+        self.lens.lerp(scratch, self.position);
+    }
+
+    fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    fn is_paused(&self) -> bool {
+        self.paused
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    };
+
+    use bevy::asset::AssetPlugin;
+
+    use super::*;
+    use crate::lens::{TransformPositionLens, TransformScaleLens};
+
+    #[derive(Asset, TypePath, Debug, Default)]
+    struct TestAsset(f32);
+
+    #[test]
+    fn asset_target_marks_asset_changed() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<TestAsset>();
+        let handle = app.world.resource_mut::<Assets<TestAsset>>().add(TestAsset(0.));
+        app.update();
+        app.world.resource_mut::<Events<AssetEvent<TestAsset>>>().clear();
+
+        let mut target = AssetTarget::new(app.world.resource_mut::<Assets<TestAsset>>());
+        target.handle = handle.clone();
+        assert!(target.is_valid());
+        target.target_mut().0 = 1.;
+        app.update();
+
+        assert_eq!(app.world.resource::<Assets<TestAsset>>().get(&handle).unwrap().0, 1.);
+        let events = app.world.resource::<Events<AssetEvent<TestAsset>>>();
+        let modified = events
+            .iter_current_update_events()
+            .any(|event| *event == AssetEvent::Modified { id: handle.id() });
+        assert!(modified);
+    }
+
+    #[test]
+    fn peek_writes_current_value_to_scratch() {
+        let mut tween = Tween::new(
+            EaseMethod::Linear,
+            Duration::from_secs(1),
+            TransformPositionLens {
+                start: Vec3::ZERO,
+                end: Vec3::new(4., 2., 0.),
+            },
+        );
+        tween.set_progress(0.25);
+
+        let mut scratch = Transform::default();
+        tween.peek(&mut scratch);
+        assert!(scratch.translation.abs_diff_eq(Vec3::new(1., 0.5, 0.), 1e-5));
+        assert_eq!(tween.progress(), 0.25);
+
+        tween.set_direction(TweeningDirection::Backward);
+        tween.peek(&mut scratch);
+        assert!(scratch.translation.abs_diff_eq(Vec3::new(3., 1.5, 0.), 1e-5));
+    }
+
+    struct TestTarget(Transform);
+
+    impl Targetable<Transform> for TestTarget {
+        fn target_mut(&mut self) -> &mut Transform {
+            &mut self.0
+        }
+    }
+
+    fn position_tween(end: Vec3) -> Tween<Transform> {
+        Tween::new(
+            EaseMethod::Linear,
+            Duration::from_secs(1),
+            TransformPositionLens { start: Vec3::ZERO, end },
+        )
+    }
+
+    #[test]
+    fn sequence_fires_completed_event_once() {
+        let mut world = World::new();
+        world.init_resource::<Events<TweenCompleted>>();
+        let entity = world.spawn_empty().id();
+        let mut events = world.resource_mut::<Events<TweenCompleted>>();
+        let mut target = TestTarget(Transform::default());
+
+        let mut sequence = position_tween(Vec3::X)
+            .then(position_tween(Vec3::Y))
+            .with_completed_event(7);
+
+        let mut tick = |sequence: &mut Sequence<Transform>, secs: f32| {
+            sequence.tick(Duration::from_secs_f32(secs), &mut target, entity, &mut events)
+        };
+        assert_eq!(tick(&mut sequence, 1.5), TweenState::Active);
+        assert_eq!(tick(&mut sequence, 1.0), TweenState::Completed);
+        assert_eq!(tick(&mut sequence, 1.0), TweenState::Completed);
+
+        let mut reader = events.get_reader();
+        let fired: Vec<_> = reader.read(&events).map(|ev| (ev.entity, ev.user_data)).collect();
+        assert_eq!(fired, vec![(entity, 7)]);
+    }
+
+    #[test]
+    fn replayed_sequence_and_tracks_report_single_completion() {
+        let mut world = World::new();
+        world.init_resource::<Events<TweenCompleted>>();
+        let entity = world.spawn_empty().id();
+        let mut events = world.resource_mut::<Events<TweenCompleted>>();
+        let mut target = TestTarget(Transform::default());
+
+        let mut sequence = position_tween(Vec3::X)
+            .then(position_tween(Vec3::Y))
+            .with_completed_event(1);
+        let mut tracks = Tracks::new([position_tween(Vec3::X), position_tween(Vec3::Y)]).with_completed_event(2);
+        for _ in 0..2 {
+            sequence.rewind();
+            tracks.rewind();
+            for _ in 0..3 {
+                sequence.tick(Duration::from_secs(1), &mut target, entity, &mut events);
+                tracks.tick(Duration::from_secs(1), &mut target, entity, &mut events);
+            }
+            assert_eq!(sequence.times_completed(), 1);
+            assert_eq!(tracks.times_completed(), 1);
+        }
+
+        let mut reader = events.get_reader();
+        let fired: Vec<_> = reader
+            .read(&events)
+            .map(|ev| (ev.user_data, ev.times_completed))
+            .collect();
+        assert_eq!(fired, vec![(2, 1), (1, 1), (2, 1), (1, 1)]);
+    }
+
+    #[test]
+    fn repeated_tween_events_count_iterations() {
+        let mut world = World::new();
+        world.init_resource::<Events<TweenCompleted>>();
+        let entity = world.spawn_empty().id();
+        let mut events = world.resource_mut::<Events<TweenCompleted>>();
+        let mut target = TestTarget(Transform::default());
+
+        let mut tween = position_tween(Vec3::X)
+            .with_repeat_count(RepeatCount::Finite(3))
+            .with_completed_event(5);
+        for _ in 0..4 {
+            tween.tick(Duration::from_secs(1), &mut target, entity, &mut events);
+        }
+
+        let mut reader = events.get_reader();
+        let fired: Vec<_> = reader
+            .read(&events)
+            .map(|ev| (ev.user_data, ev.times_completed))
+            .collect();
+        assert_eq!(fired, vec![(5, 1), (5, 2), (5, 3)]);
+    }
+
+    #[test]
+    fn started_callback_fires_after_rewind() {
+        let mut world = World::new();
+        world.init_resource::<Events<TweenCompleted>>();
+        let entity = world.spawn_empty().id();
+        let mut events = world.resource_mut::<Events<TweenCompleted>>();
+        let mut target = TestTarget(Transform::default());
+
+        let calls = Arc::new(AtomicU32::new(0));
+        let callback_calls = calls.clone();
+        let mut tween = position_tween(Vec3::X).with_started(move |_, tween| {
+            assert!(tween.elapsed() > Duration::ZERO);
+            callback_calls.fetch_add(1, Ordering::Relaxed);
+        });
+
+        let delta = Duration::from_millis(100);
+        tween.tick(Duration::ZERO, &mut target, entity, &mut events);
+        assert_eq!(calls.load(Ordering::Relaxed), 0);
+        tween.tick(delta, &mut target, entity, &mut events);
+        tween.tick(delta, &mut target, entity, &mut events);
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+        tween.rewind();
+        tween.tick(delta, &mut target, entity, &mut events);
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn zero_delay_completes_on_first_tick() {
+        let mut world = World::new();
+        world.init_resource::<Events<TweenCompleted>>();
+        let entity = world.spawn_empty().id();
+        let mut events = world.resource_mut::<Events<TweenCompleted>>();
+        let mut target = TestTarget(Transform::default());
+
+        let calls = Arc::new(AtomicU32::new(0));
+        let callback_calls = calls.clone();
+        let mut delay = Delay::new(Duration::ZERO).with_completed_event(3);
+        delay.set_completed(move |_, _| {
+            callback_calls.fetch_add(1, Ordering::Relaxed);
+        });
+        assert_eq!(delay.times_completed(), 0);
+
+        for _ in 0..2 {
+            let state = delay.tick(Duration::ZERO, &mut target, entity, &mut events);
+            assert_eq!(state, TweenState::Completed);
+        }
+        assert_eq!(delay.times_completed(), 1);
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+
+        let mut reader = events.get_reader();
+        let fired: Vec<_> = reader.read(&events).map(|ev| (ev.entity, ev.user_data)).collect();
+        assert_eq!(fired, vec![(entity, 3)]);
+    }
+
+    #[test]
+    fn sequence_seeks_to_child_start() {
+        let mut world = World::new();
+        world.init_resource::<Events<TweenCompleted>>();
+        let entity = world.spawn_empty().id();
+        let mut events = world.resource_mut::<Events<TweenCompleted>>();
+        let mut target = TestTarget(Transform::default());
+
+        let mut sequence = position_tween(Vec3::X)
+            .then(position_tween(Vec3::Y))
+            .then(position_tween(Vec3::Z));
+        sequence.tick(Duration::from_millis(500), &mut target, entity, &mut events);
+
+        sequence.seek_to_index(1);
+        assert_eq!(sequence.index(), 1);
+        assert!((sequence.progress() - 1. / 3.).abs() < 1e-5);
+        assert_eq!(sequence.current().elapsed(), Duration::ZERO);
+
+        // the next tick animates the second tween from its start
+        sequence.tick(Duration::from_millis(500), &mut target, entity, &mut events);
+        assert!(target.0.translation.abs_diff_eq(Vec3::Y * 0.5, 1e-5));
+
+        sequence.seek_to_index(7);
+        assert_eq!(sequence.index(), 2);
+        assert!((sequence.progress() - 2. / 3.).abs() < 1e-5);
+    }
+
+    #[test]
+    fn ping_pong_returns_to_start() {
+        let mut world = World::new();
+        world.init_resource::<Events<TweenCompleted>>();
+        let entity = world.spawn_empty().id();
+        let mut events = world.resource_mut::<Events<TweenCompleted>>();
+        let mut target = TestTarget(Transform::default());
+
+        let mut tween = position_tween(Vec3::X).ping_pong().with_completed_event(3);
+        let second = Duration::from_secs(1);
+
+        assert_eq!(tween.tick(second, &mut target, entity, &mut events), TweenState::Active);
+        assert!(target.0.translation.abs_diff_eq(Vec3::X, 1e-5));
+
+        assert_eq!(
+            tween.tick(second, &mut target, entity, &mut events),
+            TweenState::Completed
+        );
+        assert!(target.0.translation.abs_diff_eq(Vec3::ZERO, 1e-5));
+
+        let mut reader = events.get_reader();
+        assert_eq!(reader.read(&events).count(), 2);
+    }
+
+    #[test]
+    fn paused_tweenables_keep_their_elapsed_time() {
+        let mut world = World::new();
+        world.init_resource::<Events<TweenCompleted>>();
+        let entity = world.spawn_empty().id();
+        let mut events = world.resource_mut::<Events<TweenCompleted>>();
+        let quarter = Duration::from_millis(250);
+
+        let tweenables: Vec<BoxedTweenable<Transform>> = vec![
+            Box::new(position_tween(Vec3::X)),
+            Box::new(position_tween(Vec3::X).then(position_tween(Vec3::Y))),
+            Box::new(Tracks::new([position_tween(Vec3::X), position_tween(Vec3::Y)])),
+            Box::new(Delay::new(Duration::from_secs(1))),
+        ];
+        for mut tweenable in tweenables {
+            let mut target = TestTarget(Transform::default());
+            tweenable.tick(quarter, &mut target, entity, &mut events);
+            assert!(!tweenable.is_paused());
+
+            tweenable.set_paused(true);
+            assert!(tweenable.is_paused());
+            let translation = target.0.translation;
+            for _ in 0..3 {
+                let state = tweenable.tick(Duration::from_secs(1), &mut target, entity, &mut events);
+                assert_eq!(state, TweenState::Active);
+                assert_eq!(tweenable.elapsed(), quarter);
+                assert_eq!(target.0.translation, translation);
+            }
+
+            tweenable.set_paused(false);
+            tweenable.tick(quarter, &mut target, entity, &mut events);
+            assert_eq!(tweenable.elapsed(), quarter * 2);
+        }
+    }
+
+    #[test]
+    fn tween_speed_scales_elapsed() {
+        let mut world = World::new();
+        world.init_resource::<Events<TweenCompleted>>();
+        let entity = world.spawn_empty().id();
+        let mut events = world.resource_mut::<Events<TweenCompleted>>();
+        let mut target = TestTarget(Transform::default());
+        let delta = Duration::from_millis(200);
+
+        let mut normal = position_tween(Vec3::X);
+        let mut fast = position_tween(Vec3::X).with_speed(2.);
+        let mut frozen = position_tween(Vec3::X).with_speed(-1.);
+        assert_eq!(frozen.speed(), 0.);
+        for tween in [&mut normal, &mut fast, &mut frozen] {
+            tween.tick(delta, &mut target, entity, &mut events);
+        }
+
+        assert_eq!(normal.elapsed(), delta);
+        assert_eq!(fast.elapsed(), delta * 2);
+        assert_eq!(frozen.elapsed(), Duration::ZERO);
+        assert_eq!(fast.duration(), normal.duration());
+        assert_eq!(fast.total_duration(), normal.total_duration());
+    }
+
+    #[test]
+    fn spring_settles_at_target() {
+        let mut world = World::new();
+        world.init_resource::<Events<TweenCompleted>>();
+        let entity = world.spawn_empty().id();
+        let mut events = world.resource_mut::<Events<TweenCompleted>>();
+        let mut target = TestTarget(Transform::default());
+
+        let lens = TransformPositionLens {
+            start: Vec3::ZERO,
+            end: Vec3::X,
+        };
+        let mut spring = Spring::new(lens).with_stiffness(200.).with_damping(10.);
+        assert_eq!(Tweenable::<Transform>::total_duration(&spring), TotalDuration::Infinite);
+
+        let frame = Duration::from_secs_f32(1. / 60.);
+        let mut overshot = false;
+        let mut frames = 0;
+        while spring.tick(frame, &mut target, entity, &mut events) == TweenState::Active {
+            overshot |= target.0.translation.x > 1.;
+            frames += 1;
+            assert!(frames < 600, "spring didn't settle in 10 seconds");
+        }
+
+        assert!(overshot);
+        assert!(target.0.translation.abs_diff_eq(Vec3::X, 1e-5));
+        assert_eq!(Tweenable::<Transform>::progress(&spring), 1.);
+    }
+
+    #[test]
+    fn offset_track_stays_idle_until_its_offset() {
+        let mut world = World::new();
+        world.init_resource::<Events<TweenCompleted>>();
+        let entity = world.spawn_empty().id();
+        let mut events = world.resource_mut::<Events<TweenCompleted>>();
+        let mut target = TestTarget(Transform::default());
+
+        let second = Duration::from_secs(1);
+        let scale = Tween::new(
+            EaseMethod::Linear,
+            second,
+            TransformScaleLens {
+                start: Vec3::ONE,
+                end: Vec3::splat(2.),
+            },
+        );
+        let mut tracks = Tracks::with_offsets(
+            [
+                Box::new(position_tween(Vec3::X)) as BoxedTweenable<Transform>,
+                Box::new(scale),
+            ],
+            [Duration::ZERO, second],
+        );
+        assert_eq!(tracks.duration(), second * 2);
+
+        let half = second / 2;
+        assert_eq!(tracks.tick(half, &mut target, entity, &mut events), TweenState::Active);
+        assert_eq!(tracks.tick(half, &mut target, entity, &mut events), TweenState::Active);
+        assert!(target.0.translation.abs_diff_eq(Vec3::X, 1e-5));
+        assert_eq!(target.0.scale, Vec3::ONE);
+
+        assert_eq!(tracks.tick(half, &mut target, entity, &mut events), TweenState::Active);
+        assert!(target.0.scale.abs_diff_eq(Vec3::splat(1.5), 1e-5));
+        assert_eq!(
+            tracks.tick(half, &mut target, entity, &mut events),
+            TweenState::Completed
+        );
+        assert!(target.0.scale.abs_diff_eq(Vec3::splat(2.), 1e-5));
+
+        tracks.set_elapsed(half);
+        assert_eq!(tracks.tracks[1].elapsed(), Duration::ZERO);
+        tracks.set_elapsed(second + half);
+        assert_eq!(tracks.tracks[1].elapsed(), half);
+    }
 }
@@ -0,0 +1,144 @@
+//! packs the textures of a map into a single atlas so the whole map can share one material
+
+use std::collections::HashMap;
+
+use bevy::{
+    math::URect,
+    prelude::{Rect, UVec2, Vec2},
+};
+
+/// bytes per pixel of the atlas, textures are converted to `Rgba8UnormSrgb` before being copied in
+const BYTES_PER_PIXEL: usize = 4;
+
+/// texture name given to the faces moved into the atlas, so they all end up in a single mesh surface using the atlas
+/// material
+pub const ATLAS_TEXTURE: &str = "valve_map_atlas";
+
+/// where each texture ended up in the atlas, in pixels
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AtlasLayout {
+    pub size: UVec2,
+    pub rects: HashMap<String, URect>,
+}
+
+impl AtlasLayout {
+    /// shelf packs the textures: tallest first, left to right in rows no wider than `max_width` (or the widest
+    /// texture if that is wider)
+    pub fn pack(sizes: impl IntoIterator<Item = (String, UVec2)>, max_width: u32) -> Self {
+        let mut sizes: Vec<_> = sizes.into_iter().collect();
+        sizes.sort_by(|a, b| b.1.y.cmp(&a.1.y).then_with(|| a.0.cmp(&b.0)));
+        let max_width = sizes.iter().map(|(_, size)| size.x).fold(max_width, u32::max);
+
+        let mut layout = AtlasLayout::default();
+        let (mut cursor, mut row_height) = (UVec2::ZERO, 0);
+        for (name, size) in sizes {
+            if cursor.x > 0 && cursor.x + size.x > max_width {
+                cursor = UVec2::new(0, cursor.y + row_height);
+                row_height = 0;
+            }
+
+            layout.rects.insert(name, URect::from_corners(cursor, cursor + size));
+            cursor.x += size.x;
+            row_height = row_height.max(size.y);
+            layout.size = layout.size.max(UVec2::new(cursor.x, cursor.y + row_height));
+        }
+
+        layout
+    }
+
+    /// the rect of `texture` in normalized atlas uv coordinates
+    pub fn uv_rect(&self, texture: &str) -> Option<Rect> {
+        let rect = self.rects.get(texture)?;
+        let size = self.size.as_vec2();
+        Some(Rect::from_corners(rect.min.as_vec2() / size, rect.max.as_vec2() / size))
+    }
+
+    /// copies the `Rgba8` pixels of `texture` into its rect of the `Rgba8` `atlas` data
+    pub fn blit(&self, atlas: &mut [u8], texture: &str, pixels: &[u8]) {
+        let Some(rect) = self.rects.get(texture) else { return };
+        let row_bytes = rect.width() as usize * BYTES_PER_PIXEL;
+
+        for (y, row) in pixels.chunks_exact(row_bytes).enumerate() {
+            let start = ((rect.min.y as usize + y) * self.size.x as usize + rect.min.x as usize) * BYTES_PER_PIXEL;
+            atlas[start..start + row_bytes].copy_from_slice(row);
+        }
+    }
+}
+
+/// moves the uvs of a single face into `rect` and returns true, if the face fits in a single repeat of its texture
+/// once shifted by whole repeats. The atlas can't repeat a texture, so faces tiling it are left untouched and false is
+/// returned, they need to keep using their standalone texture.
+pub fn face_uvs_to_atlas(uvs: &mut [Vec2], rect: Rect) -> bool {
+    /// slack for uvs landing a hair past a texture edge because of float error
+    const EPSILON: f32 = 1e-4;
+
+    let min = uvs.iter().copied().reduce(Vec2::min).unwrap_or_default();
+    let shift = min.floor();
+    let fits = uvs
+        .iter()
+        .all(|uv| (*uv - shift).cmple(Vec2::splat(1.0 + EPSILON)).all());
+    if !fits {
+        return false;
+    }
+
+    for uv in uvs.iter_mut() {
+        let local = (*uv - shift).clamp(Vec2::ZERO, Vec2::ONE);
+        *uv = rect.min + local * rect.size();
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn packed_uvs_stay_in_their_rect() {
+        let layout = AtlasLayout::pack(
+            [
+                ("stone".to_string(), UVec2::new(64, 64)),
+                ("brick".to_string(), UVec2::new(128, 32)),
+                ("trim".to_string(), UVec2::new(64, 16)),
+            ],
+            128,
+        );
+        assert_eq!(layout.size, UVec2::new(128, 112));
+
+        let rects: Vec<_> = layout.rects.values().collect();
+        for (i, a) in rects.iter().enumerate() {
+            for b in &rects[i + 1..] {
+                assert!(a.intersect(**b).is_empty(), "{a:?} overlaps {b:?}");
+            }
+        }
+
+        // a face offset by a few whole repeats
+        let uvs = vec![Vec2::new(3.25, -2.0), Vec2::new(4.0, -1.5), Vec2::new(3.5, -1.0)];
+        for name in ["stone", "brick", "trim"] {
+            let rect = layout.uv_rect(name).unwrap();
+            let mut packed = uvs.clone();
+            assert!(face_uvs_to_atlas(&mut packed, rect));
+            for uv in packed {
+                assert!(rect.contains(uv), "{uv} outside of {name} {rect:?}");
+            }
+        }
+
+        let stone = layout.uv_rect("stone").unwrap();
+        let mut uvs = [Vec2::new(3.25, -2.0), Vec2::new(4.0, -1.5)];
+        assert!(face_uvs_to_atlas(&mut uvs, stone));
+        let expected = [Vec2::new(0.25, 0.0), Vec2::new(1.0, 0.5)].map(|uv| stone.min + uv * stone.size());
+        assert_eq!(uvs, expected);
+    }
+
+    #[test]
+    fn tiling_faces_are_not_packed() {
+        let rect = Rect::new(0.0, 0.0, 0.5, 0.5);
+        for tiling in [
+            [Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0), Vec2::new(2.0, 2.0)],
+            [Vec2::new(0.5, 0.0), Vec2::new(1.5, 0.0), Vec2::new(1.5, 1.0)],
+        ] {
+            let mut uvs = tiling;
+            assert!(!face_uvs_to_atlas(&mut uvs, rect));
+            assert_eq!(uvs, tiling);
+        }
+    }
+}
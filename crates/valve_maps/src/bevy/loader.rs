@@ -2,36 +2,67 @@ use bevy::{
     asset::{AssetLoader, LoadContext, LoadedAsset},
     prelude::*,
     render::{
-        render_resource::{AddressMode, FilterMode, SamplerDescriptor},
+        render_resource::{AddressMode, Extent3d, FilterMode, SamplerDescriptor, TextureDimension, TextureFormat},
         texture::{CompressedImageFormats, ImageSampler, ImageType},
     },
     utils::{BoxedFuture, HashMap},
 };
+use bevy_rapier3d::prelude::Collider;
 
 use crate::{
-    convert::{quake_point_to_bevy_point, MeshSurface},
+    atlas::{AtlasLayout, ATLAS_TEXTURE},
+    convert::{MeshSurface, UpAxis},
     formats::shared::Fields,
-    generate::{ConvexCollision, Geometry, TextureInfo},
+    generate::{ConcaveCollision, ConvexCollision, Geometry, TextureInfo},
 };
 
-use super::ValveMap;
+use super::{ValveMap, ValveMapColliderSettings, ValveMapSensors, ValveMapSettings, ValveMapWorldspawn};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ValveMapEntity {
     pub fields: Fields,
     pub collision_geometry: Vec<ConvexCollision>,
+    /// only present for entities flagged with the `concave` property
+    pub concave_collision: Option<ConcaveCollision>,
     pub visual_geometry: Vec<VisualGeometry>,
+    /// up axis of the source map, used when converting point properties to bevy space
+    pub up_axis: UpAxis,
 }
 
 impl ValveMapEntity {
-    fn new(fields: Fields, collision_geometry: Vec<ConvexCollision>) -> ValveMapEntity {
+    fn new(
+        fields: Fields,
+        collision_geometry: Vec<ConvexCollision>,
+        concave_collision: Option<ConcaveCollision>,
+        up_axis: UpAxis,
+    ) -> ValveMapEntity {
         ValveMapEntity {
             fields,
             visual_geometry: Vec::new(),
             collision_geometry,
+            concave_collision,
+            up_axis,
         }
     }
 
+    /// builds the colliders for this entity along with their positions. Concave entities get a single convex
+    /// decomposition when enabled in the settings, otherwise each brush gets its own convex hull.
+    pub fn build_colliders(&self, settings: &ValveMapColliderSettings) -> Vec<(Vec3, Collider)> {
+        if let (true, Some(concave)) = (settings.convex_decomposition, &self.concave_collision) {
+            let collider = Collider::convex_decomposition_with_params(
+                &concave.to_local(),
+                &concave.indices,
+                &settings.decomposition_params,
+            );
+            return vec![(concave.center(), collider)];
+        }
+
+        self.collision_geometry
+            .iter()
+            .filter_map(|geo| Collider::convex_hull(&geo.to_local()).map(|collider| (geo.center(), collider)))
+            .collect()
+    }
+
     pub fn get_property(&self, name: &str) -> Option<&str> {
         if let Some(s) = self.fields.get(&String::from(name)) {
             return Some(&s[..]);
@@ -39,11 +70,8 @@ impl ValveMapEntity {
         None
     }
 
-    pub fn is_sensor(&self) -> bool {
-        if let Some(prop) = self.fields.get("classname") {
-            return prop == "sensor";
-        }
-        false
+    pub fn is_sensor(&self, sensors: &ValveMapSensors) -> bool {
+        sensors.is_sensor(&self.fields)
     }
 
     pub fn get_bool_property(&self, name: &str) -> Option<bool> {
@@ -66,7 +94,7 @@ impl ValveMapEntity {
             let x: f32 = comps.next().unwrap_or("0.0").parse().unwrap_or(0.0);
             let y: f32 = comps.next().unwrap_or("0.0").parse().unwrap_or(0.0);
             let z: f32 = comps.next().unwrap_or("0.0").parse().unwrap_or(0.0);
-            return Some(quake_point_to_bevy_point(Vec3::new(x, y, z), 16.0));
+            return Some(self.up_axis.convert_point(Vec3::new(x, y, z), 16.0));
         }
         None
     }
@@ -82,19 +110,27 @@ impl ValveMapEntity {
         None
     }
 
+    /// yaw in degrees from the `angle` property, falling back to the yaw of a "pitch yaw roll" `angles` property
+    pub fn get_yaw_property(&self) -> Option<f32> {
+        self.get_f32_property("angle")
+            .or_else(|| self.get_vec3_property_raw("angles").map(|angles| angles.y))
+    }
+
     pub fn get_color_property(&self, name: &str) -> Option<Color> {
-        if let Some(prop) = self.fields.get(name) {
-            let mut comps = prop.split(' ');
-            let r: u8 = comps.next().unwrap_or("255").parse().unwrap_or(255);
-            let g: u8 = comps.next().unwrap_or("255").parse().unwrap_or(0);
-            let b: u8 = comps.next().unwrap_or("255").parse().unwrap_or(255);
-            return Some(Color::rgb_u8(r, g, b));
-        }
-        None
+        self.fields.get(name).map(|prop| parse_color(prop))
     }
 }
 
-#[derive(Debug)]
+/// parses a "r g b" property with 0 - 255 components
+pub(crate) fn parse_color(prop: &str) -> Color {
+    let mut comps = prop.split(' ');
+    let r: u8 = comps.next().unwrap_or("255").parse().unwrap_or(255);
+    let g: u8 = comps.next().unwrap_or("255").parse().unwrap_or(0);
+    let b: u8 = comps.next().unwrap_or("255").parse().unwrap_or(255);
+    Color::rgb_u8(r, g, b)
+}
+
+#[derive(Debug, Clone)]
 pub struct VisualGeometry {
     pub origin: Vec3,
     pub mesh: Handle<Mesh>,
@@ -107,8 +143,17 @@ impl VisualGeometry {
     }
 }
 
-#[derive(Default)]
-pub struct ValveMapLoader;
+pub struct ValveMapLoader {
+    settings: ValveMapSettings,
+}
+
+impl FromWorld for ValveMapLoader {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            settings: world.get_resource::<ValveMapSettings>().cloned().unwrap_or_default(),
+        }
+    }
+}
 
 impl AssetLoader for ValveMapLoader {
     fn load<'a>(
@@ -116,7 +161,7 @@ impl AssetLoader for ValveMapLoader {
         bytes: &'a [u8],
         load_context: &'a mut LoadContext,
     ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
-        Box::pin(async move { Ok(load_obj(bytes, load_context).await?) })
+        Box::pin(async move { Ok(load_obj(bytes, load_context, &self.settings).await?) })
     }
 
     fn extensions(&self) -> &[&str] {
@@ -141,13 +186,29 @@ fn texture_sampler<'a>() -> SamplerDescriptor<'a> {
     }
 }
 
-/// loads all Textures and creates a StandardMaterial per Texture. Grabs the texture dimensions as well for uv calculations
+/// widest the texture atlas gets before starting a new row of textures
+const ATLAS_MAX_WIDTH: u32 = 2048;
+
+/// texture sizes, the material for each texture name, the atlas layout when packing textures and the packed textures
+/// themselves, which faces tiling their texture still need as a standalone texture
+type LoadedTextures = (
+    TextureInfo,
+    HashMap<String, Handle<StandardMaterial>>,
+    Option<AtlasLayout>,
+    HashMap<String, Image>,
+);
+
+/// loads all Textures and creates a StandardMaterial per Texture. Grabs the texture dimensions as well for uv calculations.
+/// When `pack_textures` is set the textures are packed into a single atlas with a single material stored under
+/// `ATLAS_TEXTURE` instead, and the layout of the atlas is returned so the uvs can be moved into it.
 async fn load_textures(
     map: &crate::Map,
     load_context: &mut LoadContext<'_>,
-) -> Result<(TextureInfo, HashMap<String, Handle<StandardMaterial>>), bevy::asset::Error> {
+    pack_textures: bool,
+) -> Result<LoadedTextures, bevy::asset::Error> {
     let mut map_texture_info = TextureInfo::new();
     let mut materials = HashMap::new();
+    let mut packed_textures = Vec::new();
 
     // load all the textures since we will need their size then stuff them in materials
     for texture_name in map.get_texture_names() {
@@ -169,43 +230,133 @@ async fn load_textures(
             texture.texture_descriptor.size.height,
         );
 
-        // create a material with texture
-        let texture_handle = load_context.set_labeled_asset(&file, LoadedAsset::new(texture));
-
-        let material = StandardMaterial {
-            base_color_texture: Some(texture_handle),
-            ..default()
-        };
+        if pack_textures {
+            packed_textures.push((texture_name.clone(), texture));
+            continue;
+        }
 
-        let material_handle =
-            load_context.set_labeled_asset(&format!("materials/{}", texture_name), LoadedAsset::new(material));
+        let material_handle = texture_material(load_context, texture_name, texture);
         materials.insert(texture_name.clone(), material_handle);
     }
 
-    Ok((map_texture_info, materials))
+    if !pack_textures {
+        return Ok((map_texture_info, materials, None, HashMap::new()));
+    }
+
+    let (atlas, layout) = build_atlas(&packed_textures);
+    let texture_handle = load_context.set_labeled_asset("textures/atlas", LoadedAsset::new(atlas));
+    let material = StandardMaterial {
+        base_color_texture: Some(texture_handle),
+        ..default()
+    };
+    let material_handle = load_context.set_labeled_asset("materials/atlas", LoadedAsset::new(material));
+    materials.insert(ATLAS_TEXTURE.to_string(), material_handle);
+
+    let mut unpacked_textures = HashMap::new();
+    for (texture_name, texture) in packed_textures {
+        if layout.rects.contains_key(&texture_name) {
+            unpacked_textures.insert(texture_name, texture);
+            continue;
+        }
+
+        // textures that couldn't go in the atlas get their own material as usual
+        let material_handle = texture_material(load_context, &texture_name, texture);
+        materials.insert(texture_name, material_handle);
+    }
+
+    Ok((map_texture_info, materials, Some(layout), unpacked_textures))
+}
+
+/// adds `texture` and a material using it to the loaded assets
+fn texture_material(
+    load_context: &mut LoadContext<'_>,
+    texture_name: &str,
+    texture: Image,
+) -> Handle<StandardMaterial> {
+    let file = format!("textures/{}.png", texture_name);
+    let texture_handle = load_context.set_labeled_asset(&file, LoadedAsset::new(texture));
+    let material = StandardMaterial {
+        base_color_texture: Some(texture_handle),
+        ..default()
+    };
+    load_context.set_labeled_asset(&format!("materials/{}", texture_name), LoadedAsset::new(material))
+}
+
+/// packs the textures into a single `Rgba8UnormSrgb` image. Textures that can't be converted to that format are
+/// left out of the layout and keep their plain uvs.
+fn build_atlas(textures: &[(String, Image)]) -> (Image, AtlasLayout) {
+    let textures: Vec<(&String, Image)> = textures
+        .iter()
+        .filter_map(|(name, texture)| Some((name, texture.convert(TextureFormat::Rgba8UnormSrgb)?)))
+        .collect();
+
+    let layout = AtlasLayout::pack(
+        textures
+            .iter()
+            .map(|(name, texture)| (name.to_string(), texture.size().as_uvec2())),
+        ATLAS_MAX_WIDTH,
+    );
+
+    let mut data = vec![0; layout.size.x as usize * layout.size.y as usize * 4];
+    for (name, texture) in &textures {
+        layout.blit(&mut data, name, &texture.data);
+    }
+
+    let mut atlas = Image::new(
+        Extent3d {
+            width: layout.size.x.max(1),
+            height: layout.size.y.max(1),
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    atlas.sampler_descriptor = ImageSampler::Descriptor(texture_sampler());
+
+    (atlas, layout)
 }
 
-async fn load_obj<'a, 'b>(bytes: &'a [u8], load_context: &'a mut LoadContext<'b>) -> Result<(), bevy::asset::Error> {
+async fn load_obj<'a, 'b>(
+    bytes: &'a [u8],
+    load_context: &'a mut LoadContext<'b>,
+    settings: &ValveMapSettings,
+) -> Result<(), bevy::asset::Error> {
     let string = std::str::from_utf8(bytes)?;
     let map = super::super::parse(string).unwrap();
+    let worldspawn = map
+        .worldspawn()
+        .map(|e| ValveMapWorldspawn::from_fields(&e.fields))
+        .unwrap_or_default();
 
     // load all the textures since we will need their size then stuff them in materials
-    let (map_texture_info, materials) = load_textures(&map, load_context).await?;
+    let (map_texture_info, mut materials, atlas, mut unpacked_textures) =
+        load_textures(&map, load_context, settings.pack_textures).await?;
 
     // build general geometry which will be used to generate Meshes and Colliders
-    let entity_geometry = map.build_entity_geometry(&map_texture_info);
+    let mut entity_geometry = map.build_entity_geometry(&map_texture_info, settings.up_axis);
+    if let Some(atlas) = &atlas {
+        entity_geometry.iter_mut().for_each(|geo| geo.pack_uvs(atlas));
+    }
 
     // build collision geometry, a Vec of ConvexCollision per entity
     let collision_geometry: Vec<Vec<ConvexCollision>> =
         entity_geometry.iter().map(Geometry::get_collision_geometry).collect();
 
+    // concave entities also get their full triangle mesh so they can be decomposed when instantiated
+    let concave_geometry: Vec<Option<ConcaveCollision>> = entity_geometry
+        .iter()
+        .zip(&map.entities)
+        .map(|(geo, e)| e.fields.is_concave().then(|| geo.get_concave_collision_geometry()))
+        .collect();
+
     // build visual geometry, a Vec of MeshSurfaces per entity
     let mesh_surfaces: Vec<Vec<MeshSurface>> = entity_geometry
         .iter()
-        .enumerate()
+        .zip(&map.entities)
         .map(
-            |(i, geo)| {
-                if map.entities[i].fields.is_sensor() {
+            |(geo, e)| {
+                if settings.sensors.is_sensor(&e.fields) {
                     Vec::new()
                 } else {
                     geo.get_visual_geometry()
@@ -221,14 +372,19 @@ async fn load_obj<'a, 'b>(bytes: &'a [u8], load_context: &'a mut LoadContext<'b>
     let mut entities: Vec<ValveMapEntity> = map
         .entities
         .into_iter()
-        .zip(collision_geometry)
-        .map(|(e, cg)| ValveMapEntity::new(e.fields, cg))
+        .zip(collision_geometry.into_iter().zip(concave_geometry))
+        .map(|(e, (cg, concave))| ValveMapEntity::new(e.fields, cg, concave, settings.up_axis))
         .collect();
 
     for (i, mesh_surface) in mesh_surfaces.iter().enumerate() {
         for (j, surface) in mesh_surface.iter().enumerate() {
             let material = {
                 if let Some(tex_name) = &surface.texture {
+                    // packed textures only get a standalone material once a face tiling them needs one
+                    if let Some(texture) = unpacked_textures.remove(tex_name) {
+                        let material_handle = texture_material(load_context, tex_name, texture);
+                        materials.insert(tex_name.clone(), material_handle);
+                    }
                     materials.get(tex_name).unwrap().clone()
                 } else {
                     default_material_handle.clone()
@@ -247,7 +403,7 @@ async fn load_obj<'a, 'b>(bytes: &'a [u8], load_context: &'a mut LoadContext<'b>
         }
     }
 
-    let valve_map = ValveMap { entities };
+    let valve_map = ValveMap { entities, worldspawn };
     load_context.set_default_asset(LoadedAsset::new(valve_map));
 
     Ok(())
@@ -1,17 +1,77 @@
 use bevy::{
+    asset::HandleId,
     prelude::*,
     reflect::{TypePath, TypeUuid},
+    utils::HashSet,
 };
-use bevy_rapier3d::prelude::{ActiveEvents, RigidBody, Sensor};
+use bevy_rapier3d::prelude::{ActiveEvents, RigidBody, Sensor, VHACDParameters};
 
-use self::loader::{ValveMapEntity, ValveMapLoader};
+use crate::{convert::UpAxis, formats::shared::Fields, generate::points_bounds};
+
+use self::loader::{parse_color, ValveMapEntity, ValveMapLoader};
+pub use self::overrides::ValveMapOverrides;
 
 pub mod loader;
+mod overrides;
 
 #[derive(Debug, TypeUuid, TypePath)]
 #[uuid = "44cadc56-aa9c-4543-8640-a018b74b5052"]
 pub struct ValveMap {
     pub entities: Vec<ValveMapEntity>,
+    pub worldspawn: ValveMapWorldspawn,
+}
+
+impl ValveMap {
+    pub fn mesh_handles(&self) -> impl Iterator<Item = &Handle<Mesh>> {
+        self.entities
+            .iter()
+            .flat_map(|e| e.visual_geometry.iter().map(|geo| &geo.mesh))
+    }
+
+    pub fn material_handles(&self) -> impl Iterator<Item = &Handle<StandardMaterial>> {
+        self.entities
+            .iter()
+            .flat_map(|e| e.visual_geometry.iter().map(|geo| &geo.material))
+    }
+
+    /// axis aligned `(min, max)` bounds of all brush vertices in Bevy space, e.g. to frame a camera on the map. A map
+    /// without brushes gets zero sized bounds at the origin.
+    pub fn bounds(&self) -> (Vec3, Vec3) {
+        let points = self
+            .entities
+            .iter()
+            .flat_map(|e| e.collision_geometry.iter().flat_map(|geo| geo.points.iter().copied()));
+        points_bounds(points).unwrap_or_default()
+    }
+
+    /// center of the [`bounds`](Self::bounds)
+    pub fn center(&self) -> Vec3 {
+        let (min, max) = self.bounds();
+        (min + max) * 0.5
+    }
+}
+
+/// global map properties read from the worldspawn entity. Inserted as a Resource each time a map is instantiated
+/// so games can configure the scene (sky, physics, fog) from the map.
+#[derive(Resource, Debug, Clone, Default, PartialEq)]
+pub struct ValveMapWorldspawn {
+    pub skybox: Option<String>,
+    pub gravity: Option<f32>,
+    pub fog_color: Option<Color>,
+    pub fog_density: Option<f32>,
+}
+
+impl ValveMapWorldspawn {
+    pub fn from_fields(fields: &Fields) -> Self {
+        let get_f32 = |name| fields.get_property(name).and_then(|v| v.parse::<f32>().ok());
+
+        Self {
+            skybox: fields.get_property("skybox").map(String::from),
+            gravity: get_f32("gravity"),
+            fog_color: fields.get_property("fog_color").map(parse_color),
+            fog_density: get_f32("fog_density"),
+        }
+    }
 }
 
 #[derive(Default, Bundle)]
@@ -23,22 +83,145 @@ pub struct ValveMapBundle {
     pub computed_visibility: ComputedVisibility,
 }
 
+/// add to the Entity with the `ValveMapBundle` to offset the map root so the center of the map's bounds ends up at
+/// the origin. Spawn points are offset along with it.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct ValveMapRecenter;
+
 /// any Entities with this Component will be warped to the "classname = spawn_point" from the map on map load or reload
 #[derive(Component)]
 pub struct ValveMapPlayer;
 
+/// sent for each `ValveMapPlayer` warped to a spawn point. Controllers that keep their view yaw outside of the
+/// `Transform` use it to face the spawn point's `angle`/`angles` too.
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct ValveMapPlayerSpawned {
+    pub entity: Entity,
+    /// yaw in radians the player's `Transform` was rotated to
+    pub yaw: f32,
+}
+
 /// Component added to the Entity that the Handle<ValveMap> was added to after the map is loaded. Used later
-/// during hot-reload to identify the map and swap in the new one.
+/// during hot-reload to identify the map and swap in the new one. Weak handles to the meshes and materials of the
+/// current instantiation are kept so any that the reloaded map no longer uses can be released.
 #[derive(Component)]
-struct ValveMapHandled(pub Handle<ValveMap>);
+struct ValveMapHandled {
+    map: Handle<ValveMap>,
+    meshes: Vec<Handle<Mesh>>,
+    materials: Vec<Handle<StandardMaterial>>,
+}
+
+impl ValveMapHandled {
+    fn new(handle: Handle<ValveMap>, map: &ValveMap) -> Self {
+        Self {
+            map: handle,
+            meshes: map.mesh_handles().map(Handle::clone_weak).collect(),
+            materials: map.material_handles().map(Handle::clone_weak).collect(),
+        }
+    }
+
+    /// removes any previously instantiated mesh/material that the new version of the map does not reference
+    fn release_stale_assets(
+        &self,
+        map: &ValveMap,
+        meshes: &mut Assets<Mesh>,
+        materials: &mut Assets<StandardMaterial>,
+    ) {
+        let live_meshes: HashSet<HandleId> = map.mesh_handles().map(Handle::id).collect();
+        for mesh in self.meshes.iter().filter(|h| !live_meshes.contains(&h.id())) {
+            meshes.remove(mesh);
+        }
+
+        let live_materials: HashSet<HandleId> = map.material_handles().map(Handle::id).collect();
+        for material in self.materials.iter().filter(|h| !live_materials.contains(&h.id())) {
+            materials.remove(material);
+        }
+    }
+}
+
+/// controls when a loaded map gets instantiated. Insert this Resource after adding the `ValveMapPlugin` to change
+/// the default of `Auto`.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValveMapSpawnMode {
+    /// entities are spawned as soon as the map asset finishes loading
+    #[default]
+    Auto,
+    /// the map asset is loaded but nothing is spawned until a `SpawnValveMap` event is sent for the Entity holding
+    /// the map handle. Useful for preloading maps behind a loading screen.
+    Manual,
+}
+
+/// requests instantiation of the map on the given Entity (the one the `ValveMapBundle` was added to) when using
+/// `ValveMapSpawnMode::Manual`. If the map is still loading it will be spawned as soon as it is ready.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct SpawnValveMap(pub Entity);
+
+/// controls how colliders are generated when a map is instantiated. By default every brush gets a convex hull which
+/// is wrong for concave shapes. Enabling `convex_decomposition` makes entities with a `"concave" "1"` property get
+/// a single V-HACD decomposed collider built from all of their brushes instead.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ValveMapColliderSettings {
+    pub convex_decomposition: bool,
+    pub decomposition_params: VHACDParameters,
+}
+
+/// settings used by the loader when converting maps. Insert before adding the `ValveMapPlugin`, the loader reads
+/// them once when it is created so changing the resource afterwards, e.g. at runtime, has no effect.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ValveMapSettings {
+    pub up_axis: UpAxis,
+    /// packs all the map textures into a single atlas image used by a single material to cut down on materials and
+    /// draw calls. The atlas can't repeat textures so faces tiling their texture more than once keep using the
+    /// standalone texture, see `atlas::face_uvs_to_atlas`.
+    pub pack_textures: bool,
+    /// which entities are sensors, see [`ValveMapSensors`]
+    pub sensors: ValveMapSensors,
+}
+
+/// decides which entities are sensors: they get no visual geometry and their colliders get a `Sensor`. An entity is a
+/// sensor when its classname is one of `classnames` or starts with one of `prefixes`, e.g. `trigger_` to treat all
+/// the `trigger_once`/`trigger_multiple` style entities as sensors. Defaults to just the `sensor` classname.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValveMapSensors {
+    pub classnames: HashSet<String>,
+    pub prefixes: Vec<String>,
+}
+
+impl Default for ValveMapSensors {
+    fn default() -> Self {
+        Self {
+            classnames: HashSet::from_iter(["sensor".to_string()]),
+            prefixes: Vec::new(),
+        }
+    }
+}
+
+impl ValveMapSensors {
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefixes.push(prefix.into());
+        self
+    }
+
+    pub fn is_sensor(&self, fields: &Fields) -> bool {
+        fields.get_property("classname").is_some_and(|classname| {
+            self.classnames.contains(classname) || self.prefixes.iter().any(|prefix| classname.starts_with(prefix))
+        })
+    }
+}
 
 #[derive(Default)]
 pub struct ValveMapPlugin;
 
 impl Plugin for ValveMapPlugin {
     fn build(&self, app: &mut App) {
-        app.init_asset_loader::<ValveMapLoader>()
+        app.init_resource::<ValveMapSettings>()
+            .init_asset_loader::<ValveMapLoader>()
             .add_asset::<ValveMap>()
+            .init_resource::<ValveMapSpawnMode>()
+            .init_resource::<ValveMapColliderSettings>()
+            .init_resource::<ValveMapOverrides>()
+            .add_event::<SpawnValveMap>()
+            .add_event::<ValveMapPlayerSpawned>()
             .add_systems(Update, handle_loaded_maps);
     }
 }
@@ -46,55 +229,112 @@ impl Plugin for ValveMapPlugin {
 fn handle_loaded_maps(
     mut commands: Commands,
     mut ev_asset: EventReader<AssetEvent<ValveMap>>,
+    mut ev_spawn: EventReader<SpawnValveMap>,
+    mut pending_spawns: Local<HashSet<Entity>>,
+    spawn_mode: Res<ValveMapSpawnMode>,
+    collider_settings: Res<ValveMapColliderSettings>,
+    settings: Res<ValveMapSettings>,
+    overrides: Res<ValveMapOverrides>,
     map_assets: ResMut<Assets<ValveMap>>,
-    q: Query<(Entity, &Handle<ValveMap>)>,
-    q_mod: Query<(Entity, &ValveMapHandled)>,
-    q_players: Query<&mut Transform, With<ValveMapPlayer>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    q: Query<(Entity, &Handle<ValveMap>, Option<&ValveMapRecenter>)>,
+    mut q_mod: Query<(Entity, &mut ValveMapHandled, Option<&ValveMapRecenter>)>,
+    q_players: Query<(Entity, &mut Transform), With<ValveMapPlayer>>,
+    mut ev_player_spawned: EventWriter<ValveMapPlayerSpawned>,
 ) {
-    for (entity, map_bundle) in q.iter() {
+    pending_spawns.extend(ev_spawn.iter().map(|ev| ev.0));
+
+    let root_offset = |map: &ValveMap, recenter: Option<&ValveMapRecenter>| match recenter {
+        Some(_) => -map.center(),
+        None => Vec3::ZERO,
+    };
+
+    for (entity, map_bundle, recenter) in q.iter() {
+        if *spawn_mode == ValveMapSpawnMode::Manual && !pending_spawns.contains(&entity) {
+            continue;
+        }
+
         if let Some(map) = map_assets.get(&map_bundle) {
+            pending_spawns.remove(&entity);
+            let offset = root_offset(map, recenter);
             commands.entity(entity).remove::<ValveMapBundle>().insert((
-                ValveMapHandled(map_bundle.clone()),
-                TransformBundle::default(),
+                ValveMapHandled::new(map_bundle.clone(), map),
+                TransformBundle::from_transform(Transform::from_translation(offset)),
                 VisibilityBundle::default(),
                 Name::new("ValveMapRoot"),
             ));
-            instantiate_map_entities(&mut commands, entity, map, q_players);
+            instantiate_map_entities(
+                &mut commands,
+                entity,
+                map,
+                offset,
+                &collider_settings,
+                &settings.sensors,
+                &overrides,
+                q_players,
+                &mut ev_player_spawned,
+            );
             return;
         }
     }
 
     for ev in ev_asset.iter() {
         if let AssetEvent::Modified { handle } = ev {
-            for (entity, map_bundle) in q_mod.iter() {
-                if map_bundle.0 != *handle {
+            for (entity, mut handled, recenter) in q_mod.iter_mut() {
+                if handled.map != *handle {
                     continue;
                 }
                 commands.entity(entity).despawn_descendants();
 
-                let map = map_assets.get(&map_bundle.0).unwrap();
-                instantiate_map_entities(&mut commands, entity, map, q_players);
+                let map = map_assets.get(&handled.map).unwrap();
+                handled.release_stale_assets(map, &mut meshes, &mut materials);
+                *handled = ValveMapHandled::new(handled.map.clone(), map);
+                let offset = root_offset(map, recenter);
+                if recenter.is_some() {
+                    commands.entity(entity).insert(Transform::from_translation(offset));
+                }
+                instantiate_map_entities(
+                    &mut commands,
+                    entity,
+                    map,
+                    offset,
+                    &collider_settings,
+                    &settings.sensors,
+                    &overrides,
+                    q_players,
+                    &mut ev_player_spawned,
+                );
                 return;
             }
         }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn instantiate_map_entities(
     commands: &mut Commands,
     entity: Entity,
     map: &ValveMap,
-    mut q_players: Query<&mut Transform, With<ValveMapPlayer>>,
+    root_offset: Vec3,
+    collider_settings: &ValveMapColliderSettings,
+    sensors: &ValveMapSensors,
+    overrides: &ValveMapOverrides,
+    mut q_players: Query<(Entity, &mut Transform), With<ValveMapPlayer>>,
+    ev_player_spawned: &mut EventWriter<ValveMapPlayerSpawned>,
 ) {
+    commands.insert_resource(map.worldspawn.clone());
+
     commands.entity(entity).with_children(|builder| {
-        for map_entity in &map.entities {
+        for (i, map_entity) in map.entities.iter().enumerate() {
+            let map_entity = overrides.apply(i, map_entity);
             println!(
                 "------------ class: {:?}, visuals: {}, collisions: {}",
                 map_entity.get_property("classname"),
                 map_entity.visual_geometry.len(),
                 map_entity.collision_geometry.len()
             );
-            let is_sensor = map_entity.is_sensor();
+            let is_sensor = map_entity.is_sensor(sensors);
 
             // handle any point types
             if let Some("light") = map_entity.get_property("classname") {
@@ -113,13 +353,14 @@ fn instantiate_map_entities(
 
             if let Some("spawn_point") = map_entity.get_property("classname") {
                 let position = map_entity.get_vec3_property("origin").unwrap();
-                let rotation = map_entity
-                    .get_f32_property("angle")
-                    .and_then(|a| Some(a - 90.))
-                    .unwrap_or(0.);
-                for mut tf in q_players.iter_mut() {
-                    tf.translation = position;
-                    tf.rotation = Quat::from_rotation_y(rotation.to_radians());
+                let yaw = map_entity
+                    .get_yaw_property()
+                    .map_or(0., |angle| angle - 90.)
+                    .to_radians();
+                for (player, mut tf) in q_players.iter_mut() {
+                    tf.translation = position + root_offset;
+                    tf.rotation = Quat::from_rotation_y(yaw);
+                    ev_player_spawned.send(ValveMapPlayerSpawned { entity: player, yaw });
                 }
             }
 
@@ -135,12 +376,12 @@ fn instantiate_map_entities(
                 ));
             }
 
-            for geo in &map_entity.collision_geometry {
+            for (center, collider) in map_entity.build_colliders(collider_settings) {
                 let mut entity = builder.spawn((
-                    // Collider::convex_hull(&geo.to_local()).unwrap(),
+                    collider,
                     RigidBody::Fixed, // is this necessary?
                     GlobalTransform::default(),
-                    Transform::from_translation(geo.center()),
+                    Transform::from_translation(center),
                     Name::new("ValveMapBrushCollider"),
                 ));
 
@@ -151,3 +392,329 @@ fn instantiate_map_entities(
         }
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_rapier3d::prelude::Collider;
+
+    #[test]
+    fn manual_spawn_mode_waits_for_event() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), ValveMapPlugin))
+            .add_asset::<Mesh>()
+            .add_asset::<StandardMaterial>()
+            .insert_resource(ValveMapSpawnMode::Manual);
+
+        let mut fields = Fields::default();
+        fields.insert("classname".into(), "light".into());
+        fields.insert("origin".into(), "0 0 0".into());
+        let map = ValveMap {
+            entities: vec![ValveMapEntity {
+                fields,
+                collision_geometry: Vec::new(),
+                concave_collision: None,
+                up_axis: UpAxis::Z,
+                visual_geometry: Vec::new(),
+            }],
+            worldspawn: default(),
+        };
+        let handle = app.world.resource_mut::<Assets<ValveMap>>().add(map);
+        let root = app
+            .world
+            .spawn(ValveMapBundle {
+                map: handle,
+                ..default()
+            })
+            .id();
+
+        app.update();
+        app.update();
+        assert!(app.world.get::<Children>(root).is_none());
+
+        app.world.send_event(SpawnValveMap(root));
+        app.update();
+        assert_eq!(app.world.get::<Children>(root).map(|c| c.len()), Some(1));
+    }
+
+    #[test]
+    fn hot_reload_releases_previous_assets() {
+        fn build_map(app: &mut App) -> ValveMap {
+            let mesh = app.world.resource_mut::<Assets<Mesh>>().add(Mesh::new(
+                bevy::render::render_resource::PrimitiveTopology::TriangleList,
+            ));
+            let material = app
+                .world
+                .resource_mut::<Assets<StandardMaterial>>()
+                .add(StandardMaterial::default());
+
+            ValveMap {
+                entities: vec![ValveMapEntity {
+                    fields: Fields::default(),
+                    collision_geometry: Vec::new(),
+                    concave_collision: None,
+                    up_axis: UpAxis::Z,
+                    visual_geometry: vec![loader::VisualGeometry {
+                        origin: Vec3::ZERO,
+                        mesh,
+                        material,
+                    }],
+                }],
+                worldspawn: default(),
+            }
+        }
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), ValveMapPlugin))
+            .add_asset::<Mesh>()
+            .add_asset::<StandardMaterial>();
+
+        let map = build_map(&mut app);
+        let handle = app.world.resource_mut::<Assets<ValveMap>>().add(map);
+        app.world.spawn(ValveMapBundle {
+            map: handle.clone(),
+            ..default()
+        });
+        app.update();
+
+        for _ in 0..3 {
+            let map = build_map(&mut app);
+            app.world
+                .resource_mut::<Assets<ValveMap>>()
+                .set_untracked(handle.clone(), map);
+            app.world.send_event(AssetEvent::Modified { handle: handle.clone() });
+            app.update();
+            app.update();
+
+            assert_eq!(app.world.resource::<Assets<Mesh>>().len(), 1);
+            assert_eq!(app.world.resource::<Assets<StandardMaterial>>().len(), 1);
+        }
+    }
+
+    #[test]
+    fn concave_entity_decomposes_into_multiple_pieces() {
+        use crate::generate::{ConcaveCollision, ConvexCollision};
+
+        fn cuboid(min: Vec3, max: Vec3) -> (Vec<Vec3>, Vec<[u32; 3]>) {
+            let vertices = (0..8)
+                .map(|i| Vec3::select(BVec3::new(i & 1 != 0, i & 2 != 0, i & 4 != 0), max, min))
+                .collect();
+            let indices = vec![
+                [0, 2, 1],
+                [1, 2, 3],
+                [4, 5, 6],
+                [5, 7, 6],
+                [0, 1, 4],
+                [1, 5, 4],
+                [2, 6, 3],
+                [3, 6, 7],
+                [0, 4, 2],
+                [2, 4, 6],
+                [1, 3, 5],
+                [3, 7, 5],
+            ];
+            (vertices, indices)
+        }
+
+        // an L shape made of two boxes
+        let boxes = [
+            cuboid(Vec3::ZERO, Vec3::new(4.0, 1.0, 1.0)),
+            cuboid(Vec3::ZERO, Vec3::new(1.0, 4.0, 1.0)),
+        ];
+        let mut concave = ConcaveCollision::new(Vec::new(), Vec::new());
+        for (vertices, indices) in &boxes {
+            let offset = concave.vertices.len() as u32;
+            concave.vertices.extend(vertices);
+            concave
+                .indices
+                .extend(indices.iter().map(|tri| tri.map(|i| i + offset)));
+        }
+
+        let map_entity = ValveMapEntity {
+            fields: Fields::default(),
+            collision_geometry: boxes.iter().map(|(v, _)| ConvexCollision::new(v.clone())).collect(),
+            concave_collision: Some(concave),
+            up_axis: UpAxis::Z,
+            visual_geometry: Vec::new(),
+        };
+
+        let hulls = map_entity.build_colliders(&ValveMapColliderSettings::default());
+        assert_eq!(hulls.len(), 2);
+
+        let settings = ValveMapColliderSettings {
+            convex_decomposition: true,
+            ..default()
+        };
+        let decomposed = map_entity.build_colliders(&settings);
+        assert_eq!(decomposed.len(), 1);
+        let compound = decomposed[0].1.as_compound().unwrap();
+        assert!(compound.shapes().len() > 1);
+    }
+
+    #[test]
+    fn overrides_apply_on_instantiation() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), ValveMapPlugin))
+            .add_asset::<Mesh>()
+            .add_asset::<StandardMaterial>();
+
+        let mut fields = Fields::default();
+        fields.insert("classname".into(), "light".into());
+        fields.insert("origin".into(), "0 0 0".into());
+        fields.insert("intensity".into(), "800".into());
+        let map = ValveMap {
+            entities: vec![ValveMapEntity {
+                fields,
+                collision_geometry: Vec::new(),
+                concave_collision: None,
+                up_axis: UpAxis::Z,
+                visual_geometry: Vec::new(),
+            }],
+            worldspawn: default(),
+        };
+
+        app.world
+            .resource_mut::<ValveMapOverrides>()
+            .set(0, "intensity", "1234");
+        let handle = app.world.resource_mut::<Assets<ValveMap>>().add(map);
+        app.world.spawn(ValveMapBundle {
+            map: handle,
+            ..default()
+        });
+        app.update();
+
+        let light = app.world.query::<&PointLight>().single(&app.world);
+        assert_eq!(light.intensity, 1234.0);
+    }
+
+    /// worldspawn with a single 128x128x32 brush with a corner at the origin
+    fn cube_map() -> ValveMap {
+        let map = crate::parse(
+            r#"
+{
+"classname" "worldspawn"
+{
+( 0 0 0 ) ( 0 1 0 ) ( 0 0 1 ) __TB_empty [ 0 -1 0 0 ] [ 0 0 -1 0 ] 0 1 1
+( 0 0 0 ) ( 0 0 1 ) ( 1 0 0 ) __TB_empty [ 1 0 0 0 ] [ 0 0 -1 0 ] 0 1 1
+( 0 0 0 ) ( 1 0 0 ) ( 0 1 0 ) __TB_empty [ -1 0 0 0 ] [ 0 -1 0 0 ] 0 1 1
+( 128 128 32 ) ( 128 129 32 ) ( 129 128 32 ) __TB_empty [ 1 0 0 0 ] [ 0 -1 0 0 ] 0 1 1
+( 128 128 32 ) ( 129 128 32 ) ( 128 128 33 ) __TB_empty [ -1 0 0 0 ] [ 0 0 -1 0 ] 0 1 1
+( 128 128 32 ) ( 128 128 33 ) ( 128 129 32 ) __TB_empty [ 0 1 0 0 ] [ 0 0 -1 0 ] 0 1 1
+}
+}
+"#,
+        )
+        .unwrap();
+
+        let geometry = map.build_entity_geometry(&crate::generate::TextureInfo::new(), UpAxis::Z);
+        let entities = map
+            .entities
+            .into_iter()
+            .zip(&geometry)
+            .map(|(e, geo)| ValveMapEntity {
+                fields: e.fields,
+                collision_geometry: geo.get_collision_geometry(),
+                concave_collision: None,
+                up_axis: UpAxis::Z,
+                visual_geometry: Vec::new(),
+            })
+            .collect();
+
+        ValveMap {
+            entities,
+            worldspawn: default(),
+        }
+    }
+
+    #[test]
+    fn cube_map_bounds() {
+        let map = cube_map();
+
+        // Z-up quake units to Y-up, 16 units per meter
+        let (min, max) = map.bounds();
+        assert!(min.abs_diff_eq(Vec3::new(0.0, 0.0, -8.0), 1e-4), "min {min}");
+        assert!(max.abs_diff_eq(Vec3::new(8.0, 2.0, 0.0), 1e-4), "max {max}");
+        assert!(map.center().abs_diff_eq(Vec3::new(4.0, 1.0, -4.0), 1e-4));
+    }
+
+    #[test]
+    fn trigger_prefix_makes_sensors() {
+        let mut fields = Fields::default();
+        fields.insert("classname".into(), "trigger_once".into());
+        assert!(!ValveMapSensors::default().is_sensor(&fields));
+        assert!(ValveMapSensors::default().with_prefix("trigger_").is_sensor(&fields));
+
+        let mut app = App::new();
+        app.insert_resource(ValveMapSettings {
+            sensors: ValveMapSensors::default().with_prefix("trigger_"),
+            ..default()
+        })
+        .add_plugins((MinimalPlugins, AssetPlugin::default(), ValveMapPlugin))
+        .add_asset::<Mesh>()
+        .add_asset::<StandardMaterial>();
+
+        let mut map = cube_map();
+        map.entities[0].fields = fields;
+        let handle = app.world.resource_mut::<Assets<ValveMap>>().add(map);
+        app.world.spawn(ValveMapBundle {
+            map: handle,
+            ..default()
+        });
+        app.update();
+
+        let mut sensors = app.world.query_filtered::<(), (With<Collider>, With<Sensor>)>();
+        assert_eq!(sensors.iter(&app.world).count(), 1);
+    }
+
+    #[test]
+    fn recenter_offsets_map_root() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), ValveMapPlugin))
+            .add_asset::<Mesh>()
+            .add_asset::<StandardMaterial>();
+
+        let handle = app.world.resource_mut::<Assets<ValveMap>>().add(cube_map());
+        let root = app
+            .world
+            .spawn((
+                ValveMapBundle {
+                    map: handle,
+                    ..default()
+                },
+                ValveMapRecenter,
+            ))
+            .id();
+        app.update();
+
+        let translation = app.world.get::<Transform>(root).unwrap().translation;
+        assert!(
+            translation.abs_diff_eq(Vec3::new(-4.0, -1.0, 4.0), 1e-4),
+            "root at {translation}"
+        );
+    }
+
+    #[test]
+    fn worldspawn_properties() {
+        let map = crate::parse(
+            r#"
+{
+"classname" "worldspawn"
+"skybox" "sky/day"
+"gravity" "800"
+"fog_color" "128 64 255"
+"fog_density" "0.02"
+}
+{
+"classname" "light"
+}
+"#,
+        )
+        .unwrap();
+
+        let worldspawn = ValveMapWorldspawn::from_fields(&map.worldspawn().unwrap().fields);
+        assert_eq!(worldspawn.skybox.as_deref(), Some("sky/day"));
+        assert_eq!(worldspawn.gravity, Some(800.0));
+        assert_eq!(worldspawn.fog_color, Some(Color::rgb_u8(128, 64, 255)));
+        assert_eq!(worldspawn.fog_density, Some(0.02));
+    }
+}
@@ -0,0 +1,83 @@
+use std::{borrow::Cow, collections::BTreeMap, path::Path};
+
+use bevy::prelude::Resource;
+use serde::{Deserialize, Serialize};
+
+use super::loader::ValveMapEntity;
+
+/// non-destructive property edits layered on top of the parsed map, keyed by the index of the entity in the map file.
+/// They are applied each time a map is instantiated and can be saved to/loaded from disk (as RON) so edits made in
+/// game survive without rewriting the .map file.
+#[derive(Resource, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ValveMapOverrides(pub BTreeMap<usize, BTreeMap<String, String>>);
+
+impl ValveMapOverrides {
+    pub fn set(&mut self, entity_index: usize, key: impl Into<String>, value: impl Into<String>) {
+        self.0.entry(entity_index).or_default().insert(key.into(), value.into());
+    }
+
+    pub fn get(&self, entity_index: usize, key: &str) -> Option<&str> {
+        self.0.get(&entity_index)?.get(key).map(String::as_str)
+    }
+
+    /// removes an override, returning the entity to the value from the map file
+    pub fn remove(&mut self, entity_index: usize, key: &str) -> Option<String> {
+        let properties = self.0.get_mut(&entity_index)?;
+        let value = properties.remove(key);
+        if properties.is_empty() {
+            self.0.remove(&entity_index);
+        }
+        value
+    }
+
+    /// returns the entity with any overrides applied. Only entities that have overrides get cloned.
+    pub fn apply<'a>(&self, entity_index: usize, entity: &'a ValveMapEntity) -> Cow<'a, ValveMapEntity> {
+        match self.0.get(&entity_index) {
+            Some(properties) if !properties.is_empty() => {
+                let mut entity = entity.clone();
+                entity
+                    .fields
+                    .extend(properties.iter().map(|(k, v)| (k.clone(), v.clone())));
+                Cow::Owned(entity)
+            }
+            _ => Cow::Borrowed(entity),
+        }
+    }
+
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+    }
+
+    pub fn from_ron(ron: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(ron)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), bevy::asset::Error> {
+        std::fs::write(path, self.to_ron()?)?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, bevy::asset::Error> {
+        Ok(Self::from_ron(&std::fs::read_to_string(path)?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ron_round_trip() {
+        let mut overrides = ValveMapOverrides::default();
+        overrides.set(3, "intensity", "1200");
+        overrides.set(3, "color", "255 0 0");
+        overrides.set(7, "target", "door_1");
+
+        let ron = overrides.to_ron().unwrap();
+        assert_eq!(ValveMapOverrides::from_ron(&ron).unwrap(), overrides);
+
+        assert_eq!(overrides.remove(7, "target").as_deref(), Some("door_1"));
+        assert!(!overrides.0.contains_key(&7));
+        assert_eq!(overrides.get(3, "color"), Some("255 0 0"));
+    }
+}
@@ -4,12 +4,36 @@ use bevy::{
 };
 
 pub fn quake_point_to_bevy_point(point: Vec3, inverse_scale_factor: f32) -> Vec3 {
-    quake_direction_to_bevy_direction(point) / inverse_scale_factor
+    UpAxis::Z.convert_point(point, inverse_scale_factor)
 }
 
 pub fn quake_direction_to_bevy_direction(dir: Vec3) -> Vec3 {
-    let rot = Quat::from_axis_angle(Vec3::new(-1.0, 0.0, 0.0), 90.0_f32.to_radians());
-    rot * dir
+    UpAxis::Z.convert_direction(dir)
+}
+
+/// the up axis of the source map. Quake style maps are Z-up and get rotated into Bevy's Y-up space, maps that are
+/// already Y-up are only scaled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UpAxis {
+    #[default]
+    Z,
+    Y,
+}
+
+impl UpAxis {
+    pub fn convert_point(self, point: Vec3, inverse_scale_factor: f32) -> Vec3 {
+        self.convert_direction(point) / inverse_scale_factor
+    }
+
+    pub fn convert_direction(self, dir: Vec3) -> Vec3 {
+        match self {
+            UpAxis::Z => {
+                let rot = Quat::from_axis_angle(Vec3::new(-1.0, 0.0, 0.0), 90.0_f32.to_radians());
+                rot * dir
+            }
+            UpAxis::Y => dir,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -102,3 +126,21 @@ impl From<&MeshSurface> for Mesh {
         mesh
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn up_axis_conversion() {
+        let point = Vec3::new(16.0, 32.0, 48.0);
+
+        let z_up = UpAxis::Z.convert_point(point, 16.0);
+        assert!(z_up.abs_diff_eq(Vec3::new(1.0, 3.0, -2.0), 1e-5));
+        assert!(UpAxis::Z.convert_direction(Vec3::Z).abs_diff_eq(Vec3::Y, 1e-5));
+
+        let y_up = UpAxis::Y.convert_point(point, 16.0);
+        assert_eq!(y_up, Vec3::new(1.0, 2.0, 3.0));
+        assert_eq!(UpAxis::Y.convert_direction(Vec3::Y), Vec3::Y);
+    }
+}
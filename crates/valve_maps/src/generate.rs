@@ -3,18 +3,19 @@ use std::collections::HashMap;
 use bevy::prelude::{Vec2, Vec3};
 
 use crate::{
-    convert::MeshSurface,
+    atlas::{face_uvs_to_atlas, AtlasLayout, ATLAS_TEXTURE},
+    convert::{MeshSurface, UpAxis},
     formats::shared::{MapEntity, Plane},
 };
 
 use self::brush::BrushGeometry;
 
-pub fn entity_build(textures: &TextureInfo, entity: &MapEntity) -> Geometry {
+pub fn entity_build(textures: &TextureInfo, entity: &MapEntity, up_axis: UpAxis) -> Geometry {
     // Build brushes
     let brush_geometry: Vec<brush::BrushGeometry> = entity
         .brushes
         .iter()
-        .map(|brush| brush::build(textures, entity, brush))
+        .map(|brush| brush::build(textures, entity, brush, up_axis))
         .collect();
 
     Geometry::new(brush_geometry)
@@ -60,6 +61,47 @@ impl Geometry {
         Geometry { brush_geometry }
     }
 
+    /// axis aligned `(min, max)` bounds of every brush vertex in Bevy space. Point entities have no brushes and get
+    /// zero sized bounds at the origin.
+    pub fn bounds(&self) -> (Vec3, Vec3) {
+        let vertices = self
+            .brush_geometry
+            .iter()
+            .flat_map(|brush| brush.plane_geometry.iter())
+            .flat_map(|plane_geo| plane_geo.vertices.iter().map(|vertex| vertex.vertex));
+        points_bounds(vertices).unwrap_or_default()
+    }
+
+    /// moves the uvs of every textured face into the sub-rect of its texture in `atlas`, see `face_uvs_to_atlas`. The
+    /// faces moved into the atlas are renamed to `ATLAS_TEXTURE`, faces tiling their texture keep it.
+    pub fn pack_uvs(&mut self, atlas: &AtlasLayout) {
+        let planes = self
+            .brush_geometry
+            .iter_mut()
+            .flat_map(|brush| brush.plane_geometry.iter_mut());
+        for plane_geo in planes {
+            let Some(rect) = plane_geo.texture.as_deref().and_then(|texture| atlas.uv_rect(texture)) else {
+                continue;
+            };
+            let Some(mut uvs) = plane_geo
+                .vertices
+                .iter()
+                .map(|vertex| vertex.uv)
+                .collect::<Option<Vec<_>>>()
+            else {
+                continue;
+            };
+
+            if !face_uvs_to_atlas(&mut uvs, rect) {
+                continue;
+            }
+            for (vertex, uv) in plane_geo.vertices.iter_mut().zip(uvs) {
+                vertex.uv = Some(uv);
+            }
+            plane_geo.texture = Some(ATLAS_TEXTURE.to_string());
+        }
+    }
+
     pub fn get_collision_geometry(&self) -> Vec<ConvexCollision> {
         self.brush_geometry
             .iter()
@@ -87,6 +129,26 @@ impl Geometry {
             .collect()
     }
 
+    /// gathers the faces of every brush into a single triangle mesh. Used to run a convex decomposition on
+    /// entities that are concave as a whole (or authored with concave brushes).
+    pub fn get_concave_collision_geometry(&self) -> ConcaveCollision {
+        let mut vertices: Vec<Vec3> = Vec::new();
+        let mut indices: Vec<[u32; 3]> = Vec::new();
+
+        for plane_geo in self.brush_geometry.iter().flat_map(|brush| brush.plane_geometry.iter()) {
+            let index_offset = vertices.len() as u32;
+            vertices.extend(plane_geo.vertices.iter().map(|vertex| vertex.vertex));
+            indices.extend(
+                plane_geo
+                    .indices
+                    .chunks_exact(3)
+                    .map(|tri| [0, 1, 2].map(|i| tri[i] as u32 + index_offset)),
+            );
+        }
+
+        ConcaveCollision::new(vertices, indices)
+    }
+
     pub fn get_visual_geometry(&self) -> Vec<MeshSurface> {
         let textures: Vec<_> = self
             .brush_geometry
@@ -161,6 +223,14 @@ impl Geometry {
     }
 }
 
+/// axis aligned `(min, max)` bounds of `points`, `None` if there are none
+pub(crate) fn points_bounds(points: impl IntoIterator<Item = Vec3>) -> Option<(Vec3, Vec3)> {
+    points.into_iter().fold(None, |bounds, point| match bounds {
+        Some((min, max)) => Some((point.min(min), point.max(max))),
+        None => Some((point, point)),
+    })
+}
+
 mod texture_filter {
     pub fn unique<'a>(textures: &'a [String]) -> impl Fn(&(usize, String)) -> bool + 'a {
         move |(i, texture): &(usize, String)| textures.iter().skip(i + 1).find(|comp| *comp == texture).is_none()
@@ -197,8 +267,35 @@ impl ConvexCollision {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct ConcaveCollision {
+    pub vertices: Vec<Vec3>,
+    pub indices: Vec<[u32; 3]>,
+}
+
+impl ConcaveCollision {
+    pub fn new(vertices: Vec<Vec3>, indices: Vec<[u32; 3]>) -> ConcaveCollision {
+        ConcaveCollision { vertices, indices }
+    }
+
+    pub fn center(&self) -> Vec3 {
+        self.vertices
+            .iter()
+            .fold(Vec3::new(0.0, 0.0, 0.0), |acc, next| acc + *next)
+            / self.vertices.len().max(1) as f32
+    }
+
+    pub fn to_local(&self) -> Vec<Vec3> {
+        let origin = self.center();
+        self.vertices.iter().map(|vertex| *vertex - origin).collect()
+    }
+}
+
 pub mod brush {
-    use crate::formats::shared::{Brush, MapEntity};
+    use crate::{
+        convert::UpAxis,
+        formats::shared::{Brush, MapEntity},
+    };
 
     use super::{
         brush_plane::{self, PlaneGeometry},
@@ -285,11 +382,11 @@ pub mod brush {
         position.is_none() || position.unwrap() >= i
     }
 
-    pub fn build(textures: &TextureInfo, entity: &MapEntity, brush: &Brush) -> BrushGeometry {
+    pub fn build(textures: &TextureInfo, entity: &MapEntity, brush: &Brush, up_axis: UpAxis) -> BrushGeometry {
         let planes = &brush.planes;
         let plane_geometry: Vec<brush_plane::PlaneGeometry> = planes
             .iter()
-            .map(|plane| brush_plane::build(textures, entity, planes, plane))
+            .map(|plane| brush_plane::build(textures, entity, planes, plane, up_axis))
             .collect();
 
         BrushGeometry::new(plane_geometry)
@@ -302,7 +399,7 @@ pub mod brush_plane {
     use bevy::prelude::Vec3;
 
     use crate::{
-        convert::{quake_direction_to_bevy_direction, quake_point_to_bevy_point},
+        convert::UpAxis,
         formats::shared::{MapEntity, Plane},
     };
 
@@ -317,11 +414,16 @@ pub mod brush_plane {
     }
 
     impl PlaneGeometry {
-        pub fn new(mut vertices: Vec<Vertex>, indices: Vec<usize>, texture: Option<String>) -> PlaneGeometry {
+        pub fn new(
+            mut vertices: Vec<Vertex>,
+            indices: Vec<usize>,
+            texture: Option<String>,
+            up_axis: UpAxis,
+        ) -> PlaneGeometry {
             // root point where we convert all points to bevy space
             vertices.iter_mut().for_each(|v| {
-                v.vertex = quake_point_to_bevy_point(v.vertex, 16.0);
-                v.normal = quake_direction_to_bevy_direction(v.normal);
+                v.vertex = up_axis.convert_point(v.vertex, 16.0);
+                v.normal = up_axis.convert_direction(v.normal);
             });
 
             PlaneGeometry {
@@ -338,6 +440,7 @@ pub mod brush_plane {
         entity: &MapEntity,
         planes: &[Plane],
         plane: &Plane,
+        up_axis: UpAxis,
     ) -> PlaneGeometry {
         let texture_info = texture_info.get(&plane.texture.name);
 
@@ -427,7 +530,7 @@ pub mod brush_plane {
             None => None,
         };
 
-        PlaneGeometry::new(world_vertices, indices, texture)
+        PlaneGeometry::new(world_vertices, indices, texture, up_axis)
     }
 }
 
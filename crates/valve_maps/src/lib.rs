@@ -1,3 +1,4 @@
+pub mod atlas;
 pub mod bevy;
 pub mod convert;
 pub mod generate;
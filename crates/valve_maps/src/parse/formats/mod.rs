@@ -2,6 +2,7 @@ pub mod shared;
 pub mod valve;
 
 use crate::{
+    convert::UpAxis,
     generate::{entity_build, Geometry, TextureInfo},
     parse::{
         common::parse,
@@ -19,6 +20,8 @@ use crate::{
 
 pub use valve::Valve;
 
+use std::{collections::BTreeMap, fmt::Write};
+
 use self::shared::MapEntity;
 
 /// Representation of a Quake/Half-Life 1 map as a `Vec` of entities
@@ -28,6 +31,13 @@ pub struct Map {
 }
 
 impl Map {
+    /// the worldspawn entity carries the global map properties and is almost always the first entity
+    pub fn worldspawn(&self) -> Option<&MapEntity> {
+        self.entities
+            .iter()
+            .find(|e| e.fields.get_property("classname") == Some("worldspawn"))
+    }
+
     pub fn get_texture_names(&self) -> Vec<&String> {
         let mut textures: Vec<_> = self
             .entities
@@ -47,13 +57,38 @@ impl Map {
         textures
     }
 
+    /// human readable overview of the parsed map: entity counts per classname, brush count and the unique
+    /// texture names. Handy when debugging parser output.
+    pub fn summary(&self) -> String {
+        let mut classnames: BTreeMap<&str, usize> = BTreeMap::new();
+        for entity in &self.entities {
+            *classnames.entry(entity.classname().unwrap_or("<none>")).or_default() += 1;
+        }
+
+        let mut out = format!("entities: {}\n", self.entities.len());
+        for (classname, count) in classnames {
+            let _ = writeln!(out, "  {}: {}", classname, count);
+        }
+
+        let brushes: usize = self.entities.iter().map(|e| e.brushes.len()).sum();
+        let _ = writeln!(out, "brushes: {}", brushes);
+
+        let textures = self.get_texture_names();
+        let _ = write!(out, "textures ({}):", textures.len());
+        for texture in textures {
+            let _ = write!(out, " {}", texture);
+        }
+
+        out
+    }
+
     /// takes the raw, parsed map data and generates usable verts/uvs/normals/tangents using plane intersection
     /// and the Texture sizes
-    pub fn build_entity_geometry(&self, textures: &TextureInfo) -> Vec<Geometry> {
+    pub fn build_entity_geometry(&self, textures: &TextureInfo, up_axis: UpAxis) -> Vec<Geometry> {
         // Build geometry
         self.entities
             .iter()
-            .map(|entity| entity_build(&textures, entity))
+            .map(|entity| entity_build(&textures, entity, up_axis))
             .collect()
     }
 }
@@ -66,3 +101,42 @@ impl<'i> Parse<'i> for Map {
         )(input)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn summary_counts() {
+        let map = crate::parse(
+            r#"
+{
+"classname" "worldspawn"
+{
+( 0 0 0 ) ( 0 1 0 ) ( 1 0 0 ) stone [ 1 0 0 0 ] [ 0 -1 0 0 ] 0 1 1
+( 0 0 16 ) ( 1 0 16 ) ( 0 1 16 ) brick [ 1 0 0 0 ] [ 0 -1 0 0 ] 0 1 1
+}
+{
+( 0 0 0 ) ( 0 1 0 ) ( 1 0 0 ) stone [ 1 0 0 0 ] [ 0 -1 0 0 ] 0 1 1
+}
+}
+{
+"classname" "light"
+"origin" "0 0 32"
+}
+{
+"classname" "light"
+"origin" "0 0 64"
+}
+"#,
+        )
+        .unwrap();
+
+        let summary = map.summary();
+        assert!(summary.contains("entities: 3"));
+        assert!(summary.contains("light: 2"));
+        assert!(summary.contains("worldspawn: 1"));
+        assert!(summary.contains("brushes: 2"));
+        assert!(summary.contains("textures (2): brick stone"));
+
+        assert_eq!(map.entities[1].to_string(), r#"light { origin: "0 0 32" }"#);
+    }
+}
@@ -6,12 +6,13 @@ use {
     crate::parse::{
         common::{fields, many_fixed, parse, quoted_string},
         core::{
+            nom,
             nom::{
                 branch::alt,
                 bytes::{tag, take_till},
-                character::{char, line_ending, multispace1, not_line_ending},
+                character::{char, multispace1, not_line_ending},
                 combinator::{iterator, map, opt, recognize},
-                error::ParseError,
+                error::{ErrorKind, ParseError},
                 multi::{fold_many0, many0},
                 sequence::{delimited, pair, preceded, terminated},
             },
@@ -20,6 +21,7 @@ use {
     },
     std::{
         collections::HashMap,
+        fmt,
         ops::{Deref, DerefMut},
     },
 };
@@ -28,8 +30,10 @@ pub(crate) fn separator<'i, E>(input: Input<'i>) -> ParseResult<Input<'i>, E>
 where
     E: ParseError<Input<'i>> + Clone,
 {
+    // a comment runs up to the line ending, which is left for `multispace1` so a comment on the last line of a
+    // file without a trailing newline is still a separator
     recognize(|input| {
-        let mut iter = iterator(input, alt((multispace1, terminated(comment, line_ending))));
+        let mut iter = iterator(input, alt((multispace1, comment)));
         iter.for_each(drop);
         iter.finish()
     })(input)
@@ -78,11 +82,9 @@ impl Fields {
         None
     }
 
-    pub fn is_sensor(&self) -> bool {
-        if let Some(prop) = self.get("classname") {
-            return prop == "sensor";
-        }
-        false
+    /// entities with `"concave" "1"` get a convex decomposition collider instead of a hull per brush
+    pub fn is_concave(&self) -> bool {
+        self.get("concave").map_or(false, |prop| prop == "1")
     }
 }
 
@@ -124,11 +126,64 @@ where
 
 /// Representation of a map entity with [key/value pairs](Fields) and a list
 /// of [Brush](Brush)es, which may be empty if the entity in question is a
-/// point entity, like a light.
+/// point entity, like a light. Any [Patch](Patch) definitions are kept separately
+/// since they do not generate geometry.
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct MapEntity {
     pub fields: Fields,
     pub brushes: Vec<Brush>,
+    pub patches: Vec<Patch>,
+}
+
+impl MapEntity {
+    pub fn classname(&self) -> Option<&str> {
+        self.fields.get_property("classname")
+    }
+}
+
+/// compact single line form: the classname followed by the remaining key/value pairs sorted by key and the
+/// brush/patch counts when there are any
+impl fmt::Display for MapEntity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.classname().unwrap_or("<none>"))?;
+
+        let mut keys: Vec<_> = self.fields.keys().filter(|k| *k != "classname").collect();
+        keys.sort();
+        if !keys.is_empty() {
+            write!(f, " {{ ")?;
+            for (i, key) in keys.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}: {:?}", key, self.fields[*key])?;
+            }
+            write!(f, " }}")?;
+        }
+
+        if !self.brushes.is_empty() {
+            write!(f, " brushes: {}", self.brushes.len())?;
+        }
+        if !self.patches.is_empty() {
+            write!(f, " patches: {}", self.patches.len())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Either kind of block that can appear in an entity after its fields
+enum EntityBlock {
+    Brush(Brush),
+    Patch(Patch),
+}
+
+impl<'i, E> Parse<'i, E> for EntityBlock
+where
+    E: ParseError<Input<'i>> + Clone,
+{
+    fn parse(input: Input<'i>) -> ParseResult<Self, E> {
+        alt((map(parse, EntityBlock::Brush), map(parse, EntityBlock::Patch)))(input)
+    }
 }
 
 impl<'i, E> Parse<'i, E> for MapEntity
@@ -138,15 +193,109 @@ where
     fn parse(input: Input<'i>) -> ParseResult<Self, E> {
         delimited(
             pair(char('{'), opt(separator)),
-            fields!(
-                MapEntity: fields = maybe_sep_terminated(parse),
-                brushes = many0(maybe_sep_terminated(parse))
+            map(
+                pair(maybe_sep_terminated(parse), many0(maybe_sep_terminated(parse))),
+                |(fields, blocks): (Fields, Vec<EntityBlock>)| {
+                    let mut entity = MapEntity {
+                        fields,
+                        ..Default::default()
+                    };
+                    for block in blocks {
+                        match block {
+                            EntityBlock::Brush(brush) => entity.brushes.push(brush),
+                            EntityBlock::Patch(patch) => entity.patches.push(patch),
+                        }
+                    }
+                    entity
+                },
             ),
             char('}'),
         )(input)
     }
 }
 
+/// Opaque representation of a bezier patch (`patchDef2`/`patchDef3`) block. These are written by some editors
+/// but are not turned into geometry. The raw body is stored so maps containing them still parse and the data is
+/// available for later use. In a map file, they usually look something like this:
+/// ```plain
+/// {
+/// patchDef2
+/// {
+/// common/caulk
+/// ( 3 3 0 0 0 )
+/// (
+/// ( ( -64 -64 0 0 0 ) ( -64 0 0 0 0.5 ) ( -64 64 0 0 1 ) )
+/// ...
+/// )
+/// }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Patch {
+    /// the patch keyword, either `patchDef2` or `patchDef3`
+    pub kind: String,
+    /// everything between the braces following the keyword, untouched
+    pub body: String,
+}
+
+impl<'i, E> Parse<'i, E> for Patch
+where
+    E: ParseError<Input<'i>> + Clone,
+{
+    fn parse(input: Input<'i>) -> ParseResult<Self, E> {
+        map(
+            delimited(
+                pair(char('{'), opt(separator)),
+                pair(
+                    maybe_sep_terminated(alt((tag("patchDef2"), tag("patchDef3")))),
+                    balanced_braces,
+                ),
+                pair(opt(separator), char('}')),
+            ),
+            |(kind, body): (Input<'i>, Input<'i>)| Patch {
+                kind: kind.into(),
+                body: body.trim().into(),
+            },
+        )(input)
+    }
+}
+
+/// consumes a `{ ... }` block including any nested blocks, returning the contents between the outer braces.
+/// Only braces standing alone as a token count, so texture names such as `{fence` don't unbalance the block
+fn balanced_braces<'i, E>(input: Input<'i>) -> ParseResult<Input<'i>, E>
+where
+    E: ParseError<Input<'i>>,
+{
+    if !input.starts_with('{') {
+        return Err(nom::Err::Error(E::from_error_kind(input, ErrorKind::Char)));
+    }
+
+    let is_token = |i: usize| {
+        let before = input[..i].chars().next_back().is_none_or(char::is_whitespace);
+        let after = input[i + 1..].chars().next().is_none_or(char::is_whitespace);
+        before && after
+    };
+
+    let mut depth = 0;
+    for (i, c) in input.char_indices() {
+        if !is_token(i) {
+            continue;
+        }
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok((&input[i + 1..], &input[1..i]));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Err(nom::Err::Error(E::from_error_kind(input, ErrorKind::TakeUntil)))
+}
+
 /// Representation of a plane with three points describing a half-space and a texture. In a map file, it usually looks
 /// something like this with the valve format:
 /// ```plain
@@ -237,3 +386,97 @@ where
 {
     preceded(tag("//"), not_line_ending)(input)
 }
+
+#[cfg(test)]
+mod tests {
+    const PATCH_MAP: &str = r#"
+// entity 0
+{
+"classname" "worldspawn"
+{
+( 0 0 0 ) ( 0 1 0 ) ( 1 0 0 ) stone [ 1 0 0 0 ] [ 0 -1 0 0 ] 0 1 1
+( 0 0 16 ) ( 1 0 16 ) ( 0 1 16 ) stone [ 1 0 0 0 ] [ 0 -1 0 0 ] 0 1 1
+}
+{
+patchDef2
+{
+common/caulk
+( 3 3 0 0 0 )
+(
+( ( -64 -64 0 0 0 ) ( -64 0 0 0 0.5 ) ( -64 64 0 0 1 ) )
+( ( 0 -64 0 0.5 0 ) ( 0 0 0 0.5 0.5 ) ( 0 64 0 0.5 1 ) )
+( ( 64 -64 0 1 0 ) ( 64 0 0 1 0.5 ) ( 64 64 0 1 1 ) )
+)
+}
+}
+}
+// entity 1
+{
+"classname" "light"
+"origin" "0 0 32"
+}
+"#;
+
+    #[test]
+    fn parses_map_with_comments() {
+        let map = "// Game: Quake
+// Format: Valve
+// entity 0
+{ // worldspawn
+\"classname\" \"worldspawn\" // trailing comment
+// between fields
+\"wad\" // between key and value
+\"base.wad\"
+// brush 0
+{
+// plane comment
+( 0 0 0 ) ( 0 1 0 ) ( 1 0 0 ) stone [ 1 0 0 0 ] [ 0 -1 0 0 ] 0 1 1 // after a plane
+( 0 0 16 ) ( 1 0 16 ) ( 0 1 16 ) stone [ 1 0 0 0 ] [ 0 -1 0 0 ] 0 1 1
+//no space after slashes
+}
+}
+// entity 1
+{
+\"classname\" \"light\"
+}
+// comment at the end of the file without a newline";
+
+        for map in [map.to_string(), map.replace('\n', "\r\n")] {
+            let map = crate::parse(&map).unwrap();
+            assert_eq!(map.entities.len(), 2);
+            assert_eq!(map.entities[0].fields.get_property("wad"), Some("base.wad"));
+            assert_eq!(map.entities[0].brushes[0].planes.len(), 2);
+            assert_eq!(map.entities[1].classname(), Some("light"));
+        }
+    }
+
+    #[test]
+    fn parses_map_with_patch() {
+        let map = crate::parse(PATCH_MAP).unwrap();
+        assert_eq!(map.entities.len(), 2);
+
+        let worldspawn = &map.entities[0];
+        assert_eq!(worldspawn.brushes.len(), 1);
+        assert_eq!(worldspawn.brushes[0].planes.len(), 2);
+        assert_eq!(worldspawn.patches.len(), 1);
+        assert_eq!(worldspawn.patches[0].kind, "patchDef2");
+        assert!(worldspawn.patches[0].body.starts_with("common/caulk"));
+
+        assert_eq!(map.entities[1].fields.get_property("classname"), Some("light"));
+    }
+
+    #[test]
+    fn parses_patch_with_brace_prefixed_texture() {
+        let map = PATCH_MAP.replace("common/caulk", "{fence");
+        let map = crate::parse(&map).unwrap();
+        assert_eq!(map.entities.len(), 2);
+
+        let worldspawn = &map.entities[0];
+        assert_eq!(worldspawn.brushes.len(), 1);
+        assert_eq!(worldspawn.patches.len(), 1);
+        assert!(worldspawn.patches[0].body.starts_with("{fence"));
+        assert!(worldspawn.patches[0].body.ends_with(')'));
+
+        assert_eq!(map.entities[1].fields.get_property("classname"), Some("light"));
+    }
+}